@@ -1,5 +1,8 @@
 use std::env;
-use tune::layout::IsomorphicLayout;
+use tune::{
+    key::{HexKey, HexMapping},
+    layout::IsomorphicLayout,
+};
 
 fn main() {
     let mut args = env::args();
@@ -29,7 +32,7 @@ pub fn print_hex_keyboard(num_steps_per_octave: u16) {
         );
         println!();
 
-        let mos = layout.mos().coprime();
+        let mapping = HexMapping::new(layout.mos().coprime(), HexKey::from_coords(0, 0));
 
         for y in -10i16..=10 {
             let div = y.div_euclid(2);
@@ -40,8 +43,9 @@ pub fn print_hex_keyboard(num_steps_per_octave: u16) {
             for x in 0..20 {
                 print!(
                     "{:>4}",
-                    mos.get_key(x - div, y)
-                        .rem_euclid(i32::from(num_steps_per_octave)),
+                    mapping
+                        .degree_of(HexKey::from_coords(x - div, y))
+                        .rem_euclid(i64::from(num_steps_per_octave)),
                 );
             }
             println!();
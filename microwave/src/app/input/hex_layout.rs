@@ -1,12 +1,13 @@
 use bevy::prelude::*;
+use tune::key::HexKey;
 
 use crate::PhysicalKeyboardLayout;
 
 pub fn location_of_key(
     physical_layout: &PhysicalKeyboardLayout,
     key_code: KeyCode,
-) -> Option<(i8, i8)> {
-    Some(match (key_code, physical_layout) {
+) -> Option<HexKey> {
+    let (x, y) = match (key_code, physical_layout) {
         (KeyCode::Backquote, _) => (-6, -1),
         (KeyCode::Digit1, _) => (-5, -1),
         (KeyCode::Digit2, _) => (-4, -1),
@@ -73,5 +74,6 @@ pub fn location_of_key(
         (KeyCode::Slash, _) => (4, 2),
         (KeyCode::ShiftRight, _) => (5, 2),
         _ => return None,
-    })
+    };
+    Some(HexKey::from_coords(x, y))
 }
@@ -114,15 +114,15 @@ fn handle_scan_code_event(
         return;
     }
 
-    if let Some(key_coord) = hex_layout::location_of_key(physical_layout, key_code) {
-        let (x, y) = key_coord;
-        let degree = virtual_keyboard.get_key(x.into(), y.into());
-
+    if let Some(key) = hex_layout::location_of_key(physical_layout, key_code) {
         let event = match button_state {
-            ButtonState::Pressed => {
-                Event::Pressed(SourceId::Keyboard(x, y), Location::Degree(degree), 100)
-            }
-            ButtonState::Released => Event::Released(SourceId::Keyboard(x, y), 100),
+            ButtonState::Pressed => match virtual_keyboard.get_key(key) {
+                Some(degree) => {
+                    Event::Pressed(SourceId::Keyboard(key), Location::Degree(degree), 100)
+                }
+                None => return,
+            },
+            ButtonState::Released => Event::Released(SourceId::Keyboard(key), 100),
         };
 
         engine.handle_event(event)
@@ -189,6 +189,10 @@ fn handle_key_event(
                             virtual_keyboard.layout.toggle_next();
                             true
                         }
+                        "N" => {
+                            virtual_keyboard.notation.toggle_next();
+                            false
+                        }
                         "S" => {
                             virtual_keyboard.scale.toggle_next();
                             true
@@ -5,7 +5,8 @@ use std::{
 
 use bevy::prelude::*;
 use tune::{
-    layout::{IsomorphicLayout, Layer},
+    key::{HexKey, HexMapping},
+    layout::{Genchain, IsomorphicLayout, Layer, NoteNotation},
     pergen::Mos,
     pitch::Ratio,
     scala::Scl,
@@ -21,6 +22,7 @@ pub struct VirtualKeyboardResource {
     pub compression: Toggle<Compression>,
     pub tilt: Toggle<Tilt>,
     pub inclination: Toggle<Inclination>,
+    pub notation: Toggle<NoteNotation>,
     pub avg_step_size: Ratio,
 }
 
@@ -50,6 +52,7 @@ impl Display for OnScreenKeyboards {
 pub struct VirtualKeyboardScale {
     layout: Arc<VirtualKeyboardLayout>,
     colors: Vec<Color>,
+    isomorphic_layout: Option<IsomorphicLayout>,
 }
 
 pub struct VirtualKeyboardLayout {
@@ -82,6 +85,7 @@ impl VirtualKeyboardResource {
     pub fn new(
         scl: &Scl,
         options: CustomKeyboardOptions,
+        genchains: &[Genchain],
         palette: &ColorPalette,
     ) -> VirtualKeyboardResource {
         let on_screen_keyboards = vec![
@@ -93,17 +97,39 @@ impl VirtualKeyboardResource {
             OnScreenKeyboards::None,
         ];
 
-        let avg_step_size = if scl.period().is_negligible() {
+        let period = if scl.period().is_negligible() {
             Ratio::from_octaves(1)
         } else {
             scl.period()
-        }
-        .divided_into_equal_steps(scl.num_items());
+        };
+        let avg_step_size = period.divided_into_equal_steps(scl.num_items());
+
+        let isomorphic_layouts = match options.generator.map(|generator| {
+            (
+                generator,
+                IsomorphicLayout::find_by_generator(period, generator, scl.num_items(), genchains),
+            )
+        }) {
+            Some((_, Some(layouts))) if !layouts.is_empty() => layouts,
+            Some((generator, layouts)) => {
+                match layouts {
+                    None => log::warn!(
+                        "Generator {generator:#} does not form a MOS with {} notes. Falling back to the layouts of the average step size.",
+                        scl.num_items()
+                    ),
+                    Some(_) => log::warn!(
+                        "No genchain matches generator {generator:#}. Falling back to the layouts of the average step size."
+                    ),
+                }
+                IsomorphicLayout::find_by_step_size_with(avg_step_size, genchains)
+            }
+            None => IsomorphicLayout::find_by_step_size_with(avg_step_size, genchains),
+        };
 
         let mut scales = Vec::new();
         let mut layouts = vec![None];
 
-        IsomorphicLayout::find_by_step_size(avg_step_size)
+        isomorphic_layouts
             .into_iter()
             .map(|isomorphic_layout| {
                 let scale_name = format!(
@@ -125,6 +151,7 @@ impl VirtualKeyboardResource {
                         orig_mos: mos,
                     },
                     generate_colors(&isomorphic_layout, palette),
+                    Some(isomorphic_layout),
                 )
             })
             .chain({
@@ -143,14 +170,16 @@ impl VirtualKeyboardResource {
                         orig_mos: mos,
                     },
                     options.colors.0,
+                    None,
                 )]
             })
-            .for_each(|(layout, colors)| {
+            .for_each(|(layout, colors, isomorphic_layout)| {
                 let layout = Arc::new(layout);
 
                 scales.push(VirtualKeyboardScale {
                     layout: layout.clone(),
                     colors,
+                    isomorphic_layout,
                 });
                 layouts.push(Some(layout));
             });
@@ -165,6 +194,12 @@ impl VirtualKeyboardResource {
 
         let inclinations = vec![Inclination::Lumatone, Inclination::None];
 
+        let notations = vec![
+            NoteNotation::Accidentals,
+            NoteNotation::UpsAndDowns,
+            NoteNotation::Heji { odd_limit: 11 },
+        ];
+
         VirtualKeyboardResource {
             on_screen_keyboard: on_screen_keyboards.into(),
             scale: scales.into(),
@@ -172,6 +207,7 @@ impl VirtualKeyboardResource {
             compression: compressions.into(),
             tilt: tilts.into(),
             inclination: inclinations.into(),
+            notation: notations.into(),
             avg_step_size,
         }
     }
@@ -184,6 +220,18 @@ impl VirtualKeyboardResource {
         &self.scale.curr_option().colors
     }
 
+    pub fn note_names(&self) -> Option<String> {
+        let isomorphic_layout = self.scale.curr_option().isomorphic_layout.as_ref()?;
+        let notation = *self.notation.curr_option();
+
+        Some(
+            (0..isomorphic_layout.pergen().period())
+                .map(|index| isomorphic_layout.get_note_name_with(index, notation))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    }
+
     pub fn scale_step_sizes(&self) -> (u16, u16, i32) {
         let mos = &self.scale.curr_option().layout.orig_mos;
         (mos.primary_step(), mos.secondary_step(), mos.sharpness())
@@ -235,16 +283,15 @@ impl VirtualKeyboardResource {
             .unwrap_or_else(|| &self.scale.curr_option().layout)
     }
 
-    pub fn get_key(&self, num_primary_steps: i16, num_secondary_steps: i16) -> i32 {
-        let num_primary_steps = match self.compression.curr_option() {
-            Compression::None => num_primary_steps,
-            Compression::Compressed => num_primary_steps + num_secondary_steps,
-            Compression::Expanded => num_primary_steps - num_secondary_steps,
+    pub fn get_key(&self, key: HexKey) -> Option<i32> {
+        let key = match self.compression.curr_option() {
+            Compression::None => key,
+            Compression::Compressed => key.plus_steps(key.y(), 0)?,
+            Compression::Expanded => key.plus_steps(key.y().checked_neg()?, 0)?,
         };
 
-        self.curr_layout()
-            .mos
-            .get_key(num_primary_steps, num_secondary_steps)
+        let mapping = HexMapping::new(self.curr_layout().mos, HexKey::from_coords(0, 0));
+        i32::try_from(mapping.degree_of(key)).ok()
     }
 
     pub fn inclination(&self) -> f32 {
@@ -652,6 +652,8 @@ fn create_hud_text(
                  [Alt+C] Compression: {:?}\n\
                  [Alt+T] Tilt: {:?}\n\
                  [Alt+I] Inclination: {:?}\n\
+                 [Alt+N] Notation: {}\n\
+                 Note names: {}\n\
                  \n\
                  [Esc] Back",
                 scale_steps.0,
@@ -666,6 +668,11 @@ fn create_hud_text(
                 virtual_keyboard.compression.curr_option(),
                 virtual_keyboard.tilt.curr_option(),
                 virtual_keyboard.inclination.curr_option(),
+                virtual_keyboard.notation.curr_option(),
+                virtual_keyboard
+                    .note_names()
+                    .as_deref()
+                    .unwrap_or("Not available"),
             )
             .unwrap();
         }
@@ -758,6 +765,7 @@ impl BackendInfo for MidiOutInfo {
             Some(TuningMethod::Octave1Rt) => "Scale/Octave Tuning (1-Byte) (realtime)",
             Some(TuningMethod::Octave2) => "Scale/Octave Tuning (2-Byte)",
             Some(TuningMethod::Octave2Rt) => "Scale/Octave Tuning (2-Byte) (realtime)",
+            Some(TuningMethod::Bulk) => "Bulk Tuning Dump",
             Some(TuningMethod::ChannelFineTuning) => "Channel Fine Tuning",
             Some(TuningMethod::PitchBend) => "Pitch Bend",
             None => "None. Tuning channels exceeded! Change tuning mode.",
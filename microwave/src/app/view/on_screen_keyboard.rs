@@ -5,6 +5,7 @@ use std::{
 
 use bevy::{ecs::system::EntityCommands, prelude::*};
 use tune::{
+    key::HexKey,
     pitch::{Pitch, Ratio},
     scala::{KbmRoot, Scl},
     tuning::Scale,
@@ -238,7 +239,10 @@ impl KeyboardCreator<'_, '_, '_> {
                     continue;
                 }
 
-                let key_degree = virtual_keyboard.get_key(p, s) - tuning.1.root_offset;
+                let Some(key_degree) = virtual_keyboard.get_key(HexKey::from_coords(p, s)) else {
+                    continue;
+                };
+                let key_degree = key_degree - tuning.1.root_offset;
                 let key_color = get_key_color(key_degree);
 
                 let transform = Transform::from_translation(translation)
@@ -4,7 +4,7 @@ use async_std::task;
 use bevy::render::color::Color;
 use flume::Sender;
 use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
-use tune::math;
+use tune::{key::HexKey, math};
 use tune_cli::shared::midi::{self, MidiResult};
 
 use crate::{app::VirtualKeyboardResource, portable};
@@ -93,7 +93,9 @@ impl LumatoneLayout {
 
     pub fn from_virtual_keyboard(virtual_keyboard: &VirtualKeyboardResource) -> LumatoneLayout {
         Self::from_fn(|p, s| {
-            let degree = virtual_keyboard.get_key(p, s);
+            let Some(degree) = virtual_keyboard.get_key(HexKey::from_coords(p, s)) else {
+                return (None, Color::BLACK);
+            };
             let colors = &virtual_keyboard.colors();
 
             let channel = u8::try_from(degree.div_euclid(128) + 8);
@@ -39,6 +39,7 @@ use tune_cli::{
     shared::{
         self,
         error::ResultExt,
+        est,
         midi::MidiInArgs,
         scala::{KbmOptions, SclCommand},
     },
@@ -264,6 +265,17 @@ struct CustomKeyboardOptions {
     #[arg(long = "s-steps", default_value = "0", value_parser = u16::value_parser().range(0..100))]
     num_secondary_steps: u16,
 
+    /// Generator of the tuning if the scale is a rank-2 MOS, e.g. 696.6c for 1/4-comma meantone.
+    /// The isomorphic layouts are then derived from the generator instead of the average step size.
+    /// If the generator does not form a MOS, a warning is logged and the average step size is used
+    #[arg(long = "generator")]
+    generator: Option<Ratio>,
+
+    /// YAML file with additional genchains to derive isomorphic layouts from, in the same format as for `tune est --genchains`.
+    /// Can be specified multiple times
+    #[arg(long = "genchains")]
+    genchain_files: Vec<PathBuf>,
+
     /// Color schema of the custom isometric layout (on-screen keyboard, e.g. wgrwwgrwgrwgrwwgr for 17-EDO)
     #[arg(long = "colors", default_value = "wrgbkcmy")]
     colors: KeyColors,
@@ -401,8 +413,17 @@ impl RunOptions {
 
         let profile = MicrowaveProfile::load(&self.profile_location).await?;
 
-        let virtual_keyboard =
-            VirtualKeyboardResource::new(&scl, self.custom_keyboard, &profile.color_palette);
+        let mut genchains = Vec::new();
+        for file_name in &self.custom_keyboard.genchain_files {
+            genchains.extend(est::import_genchains(file_name)?);
+        }
+
+        let virtual_keyboard = VirtualKeyboardResource::new(
+            &scl,
+            self.custom_keyboard,
+            &genchains,
+            &profile.color_palette,
+        );
 
         let mut factory = AutomationFactory::new(HashMap::new());
 
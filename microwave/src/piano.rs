@@ -6,7 +6,7 @@ use std::{
 
 use flume::Sender;
 use tune::{
-    key::PianoKey,
+    key::{HexKey, PianoKey},
     midi::ChannelMessageType,
     pitch::Pitch,
     scala::{Kbm, Scl},
@@ -405,7 +405,7 @@ pub enum Location {
 pub enum SourceId {
     Mouse,
     Touchpad(u64),
-    Keyboard(i8, i8),
+    Keyboard(HexKey),
     Midi(PianoKey),
 }
 
@@ -0,0 +1,203 @@
+//! Consonance metrics for intervals and chords.
+
+use crate::{
+    ji::Fraction,
+    pitch::{Pitch, Ratio},
+};
+
+/// Calculates the Benedetti height numer*denom of the given [`Fraction`].
+///
+/// # Examples
+///
+/// ```
+/// # use tune::consonance;
+/// # use tune::ji::Fraction;
+/// assert_eq!(consonance::benedetti_height(Fraction::new(1, 1)), 1);
+/// assert_eq!(consonance::benedetti_height(Fraction::new(3, 2)), 6);
+/// assert_eq!(consonance::benedetti_height(Fraction::new(81, 80)), 6480);
+/// ```
+pub fn benedetti_height(fraction: Fraction) -> u128 {
+    u128::from(fraction.numer) * u128::from(fraction.denom)
+}
+
+/// Calculates Euler's gradus suavitatis 1 + Σ k·(p-1) where p^k are the prime powers of numer*denom.
+///
+/// [`None`] is returned if the result does not fit into a [`u64`].
+///
+/// # Examples
+///
+/// ```
+/// # use tune::consonance;
+/// # use tune::ji::Fraction;
+/// assert_eq!(consonance::euler_gradus(Fraction::new(1, 1)), Some(1));
+/// assert_eq!(consonance::euler_gradus(Fraction::new(2, 1)), Some(2));
+/// assert_eq!(consonance::euler_gradus(Fraction::new(3, 2)), Some(4));
+/// assert_eq!(consonance::euler_gradus(Fraction::new(5, 4)), Some(7));
+/// assert_eq!(consonance::euler_gradus(Fraction::new(16, 15)), Some(11));
+///
+/// // 2^63 and 2^63 + 1 = 3^3·19·43·5419·77158673929
+/// assert_eq!(consonance::euler_gradus(Fraction::new(1 << 63, 1)), Some(64));
+/// assert_eq!(
+///     consonance::euler_gradus(Fraction::new((1 << 63) + 1, 1)),
+///     Some(77158679413)
+/// );
+/// ```
+pub fn euler_gradus(fraction: Fraction) -> Option<u64> {
+    gradus_contribution(fraction.numer)?
+        .checked_add(gradus_contribution(fraction.denom)?)?
+        .checked_add(1)
+}
+
+fn gradus_contribution(mut int_value: u64) -> Option<u64> {
+    let mut contribution = 0u64;
+    let mut divisor = 2;
+    while divisor <= int_value / divisor {
+        while int_value % divisor == 0 {
+            int_value /= divisor;
+            contribution = contribution.checked_add(divisor - 1)?;
+        }
+        divisor += 1;
+    }
+    if int_value > 1 {
+        contribution = contribution.checked_add(int_value - 1)?;
+    }
+    Some(contribution)
+}
+
+/// Harmonic entropy model based on all fractions n/d with n*d not exceeding a given limit.
+///
+/// Fractions below and above 1/1 are modeled alike s.t. intervals close to the unison are not biased toward the upper side.
+/// The probability of hearing a fraction is modeled by a Gaussian distribution with the given `spread` around the heard interval, weighted by 1/sqrt(n*d).
+/// The harmonic entropy is the Shannon entropy (in nats) of the resulting distribution. Low values indicate consonance.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::consonance::HarmonicEntropy;
+/// # use tune::pitch::Ratio;
+/// let harmonic_entropy = HarmonicEntropy::new(Ratio::from_cents(17.0), 10000).unwrap();
+///
+/// let entropy = |cents| harmonic_entropy.of_ratio(Ratio::from_cents(cents));
+///
+/// // Just intervals are local minima
+/// assert!(entropy(701.955) < entropy(680.0));
+/// assert!(entropy(701.955) < entropy(720.0));
+///
+/// // Simple intervals are more consonant than complex ones
+/// assert!(entropy(701.955) < entropy(386.314));
+/// assert!(entropy(386.314) < entropy(551.318));
+///
+/// // The curve is symmetric
+/// assert_approx_eq!(entropy(-701.955), entropy(701.955));
+/// assert_approx_eq!(entropy(-10.0), entropy(10.0));
+///
+/// // The unison is the most consonant interval
+/// assert!(entropy(0.0) < entropy(10.0));
+///
+/// // A spread of zero is rejected
+/// assert!(HarmonicEntropy::new(Ratio::default(), 10000).is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct HarmonicEntropy {
+    spread_in_cents: f64,
+    sorted_fractions: Vec<(f64, f64)>,
+}
+
+impl HarmonicEntropy {
+    /// Creates a [`HarmonicEntropy`] model with the given `spread` (standard deviation) and N limit.
+    ///
+    /// [`None`] is returned if `spread` is zero or not finite.
+    pub fn new(spread: Ratio, n_limit: u64) -> Option<Self> {
+        let spread_in_cents = spread.as_cents().abs();
+        if spread_in_cents == 0.0 || !spread_in_cents.is_finite() {
+            return None;
+        }
+
+        let mut sorted_fractions = Vec::new();
+
+        for denom in (1..).take_while(|denom| denom * denom <= n_limit) {
+            for numer in (denom..).take_while(|numer| numer * denom <= n_limit) {
+                let fraction = Fraction::new(numer, denom);
+                if fraction.denom == denom {
+                    let cents = fraction.as_ratio().as_cents();
+                    let weight = 1.0 / ((numer * denom) as f64).sqrt();
+                    sorted_fractions.push((cents, weight));
+                    if numer != denom {
+                        sorted_fractions.push((-cents, weight));
+                    }
+                }
+            }
+        }
+        sorted_fractions.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Some(Self {
+            spread_in_cents,
+            sorted_fractions,
+        })
+    }
+
+    /// Calculates the harmonic entropy of the given interval.
+    pub fn of_ratio(&self, ratio: Ratio) -> f64 {
+        let cents = ratio.as_cents();
+        let window = 6.0 * self.spread_in_cents;
+
+        let first_index = self
+            .sorted_fractions
+            .partition_point(|&(fraction_cents, _)| fraction_cents < cents - window);
+
+        let probabilities = Vec::from_iter(
+            self.sorted_fractions[first_index..]
+                .iter()
+                .take_while(|&&(fraction_cents, _)| fraction_cents <= cents + window)
+                .map(|&(fraction_cents, weight)| {
+                    let distance = (cents - fraction_cents) / self.spread_in_cents;
+                    weight * (-0.5 * distance * distance).exp()
+                }),
+        );
+
+        let total: f64 = probabilities.iter().sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+
+        probabilities
+            .iter()
+            .map(|&probability| probability / total)
+            .filter(|&probability| probability > 0.0)
+            .map(|probability| -probability * probability.ln())
+            .sum()
+    }
+
+    /// Calculates the harmonic entropy of a chord as the mean harmonic entropy of all contained intervals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::consonance::HarmonicEntropy;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Ratio;
+    /// let harmonic_entropy = HarmonicEntropy::new(Ratio::from_cents(17.0), 10000).unwrap();
+    ///
+    /// let major_triad = [200.0, 250.0, 300.0].map(Pitch::from_hz);
+    /// let augmented_triad = [200.0, 252.0, 317.5].map(Pitch::from_hz);
+    ///
+    /// assert!(harmonic_entropy.of_chord(&major_triad) < harmonic_entropy.of_chord(&augmented_triad));
+    /// assert_eq!(harmonic_entropy.of_chord(&major_triad[..1]), 0.0);
+    /// ```
+    pub fn of_chord(&self, pitches: &[Pitch]) -> f64 {
+        let mut total = 0.0;
+        let mut num_intervals = 0;
+        for (index, &lower) in pitches.iter().enumerate() {
+            for &upper in &pitches[index + 1..] {
+                total += self.of_ratio(Ratio::between_pitches(lower, upper));
+                num_intervals += 1;
+            }
+        }
+        if num_intervals == 0 {
+            0.0
+        } else {
+            total / f64::from(num_intervals)
+        }
+    }
+}
@@ -0,0 +1,973 @@
+//! Exact representation of just intervals.
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+    ops::{Div, Mul},
+};
+
+use crate::{math, pitch::Ratio, scala::PitchValue, temperament::Comma, tuning::Approximation};
+
+/// An exact rational interval stored as a sequence of prime exponents (a "monzo") over the primes [2, 3, 5, 7, ...].
+///
+/// In contrast to the float-based [`Ratio`], [`JiRatio`]s can be stacked, inverted and reduced without any rounding errors.
+/// The supported prime factors are limited to [`math::U8_PRIMES`].
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::ji::JiRatio;
+/// let fifth = JiRatio::from_fraction(3, 2).unwrap();
+/// let major_third = JiRatio::from_fraction(5, 4).unwrap();
+///
+/// let four_fifths = fifth.pow(4).unwrap();
+/// assert_eq!(four_fifths.as_fraction(), Some((81, 16)));
+///
+/// let syntonic_comma = four_fifths.octave_reduced() / major_third;
+/// assert_eq!(syntonic_comma.as_fraction(), Some((81, 80)));
+/// assert_eq!(syntonic_comma.prime_factors(), &[-4, 4, -1]);
+/// assert_approx_eq!(syntonic_comma.as_ratio().as_cents(), 21.506290);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct JiRatio {
+    prime_factors: Vec<i32>,
+}
+
+impl JiRatio {
+    /// Creates a [`JiRatio`] equal to 1/1.
+    pub fn unison() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`JiRatio`] equal to 2/1.
+    pub fn octave() -> Self {
+        Self::from_prime_factors([1]).unwrap()
+    }
+
+    /// Creates a [`JiRatio`] from the given prime factor decomposition.
+    ///
+    /// [`None`] is returned if the provided list is longer than [`math::U8_PRIMES`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// let septimal_kleisma = JiRatio::from_prime_factors([-5, -2, 2, 1, 0]).unwrap();
+    /// assert_eq!(septimal_kleisma.as_fraction(), Some((175, 288)));
+    ///
+    /// // Trailing zeros are not significant
+    /// assert_eq!(septimal_kleisma.prime_factors(), &[-5, -2, 2, 1]);
+    ///
+    /// assert!(JiRatio::from_prime_factors(vec![1; 55]).is_none());
+    /// ```
+    pub fn from_prime_factors(prime_factors: impl Into<Vec<i32>>) -> Option<Self> {
+        let prime_factors = prime_factors.into();
+        (prime_factors.len() <= math::U8_PRIMES.len()).then(|| Self::normalized(prime_factors))
+    }
+
+    /// Creates a [`JiRatio`] from the given `numer` and `denom`.
+    ///
+    /// [`None`] is returned if either value is 0 or contains a prime factor larger than the largest of [`math::U8_PRIMES`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// let minor_seventh = JiRatio::from_fraction(14, 8).unwrap();
+    /// assert_eq!(minor_seventh.as_fraction(), Some((7, 4)));
+    /// assert_eq!(minor_seventh.prime_factors(), &[-2, 0, 0, 1]);
+    ///
+    /// assert!(JiRatio::from_fraction(0, 1).is_none());
+    /// assert!(JiRatio::from_fraction(257, 256).is_none());
+    /// ```
+    pub fn from_fraction(numer: u128, denom: u128) -> Option<Self> {
+        let numer = factorize(numer)?;
+        let denom = factorize(denom)?;
+        Some(Self::normalized(
+            numer.iter().zip(&denom).map(|(n, d)| n - d).collect(),
+        ))
+    }
+
+    /// Creates a [`JiRatio`] from the given integer value.
+    pub fn from_int(int_value: u128) -> Option<Self> {
+        Self::from_fraction(int_value, 1)
+    }
+
+    /// Recovers the exact [`JiRatio`] of a float-based [`Ratio`].
+    ///
+    /// [`None`] is returned if `ratio` is not equivalent to a fraction with the given `odd_limit`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// # use tune::pitch::Ratio;
+    /// let ji_ratio = JiRatio::from_ratio(Ratio::from_float(7.0 / 12.0), 11).unwrap();
+    /// assert_eq!(ji_ratio.as_fraction(), Some((7, 12)));
+    ///
+    /// // The round trip is lossless
+    /// let ratio = ji_ratio.as_ratio();
+    /// assert_eq!(JiRatio::from_ratio(ratio, 11), Some(ji_ratio));
+    ///
+    /// // Not a fraction with odd limit 5
+    /// assert!(JiRatio::from_ratio(ratio, 5).is_none());
+    /// ```
+    pub fn from_ratio(ratio: Ratio, odd_limit: u16) -> Option<Self> {
+        let nearest_fraction = ratio.nearest_fraction(odd_limit);
+        if !nearest_fraction.deviation.is_negligible() {
+            return None;
+        }
+
+        let fraction = Self::from_fraction(
+            u128::from(nearest_fraction.numer),
+            u128::from(nearest_fraction.denom),
+        )?;
+        Some(fraction * Self::octave().pow(nearest_fraction.num_octaves)?)
+    }
+
+    /// Converts a Scala [`PitchValue`] into a [`JiRatio`].
+    ///
+    /// [`None`] is returned if `pitch_value` is given in cents or cannot be represented by a [`JiRatio`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// # use tune::scala::PitchValue;
+    /// let ji_ratio = JiRatio::from_pitch_value(PitchValue::Fraction(9, Some(8))).unwrap();
+    /// assert_eq!(ji_ratio.as_fraction(), Some((9, 8)));
+    ///
+    /// let ji_ratio = JiRatio::from_pitch_value(PitchValue::Fraction(3, None)).unwrap();
+    /// assert_eq!(ji_ratio.as_fraction(), Some((3, 1)));
+    ///
+    /// assert!(JiRatio::from_pitch_value(PitchValue::Cents(700.0)).is_none());
+    /// ```
+    pub fn from_pitch_value(pitch_value: PitchValue) -> Option<Self> {
+        match pitch_value {
+            PitchValue::Cents(_) => None,
+            PitchValue::Fraction(numer, denom) => {
+                Self::from_fraction(u128::from(numer), u128::from(denom.unwrap_or(1)))
+            }
+        }
+    }
+
+    /// Returns the prime factor decomposition of the [`JiRatio`] without trailing zeros.
+    pub fn prime_factors(&self) -> &[i32] {
+        &self.prime_factors
+    }
+
+    /// Returns the numerator and denominator of the [`JiRatio`] in lowest terms if they fit into a [`u128`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// let pythagorean_comma = JiRatio::from_prime_factors([-19, 12]).unwrap();
+    /// assert_eq!(pythagorean_comma.as_fraction(), Some((531441, 524288)));
+    ///
+    /// let huge_interval = JiRatio::from_prime_factors([0, 100]).unwrap();
+    /// assert_eq!(huge_interval.as_fraction(), None);
+    /// ```
+    pub fn as_fraction(&self) -> Option<(u128, u128)> {
+        let mut numer: u128 = 1;
+        let mut denom: u128 = 1;
+
+        for (&power, &prime) in self.prime_factors.iter().zip(math::U8_PRIMES) {
+            let factor = u128::from(prime).checked_pow(power.unsigned_abs())?;
+            if power >= 0 {
+                numer = numer.checked_mul(factor)?;
+            } else {
+                denom = denom.checked_mul(factor)?;
+            }
+        }
+
+        Some((numer, denom))
+    }
+
+    /// Calculates the float-based [`Ratio`] of the [`JiRatio`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::ji::JiRatio;
+    /// let fifth = JiRatio::from_fraction(3, 2).unwrap();
+    /// assert_approx_eq!(fifth.as_ratio().as_cents(), 701.955001);
+    ///
+    /// // Precise even if the fraction does not fit into a u128
+    /// assert_approx_eq!(fifth.pow(100).unwrap().as_ratio().as_cents(), 70195.500087);
+    /// ```
+    pub fn as_ratio(&self) -> Ratio {
+        Ratio::from_octaves(
+            self.prime_factors
+                .iter()
+                .zip(math::U8_PRIMES)
+                .map(|(&power, &prime)| f64::from(power) * f64::from(prime).log2())
+                .sum::<f64>(),
+        )
+    }
+
+    /// Converts the [`JiRatio`] into a Scala [`PitchValue`].
+    ///
+    /// [`None`] is returned if the numerator or denominator does not fit into a [`u32`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// # use tune::scala::PitchValue;
+    /// let fifth = JiRatio::from_fraction(3, 2).unwrap();
+    /// assert_eq!(fifth.to_pitch_value(), Some(PitchValue::Fraction(3, Some(2))));
+    ///
+    /// let harmonic = JiRatio::from_int(7).unwrap();
+    /// assert_eq!(harmonic.to_pitch_value(), Some(PitchValue::Fraction(7, None)));
+    ///
+    /// assert_eq!(fifth.pow(30).unwrap().to_pitch_value(), None);
+    /// ```
+    pub fn to_pitch_value(&self) -> Option<PitchValue> {
+        let (numer, denom) = self.as_fraction()?;
+        let numer = u32::try_from(numer).ok()?;
+        Some(match denom {
+            1 => PitchValue::Fraction(numer, None),
+            _ => PitchValue::Fraction(numer, Some(u32::try_from(denom).ok()?)),
+        })
+    }
+
+    /// Converts the [`JiRatio`] into a [`Comma`] with the given `description`.
+    ///
+    /// [`None`] is returned if any prime exponent does not fit into an [`i8`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// let diesis = JiRatio::from_fraction(128, 125).unwrap();
+    /// let comma = diesis.to_comma("diesis").unwrap();
+    /// assert_eq!(comma.description(), "diesis");
+    /// assert_eq!(comma.prime_factors(), &[7, 0, -3]);
+    ///
+    /// // Lossless round trip
+    /// assert_eq!(JiRatio::from(&comma), diesis);
+    /// ```
+    pub fn to_comma(&self, description: impl Into<String>) -> Option<Comma> {
+        let prime_factors = self
+            .prime_factors
+            .iter()
+            .map(|&power| i8::try_from(power).ok())
+            .collect::<Option<Vec<_>>>()?;
+        Some(Comma::new(description.into(), prime_factors))
+    }
+
+    /// Returns the largest prime number with a non-zero exponent or 1 for the unison.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// assert_eq!(JiRatio::unison().prime_limit(), 1);
+    /// assert_eq!(JiRatio::from_fraction(2, 1).unwrap().prime_limit(), 2);
+    /// assert_eq!(JiRatio::from_fraction(15, 8).unwrap().prime_limit(), 5);
+    /// assert_eq!(JiRatio::from_fraction(33, 32).unwrap().prime_limit(), 11);
+    /// ```
+    pub fn prime_limit(&self) -> u8 {
+        if self.prime_factors.is_empty() {
+            1
+        } else {
+            math::U8_PRIMES[self.prime_factors.len() - 1]
+        }
+    }
+
+    /// Returns the larger of the odd parts of the numerator and denominator if it fits into a [`u128`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// assert_eq!(JiRatio::unison().odd_limit(), Some(1));
+    /// assert_eq!(JiRatio::from_fraction(8, 1).unwrap().odd_limit(), Some(1));
+    /// assert_eq!(JiRatio::from_fraction(15, 8).unwrap().odd_limit(), Some(15));
+    /// assert_eq!(JiRatio::from_fraction(16, 9).unwrap().odd_limit(), Some(9));
+    /// assert_eq!(JiRatio::from_fraction(10, 7).unwrap().odd_limit(), Some(7));
+    /// ```
+    pub fn odd_limit(&self) -> Option<u128> {
+        let mut odd_part = self.clone();
+        if let Some(power_of_two) = odd_part.prime_factors.first_mut() {
+            *power_of_two = 0;
+        }
+        let (numer, denom) = odd_part.as_fraction()?;
+        Some(numer.max(denom))
+    }
+
+    /// Returns the reciprocal of the [`JiRatio`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if an exponent of the result does not fit into an [`i32`]. Use [`JiRatio::checked_inv`] to handle this case.
+    pub fn inv(&self) -> Self {
+        self.checked_inv().expect("exponent overflow")
+    }
+
+    /// Returns the reciprocal of the [`JiRatio`] or [`None`] if an exponent of the result does not fit into an [`i32`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// let fifth = JiRatio::from_fraction(3, 2).unwrap();
+    /// assert_eq!(fifth.checked_inv().unwrap().as_fraction(), Some((2, 3)));
+    ///
+    /// let extreme = JiRatio::from_prime_factors([i32::MIN]).unwrap();
+    /// assert_eq!(extreme.checked_inv(), None);
+    /// ```
+    pub fn checked_inv(&self) -> Option<Self> {
+        Some(Self {
+            prime_factors: self
+                .prime_factors
+                .iter()
+                .map(|&power| power.checked_neg())
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    /// Multiplies two [`JiRatio`]s or returns [`None`] if an exponent of the result does not fit into an [`i32`].
+    ///
+    /// The [`Mul`] implementation panics in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// let fifth = JiRatio::from_fraction(3, 2).unwrap();
+    /// let fourth = JiRatio::from_fraction(4, 3).unwrap();
+    /// assert_eq!(fifth.checked_mul(&fourth).unwrap().as_fraction(), Some((2, 1)));
+    ///
+    /// let extreme = JiRatio::from_prime_factors([i32::MAX]).unwrap();
+    /// assert_eq!(extreme.checked_mul(&JiRatio::from_int(2).unwrap()), None);
+    /// ```
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        self.combined_with(other, i32::checked_add)
+    }
+
+    /// Divides two [`JiRatio`]s or returns [`None`] if an exponent of the result does not fit into an [`i32`].
+    ///
+    /// The [`Div`] implementation panics in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// let fifth = JiRatio::from_fraction(3, 2).unwrap();
+    /// let fourth = JiRatio::from_fraction(4, 3).unwrap();
+    /// assert_eq!(fifth.checked_div(&fourth).unwrap().as_fraction(), Some((9, 8)));
+    ///
+    /// let extreme = JiRatio::from_prime_factors([i32::MIN]).unwrap();
+    /// assert_eq!(extreme.checked_div(&JiRatio::from_int(2).unwrap()), None);
+    /// ```
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        self.combined_with(other, i32::checked_sub)
+    }
+
+    /// Stacks the [`JiRatio`] `num_times` times.
+    ///
+    /// [`None`] is returned if an exponent of the result does not fit into an [`i32`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// let major_third = JiRatio::from_fraction(5, 4).unwrap();
+    /// assert_eq!(major_third.pow(3).unwrap().as_fraction(), Some((125, 64)));
+    /// assert_eq!(major_third.pow(-1).unwrap().as_fraction(), Some((4, 5)));
+    /// assert_eq!(major_third.pow(0), Some(JiRatio::unison()));
+    /// assert_eq!(major_third.pow(i32::MAX), None);
+    /// ```
+    pub fn pow(&self, num_times: i32) -> Option<Self> {
+        Some(Self::normalized(
+            self.prime_factors
+                .iter()
+                .map(|&power| power.checked_mul(num_times))
+                .collect::<Option<_>>()?,
+        ))
+    }
+
+    /// Adds or removes factors of 2 such that the result lies within the range [1/1, 2/1).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// let reduce = |numer, denom| {
+    ///     JiRatio::from_fraction(numer, denom)
+    ///         .unwrap()
+    ///         .octave_reduced()
+    ///         .as_fraction()
+    ///         .unwrap()
+    /// };
+    ///
+    /// assert_eq!(reduce(1, 1), (1, 1));
+    /// assert_eq!(reduce(2, 1), (1, 1));
+    /// assert_eq!(reduce(1, 2), (1, 1));
+    /// assert_eq!(reduce(3, 1), (3, 2));
+    /// assert_eq!(reduce(3, 4), (3, 2));
+    /// assert_eq!(reduce(81, 1), (81, 64));
+    /// assert_eq!(reduce(1, 3), (4, 3));
+    /// assert_eq!(reduce(255, 1), (255, 128));
+    /// ```
+    pub fn octave_reduced(&self) -> Self {
+        let mut prime_factors = self.prime_factors.clone();
+        if let Some(power_of_two) = prime_factors.first_mut() {
+            *power_of_two = 0;
+        }
+        let mut odd_part = Self::normalized(prime_factors);
+        let num_octaves = odd_part.as_ratio().as_octaves().floor() as i32;
+        if num_octaves != 0 {
+            odd_part.prime_factors[0] = -num_octaves;
+        }
+        odd_part
+    }
+
+    fn normalized(mut prime_factors: Vec<i32>) -> Self {
+        while prime_factors.last() == Some(&0) {
+            prime_factors.pop();
+        }
+        Self { prime_factors }
+    }
+
+    fn combined_with(
+        &self,
+        other: &Self,
+        combine: impl Fn(i32, i32) -> Option<i32>,
+    ) -> Option<Self> {
+        let len = self.prime_factors.len().max(other.prime_factors.len());
+        Some(Self::normalized(
+            (0..len)
+                .map(|index| {
+                    combine(
+                        self.prime_factors.get(index).copied().unwrap_or(0),
+                        other.prime_factors.get(index).copied().unwrap_or(0),
+                    )
+                })
+                .collect::<Option<_>>()?,
+        ))
+    }
+}
+
+fn factorize(mut int_value: u128) -> Option<Vec<i32>> {
+    if int_value == 0 {
+        return None;
+    }
+
+    let mut prime_factors = vec![0; math::U8_PRIMES.len()];
+    for (power, &prime) in prime_factors.iter_mut().zip(math::U8_PRIMES) {
+        let prime = u128::from(prime);
+        while int_value % prime == 0 {
+            int_value /= prime;
+            *power += 1;
+        }
+    }
+
+    (int_value == 1).then_some(prime_factors)
+}
+
+impl From<&Comma> for JiRatio {
+    fn from(comma: &Comma) -> Self {
+        Self::normalized(
+            comma
+                .prime_factors()
+                .iter()
+                .map(|&power| i32::from(power))
+                .collect(),
+        )
+    }
+}
+
+impl From<JiRatio> for Ratio {
+    fn from(ji_ratio: JiRatio) -> Self {
+        ji_ratio.as_ratio()
+    }
+}
+
+impl Mul for JiRatio {
+    type Output = JiRatio;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(&rhs).expect("exponent overflow")
+    }
+}
+
+impl Mul for &JiRatio {
+    type Output = JiRatio;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs).expect("exponent overflow")
+    }
+}
+
+impl Div for JiRatio {
+    type Output = JiRatio;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.checked_div(&rhs).expect("exponent overflow")
+    }
+}
+
+impl Div for &JiRatio {
+    type Output = JiRatio;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.checked_div(rhs).expect("exponent overflow")
+    }
+}
+
+/// Prints the fraction representation if possible and falls back to the monzo notation.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::ji::JiRatio;
+/// assert_eq!(JiRatio::from_fraction(81, 80).unwrap().to_string(), "81/80");
+/// assert_eq!(JiRatio::from_int(3).unwrap().to_string(), "3/1");
+/// assert_eq!(JiRatio::from_prime_factors([-1, 90]).unwrap().to_string(), "[-1 90>");
+/// ```
+impl Display for JiRatio {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some((numer, denom)) = self.as_fraction() {
+            return write!(f, "{numer}/{denom}");
+        }
+
+        write!(f, "[")?;
+        for (index, power) in self.prime_factors.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{power}")?;
+        }
+        write!(f, ">")
+    }
+}
+
+/// A fraction in lowest terms, used as the result of approximation searches.
+///
+/// In contrast to [`JiRatio`], a [`Fraction`] can hold arbitrary prime factors, as they occur in continued fraction expansions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Fraction {
+    /// The numerator of the fraction.
+    pub numer: u64,
+    /// The denominator of the fraction.
+    pub denom: u64,
+}
+
+impl Fraction {
+    /// Creates a [`Fraction`] reduced to lowest terms.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::Fraction;
+    /// let fraction = Fraction::new(10, 4);
+    /// assert_eq!((fraction.numer, fraction.denom), (5, 2));
+    /// ```
+    pub fn new(numer: u64, denom: u64) -> Self {
+        let gcd = math::gcd_u64(numer, denom);
+        Self {
+            numer: numer / gcd,
+            denom: denom / gcd,
+        }
+    }
+
+    /// Calculates the float-based [`Ratio`] of the [`Fraction`].
+    pub fn as_ratio(self) -> Ratio {
+        Ratio::from_float(self.numer as f64 / self.denom as f64)
+    }
+
+    /// Converts the [`Fraction`] into a [`JiRatio`] if all prime factors are supported.
+    pub fn to_ji_ratio(self) -> Option<JiRatio> {
+        JiRatio::from_fraction(u128::from(self.numer), u128::from(self.denom))
+    }
+
+    /// Calculates the Tenney height log2(numer*denom).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::ji::Fraction;
+    /// assert_approx_eq!(Fraction::new(1, 1).tenney_height(), 0.0);
+    /// assert_approx_eq!(Fraction::new(3, 2).tenney_height(), 2.584963);
+    /// assert_approx_eq!(Fraction::new(81, 80).tenney_height(), 12.661778);
+    /// ```
+    pub fn tenney_height(self) -> f64 {
+        (self.numer as f64).log2() + (self.denom as f64).log2()
+    }
+
+    /// Calculates the Weil height log2(max(numer, denom)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::ji::Fraction;
+    /// assert_approx_eq!(Fraction::new(1, 1).weil_height(), 0.0);
+    /// assert_approx_eq!(Fraction::new(3, 2).weil_height(), 1.584963);
+    /// assert_approx_eq!(Fraction::new(2, 3).weil_height(), 1.584963);
+    /// ```
+    pub fn weil_height(self) -> f64 {
+        (self.numer.max(self.denom) as f64).log2()
+    }
+}
+
+impl Display for Fraction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.numer, self.denom)
+    }
+}
+
+/// Returns the convergents of the continued fraction expansion of `ratio` with denominators up to `max_denom`.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::ji;
+/// # use tune::pitch::Ratio;
+/// let convergents = ji::convergents(Ratio::from_float(std::f64::consts::PI), 40000);
+/// assert_eq!(
+///     Vec::from_iter(convergents.iter().map(ToString::to_string)),
+///     ["3/1", "22/7", "333/106", "355/113", "103993/33102", "104348/33215"]
+/// );
+///
+/// // The expansion terminates for exact fractions
+/// let convergents = ji::convergents(Ratio::from_float(1.6), 1000);
+/// assert_eq!(
+///     Vec::from_iter(convergents.iter().map(ToString::to_string)),
+///     ["1/1", "2/1", "3/2", "8/5"]
+/// );
+/// ```
+pub fn convergents(ratio: Ratio, max_denom: u64) -> Vec<Fraction> {
+    continued_fraction(ratio, max_denom, false)
+}
+
+/// Returns the convergents and all intermediate fractions (semiconvergents) of the continued fraction expansion of `ratio` in order of increasing denominators.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::ji;
+/// # use tune::pitch::Ratio;
+/// let semiconvergents = ji::semiconvergents(Ratio::from_float(std::f64::consts::PI), 120);
+/// assert_eq!(
+///     Vec::from_iter(semiconvergents.iter().map(ToString::to_string)),
+///     [
+///         "3/1", "4/1", "7/2", "10/3", "13/4", "16/5", "19/6", "22/7", "25/8", "47/15", "69/22", "91/29", "113/36",
+///         "135/43", "157/50", "179/57", "201/64", "223/71", "245/78", "267/85", "289/92",
+///         "311/99", "333/106", "355/113"
+///     ]
+/// );
+/// ```
+pub fn semiconvergents(ratio: Ratio, max_denom: u64) -> Vec<Fraction> {
+    continued_fraction(ratio, max_denom, true)
+}
+
+fn continued_fraction(ratio: Ratio, max_denom: u64, include_intermediate: bool) -> Vec<Fraction> {
+    let mut result = Vec::new();
+
+    let target = ratio.as_float();
+    if !(target.is_finite() && target > 0.0) {
+        return result;
+    }
+
+    let mut remainder = target;
+    let (mut numer, mut prev_numer) = (1u64, 0u64);
+    let (mut denom, mut prev_denom) = (0u64, 1u64);
+
+    loop {
+        // Compensate for rounding errors accumulated in previous iterations
+        let term = match remainder.round() {
+            rounded if (remainder - rounded).abs() < 1e-9 => rounded,
+            _ => remainder.floor(),
+        };
+        if term > u64::MAX as f64 {
+            break;
+        }
+        let term = term as u64;
+
+        let first_term = if include_intermediate && denom > 0 {
+            1
+        } else {
+            term
+        };
+        for multiplier in first_term..=term {
+            let next = multiplier
+                .checked_mul(numer)
+                .and_then(|n| n.checked_add(prev_numer))
+                .zip(
+                    multiplier
+                        .checked_mul(denom)
+                        .and_then(|d| d.checked_add(prev_denom)),
+                );
+            match next {
+                Some((n, d)) if d <= max_denom && n > 0 => result.push(Fraction::new(n, d)),
+                Some((_, d)) if d <= max_denom => {}
+                _ => return result,
+            }
+        }
+
+        (numer, prev_numer) = (term * numer + prev_numer, numer);
+        (denom, prev_denom) = (term * denom + prev_denom, denom);
+
+        let deviation = Ratio::from_float(numer as f64 / denom as f64).deviation_from(ratio);
+        let fractional_part = remainder - term as f64;
+        if deviation.as_cents().abs() < 1e-9 || fractional_part < 1e-12 {
+            return result;
+        }
+        remainder = fractional_part.recip();
+    }
+
+    result
+}
+
+/// Strategy for finding [`Fraction`]s that approximate a given [`Ratio`].
+#[derive(Clone, Debug)]
+pub enum ApproximationStrategy {
+    /// Fractions whose numerators and denominators, ignoring powers of two, do not exceed `odd_limit`.
+    OddLimit { odd_limit: u16 },
+
+    /// Like [`ApproximationStrategy::OddLimit`] but only accepting prime factors up to `prime_limit`.
+    PrimeLimit { prime_limit: u8, odd_limit: u16 },
+
+    /// Fractions built from products of the given subgroup `elements`, e.g. 2.3.7.
+    /// The products, ignoring powers of two, must not exceed `odd_limit`.
+    /// Octave shifts are only applied if 2 is a member of the subgroup.
+    Subgroup { elements: Vec<u16>, odd_limit: u16 },
+
+    /// Convergents and semiconvergents of the continued fraction expansion with denominators up to `max_denom`.
+    ContinuedFraction { max_denom: u64 },
+}
+
+/// Order in which [`ApproximationStrategy::approximate`] returns its candidates.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ApproximationRanking {
+    /// Smallest absolute deviation first.
+    Deviation,
+    /// Smallest Tenney height first.
+    TenneyHeight,
+    /// Smallest Weil height first.
+    WeilHeight,
+    /// Only keep candidates that are closer than any candidate with a smaller Tenney height, simplest candidate first.
+    ComplexityVsError,
+}
+
+impl ApproximationStrategy {
+    /// Finds all candidates for the given `ratio` and sorts them according to `ranking`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::ApproximationRanking;
+    /// # use tune::ji::ApproximationStrategy;
+    /// # use tune::pitch::Ratio;
+    /// let harmonic_seventh = Ratio::from_cents(969.0);
+    /// let print = |strategy: ApproximationStrategy, ranking| {
+    ///     Vec::from_iter(
+    ///         strategy
+    ///             .approximate(harmonic_seventh, ranking)
+    ///             .iter()
+    ///             .take(4)
+    ///             .map(|approximation| {
+    ///                 format!(
+    ///                     "{} {:+.1}c",
+    ///                     approximation.approx_value,
+    ///                     approximation.deviation.as_cents()
+    ///                 )
+    ///             }),
+    ///     )
+    /// };
+    ///
+    /// let odd_limit = ApproximationStrategy::OddLimit { odd_limit: 9 };
+    /// assert_eq!(
+    ///     print(odd_limit, ApproximationRanking::Deviation),
+    ///     ["7/4 +0.2c", "16/9 -27.1c", "12/7 +35.9c", "9/5 -48.6c"]
+    /// );
+    ///
+    /// let prime_limit = ApproximationStrategy::PrimeLimit { prime_limit: 5, odd_limit: 81 };
+    /// assert_eq!(
+    ///     print(prime_limit, ApproximationRanking::Deviation),
+    ///     ["16/9 -27.1c", "128/75 +43.6c", "9/5 -48.6c", "27/16 +63.1c"]
+    /// );
+    ///
+    /// let subgroup = ApproximationStrategy::Subgroup { elements: vec![2, 3, 7], odd_limit: 21 };
+    /// assert_eq!(
+    ///     print(subgroup, ApproximationRanking::ComplexityVsError),
+    ///     ["2/1 -231.0c", "7/4 +0.2c"]
+    /// );
+    ///
+    /// let continued_fraction = ApproximationStrategy::ContinuedFraction { max_denom: 100 };
+    /// assert_eq!(
+    ///     print(continued_fraction, ApproximationRanking::TenneyHeight),
+    ///     ["1/1 +969.0c", "2/1 -231.0c", "3/2 +267.0c", "5/3 +84.6c"]
+    /// );
+    /// ```
+    pub fn approximate(
+        &self,
+        ratio: Ratio,
+        ranking: ApproximationRanking,
+    ) -> Vec<Approximation<Fraction>> {
+        let mut candidates =
+            Vec::from_iter(
+                self.candidates(ratio)
+                    .into_iter()
+                    .map(|fraction| Approximation {
+                        approx_value: fraction,
+                        deviation: ratio.deviation_from(fraction.as_ratio()),
+                    }),
+            );
+
+        // Deviations are compared with a resolution of 1 microcent s.t. equidistant candidates are ordered by their simplicity
+        let deviation_key =
+            |a: &Approximation<Fraction>| (a.deviation.as_cents().abs() * 1e6).round();
+        let by_deviation = |a: &Approximation<Fraction>, b: &Approximation<Fraction>| {
+            deviation_key(a).total_cmp(&deviation_key(b)).then_with(|| {
+                a.approx_value
+                    .tenney_height()
+                    .total_cmp(&b.approx_value.tenney_height())
+            })
+        };
+        let by_height = |height: fn(Fraction) -> f64| {
+            move |a: &Approximation<Fraction>, b: &Approximation<Fraction>| {
+                height(a.approx_value)
+                    .total_cmp(&height(b.approx_value))
+                    .then_with(|| by_deviation(a, b))
+            }
+        };
+
+        match ranking {
+            ApproximationRanking::Deviation => candidates.sort_by(by_deviation),
+            ApproximationRanking::TenneyHeight => {
+                candidates.sort_by(by_height(Fraction::tenney_height))
+            }
+            ApproximationRanking::WeilHeight => {
+                candidates.sort_by(by_height(Fraction::weil_height))
+            }
+            ApproximationRanking::ComplexityVsError => {
+                candidates.sort_by(by_height(Fraction::tenney_height));
+                let mut best_deviation = Ratio::from_float(f64::INFINITY);
+                candidates.retain(|candidate| {
+                    let is_better = candidate.deviation.abs() < best_deviation;
+                    if is_better {
+                        best_deviation = candidate.deviation.abs();
+                    }
+                    is_better
+                });
+            }
+        }
+
+        candidates
+    }
+
+    /// Returns the candidate with the smallest deviation from `ratio`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::ApproximationStrategy;
+    /// # use tune::pitch::Ratio;
+    /// let strategy = ApproximationStrategy::PrimeLimit { prime_limit: 3, odd_limit: 81 };
+    /// let best = strategy.best_approximation(Ratio::from_cents(400.0)).unwrap();
+    /// assert_eq!(best.approx_value.to_string(), "81/64");
+    /// ```
+    pub fn best_approximation(&self, ratio: Ratio) -> Option<Approximation<Fraction>> {
+        self.approximate(ratio, ApproximationRanking::Deviation)
+            .into_iter()
+            .next()
+    }
+
+    fn candidates(&self, ratio: Ratio) -> Vec<Fraction> {
+        match self {
+            &ApproximationStrategy::OddLimit { odd_limit } => {
+                let odd_numbers = Vec::from_iter((1..=u64::from(odd_limit)).step_by(2));
+                octave_shifted_fractions(ratio, &odd_numbers, true)
+            }
+            &ApproximationStrategy::PrimeLimit {
+                prime_limit,
+                odd_limit,
+            } => {
+                let odd_primes = Vec::from_iter(
+                    math::U8_PRIMES
+                        .iter()
+                        .skip(1)
+                        .take_while(|&&prime| prime <= prime_limit)
+                        .map(|&prime| u64::from(prime)),
+                );
+                let products = products_up_to(&odd_primes, u64::from(odd_limit));
+                octave_shifted_fractions(ratio, &products, prime_limit >= 2)
+            }
+            ApproximationStrategy::Subgroup {
+                elements,
+                odd_limit,
+            } => {
+                let generators = Vec::from_iter(
+                    elements
+                        .iter()
+                        .filter(|&&element| element > 2)
+                        .map(|&element| u64::from(element)),
+                );
+                let products = products_up_to(&generators, u64::from(*odd_limit));
+                octave_shifted_fractions(ratio, &products, elements.contains(&2))
+            }
+            &ApproximationStrategy::ContinuedFraction { max_denom } => {
+                semiconvergents(ratio, max_denom)
+            }
+        }
+    }
+}
+
+fn products_up_to(generators: &[u64], limit: u64) -> Vec<u64> {
+    let mut products = vec![1u64];
+    let mut visited = HashSet::from([1]);
+    let mut index = 0;
+    while let Some(&product) = products.get(index) {
+        for &generator in generators {
+            if let Some(next) = product.checked_mul(generator) {
+                if next <= limit && visited.insert(next) {
+                    products.push(next);
+                }
+            }
+        }
+        index += 1;
+    }
+    products
+}
+
+fn octave_shifted_fractions(ratio: Ratio, factors: &[u64], shift_octaves: bool) -> Vec<Fraction> {
+    let mut fractions = Vec::new();
+    let mut visited = HashSet::new();
+    for &numer in factors {
+        for &denom in factors {
+            let mut fraction = Fraction::new(numer, denom);
+            if shift_octaves {
+                let num_octaves = ratio
+                    .deviation_from(fraction.as_ratio())
+                    .as_octaves()
+                    .round();
+                let shift = num_octaves.abs() as u32;
+                let shifted = if num_octaves >= 0.0 {
+                    2u64.checked_pow(shift)
+                        .and_then(|factor| fraction.numer.checked_mul(factor))
+                        .map(|numer| Fraction::new(numer, fraction.denom))
+                } else {
+                    2u64.checked_pow(shift)
+                        .and_then(|factor| fraction.denom.checked_mul(factor))
+                        .map(|denom| Fraction::new(fraction.numer, denom))
+                };
+                match shifted {
+                    Some(shifted) => fraction = shifted,
+                    None => continue,
+                }
+            }
+            if visited.insert(fraction) {
+                fractions.push(fraction);
+            }
+        }
+    }
+    fractions
+}
@@ -1,5 +1,12 @@
 //! Operations for working with physical or virtual keyboards.
 
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{self, BufRead, BufReader, Read},
+};
+
+use crate::pergen::Mos;
+
 /// A physical or logical key on a real or virtual instrument without any notion of a pitch.
 ///
 /// This struct does *not* represent a musical key, like in "F&nbsp;minor", which is why its name is [`PianoKey`].
@@ -94,3 +101,295 @@ impl PianoKey {
         PianoKey::from_midi_number(self.midi_number + num_steps)
     }
 }
+
+/// A key on a two-dimensional isomorphic keyboard, e.g. a hex controller, addressed by its `(x, y)` coordinates.
+///
+/// Use a [`HexMapping`] to map a [`HexKey`] to a scale degree.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HexKey {
+    x: i16,
+    y: i16,
+}
+
+impl HexKey {
+    /// Creates a [`HexKey`] instance from the given coordinates.
+    pub fn from_coords(x: i16, y: i16) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the horizontal coordinate of this [`HexKey`].
+    pub fn x(self) -> i16 {
+        self.x
+    }
+
+    /// Returns the vertical coordinate of this [`HexKey`].
+    pub fn y(self) -> i16 {
+        self.y
+    }
+
+    /// Returns the key `num_x_steps` steps to the right and `num_y_steps` steps upwards of `self`.
+    ///
+    /// [`None`] is returned if a coordinate of the resulting key exceeds the bounds of [`i16`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::key::HexKey;
+    /// let key = HexKey::from_coords(2, -1);
+    ///
+    /// assert_eq!(key.plus_steps(3, 4), Some(HexKey::from_coords(5, 3)));
+    /// assert_eq!(key.plus_steps(-2, 1), Some(HexKey::from_coords(0, 0)));
+    /// assert_eq!(key.plus_steps(i16::MAX, 0), None);
+    /// ```
+    pub fn plus_steps(self, num_x_steps: i16, num_y_steps: i16) -> Option<HexKey> {
+        Some(HexKey::from_coords(
+            self.x.checked_add(num_x_steps)?,
+            self.y.checked_add(num_y_steps)?,
+        ))
+    }
+}
+
+/// Maps [`HexKey`]s to scale degrees using the primary and secondary step of a [`Mos`].
+///
+/// Moving one key to the right adds the primary step, moving one key upwards adds the secondary step.
+/// The `origin` key maps to scale degree 0.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::key::HexKey;
+/// # use tune::key::HexMapping;
+/// # use tune::pergen::Mos;
+/// // 31-EDO with whole tones (5 steps) along the x axis and diatonic semitones (3 steps) along the y axis
+/// let mapping = HexMapping::new(Mos::new(5, 2, 5, 3).unwrap(), HexKey::from_coords(0, 0));
+///
+/// assert_eq!(mapping.degree_of(HexKey::from_coords(0, 0)), 0);
+/// assert_eq!(mapping.degree_of(HexKey::from_coords(1, 0)), 5);
+/// assert_eq!(mapping.degree_of(HexKey::from_coords(0, 1)), 3);
+/// assert_eq!(mapping.degree_of(HexKey::from_coords(-1, 2)), 1);
+///
+/// // Degrees are computed in 64-bit arithmetic s.t. extreme coordinates do not overflow
+/// assert_eq!(mapping.degree_of(HexKey::from_coords(i16::MAX, i16::MAX)), 262136);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct HexMapping {
+    mos: Mos,
+    origin: HexKey,
+}
+
+impl HexMapping {
+    /// Creates a [`HexMapping`] from the step sizes of `mos` with `origin` mapping to scale degree 0.
+    pub fn new(mos: Mos, origin: HexKey) -> Self {
+        Self { mos, origin }
+    }
+
+    /// Returns the [`Mos`] whose step sizes are used.
+    pub fn mos(&self) -> Mos {
+        self.mos
+    }
+
+    /// Returns the [`HexKey`] mapping to scale degree 0.
+    pub fn origin(&self) -> HexKey {
+        self.origin
+    }
+
+    /// Returns the scale degree of the given `key`.
+    pub fn degree_of(&self, key: HexKey) -> i64 {
+        let num_x_steps = i64::from(key.x) - i64::from(self.origin.x);
+        let num_y_steps = i64::from(key.y) - i64::from(self.origin.y);
+
+        num_x_steps * i64::from(self.mos.primary_step())
+            + num_y_steps * i64::from(self.mos.secondary_step())
+    }
+
+    /// Finds the [`HexKey`] closest to the origin that maps to the given scale `degree`.
+    ///
+    /// [`None`] is returned if the degree cannot be reached, e.g. when both step sizes are even and the degree is odd.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::key::HexKey;
+    /// # use tune::key::HexMapping;
+    /// # use tune::pergen::Mos;
+    /// let mapping = HexMapping::new(Mos::new(5, 2, 5, 3).unwrap(), HexKey::from_coords(10, 10));
+    ///
+    /// assert_eq!(mapping.find_key(0), Some(HexKey::from_coords(10, 10)));
+    /// assert_eq!(mapping.find_key(8), Some(HexKey::from_coords(11, 11)));
+    /// assert_eq!(mapping.find_key(1), Some(HexKey::from_coords(9, 12)));
+    /// assert_eq!(mapping.find_key(-31), Some(HexKey::from_coords(5, 8)));
+    ///
+    /// let even_mapping = HexMapping::new(Mos::new(5, 2, 4, 2).unwrap(), HexKey::from_coords(0, 0));
+    /// assert_eq!(even_mapping.find_key(1), None);
+    ///
+    /// // Degrees outside of the coordinate range are unreachable
+    /// assert_eq!(mapping.find_key(i64::MAX), None);
+    /// ```
+    pub fn find_key(&self, degree: i64) -> Option<HexKey> {
+        let primary_step = i64::from(self.mos.primary_step());
+        let secondary_step = i64::from(self.mos.secondary_step());
+
+        let (gcd, x, y) = extended_gcd(primary_step, secondary_step);
+        if gcd == 0 {
+            return (degree == 0).then_some(self.origin);
+        }
+        if degree % gcd != 0 {
+            return None;
+        }
+
+        // All solutions are given by (x + k * x_period, y - k * y_period)
+        let (x, y) = (x.checked_mul(degree / gcd)?, y.checked_mul(degree / gcd)?);
+        let (x_period, y_period) = (secondary_step / gcd, primary_step / gcd);
+
+        let mut candidates = vec![0];
+        for (value, period) in [(x.checked_neg()?, x_period), (y, y_period)] {
+            if period != 0 {
+                let k = value.div_euclid(period);
+                candidates.push(k);
+                candidates.extend(k.checked_add(1));
+            }
+        }
+
+        let (num_x_steps, num_y_steps) = candidates
+            .into_iter()
+            .filter_map(|k| {
+                Some((
+                    x.checked_add(k.checked_mul(x_period)?)?,
+                    y.checked_sub(k.checked_mul(y_period)?)?,
+                ))
+            })
+            .min_by_key(|(x, y)| x.unsigned_abs().saturating_add(y.unsigned_abs()))?;
+
+        self.origin.plus_steps(
+            i16::try_from(num_x_steps).ok()?,
+            i16::try_from(num_y_steps).ok()?,
+        )
+    }
+
+    /// Imports a [`HexMapping`] from a text file.
+    ///
+    /// The file consists of six integer values: The number of primary steps, the number of secondary steps, the primary step, the secondary step and the x and y coordinate of the origin.
+    /// Lines starting with `!` are comments. Any text following the value of a line is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::key::HexKey;
+    /// # use tune::key::HexMapping;
+    /// let input = [
+    ///     "! 31-EDO meantone on a hex controller",
+    ///     "5 <- number of primary steps",
+    ///     "2 <- number of secondary steps",
+    ///     "5 <- primary step",
+    ///     "3 <- secondary step",
+    ///     "-3",
+    ///     "4",
+    /// ];
+    ///
+    /// let mapping = HexMapping::import(input.join("\n").as_bytes()).unwrap();
+    ///
+    /// assert_eq!(mapping.mos().size(), 31);
+    /// assert_eq!(mapping.origin(), HexKey::from_coords(-3, 4));
+    /// assert_eq!(mapping.degree_of(HexKey::from_coords(-2, 4)), 5);
+    ///
+    /// assert_eq!(
+    ///     mapping.export().to_string().lines().collect::<Vec<_>>(),
+    ///     ["5", "2", "5", "3", "-3", "4"]
+    /// );
+    ///
+    /// assert!(HexMapping::import("5\n2\n5".as_bytes()).is_err());
+    /// ```
+    pub fn import(reader: impl Read) -> Result<Self, HexMappingImportError> {
+        let mut values = Vec::new();
+
+        for (line_number, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('!') {
+                continue;
+            }
+            let value = trimmed
+                .split_ascii_whitespace()
+                .next()
+                .and_then(|value| value.parse::<i32>().ok())
+                .ok_or(HexMappingImportError::ParseError {
+                    line_number: line_number + 1,
+                })?;
+            values.push((line_number + 1, value));
+        }
+
+        let [num_primary_steps, num_secondary_steps, primary_step, secondary_step, x, y] =
+            <[(usize, i32); 6]>::try_from(values)
+                .map_err(|values| HexMappingImportError::InconsistentNumberOfItems(values.len()))?;
+
+        let as_u16 = |(line_number, value): (usize, i32)| {
+            u16::try_from(value).map_err(|_| HexMappingImportError::ParseError { line_number })
+        };
+        let as_i16 = |(line_number, value): (usize, i32)| {
+            i16::try_from(value).map_err(|_| HexMappingImportError::ParseError { line_number })
+        };
+
+        let mos = Mos::new(
+            as_u16(num_primary_steps)?,
+            as_u16(num_secondary_steps)?,
+            as_u16(primary_step)?,
+            as_u16(secondary_step)?,
+        )
+        .ok_or(HexMappingImportError::MosTooLarge)?;
+
+        Ok(Self::new(mos, HexKey::from_coords(as_i16(x)?, as_i16(y)?)))
+    }
+
+    /// Exports the current [`HexMapping`] in the format accepted by [`HexMapping::import`].
+    pub fn export(&self) -> HexMappingExport<'_> {
+        HexMappingExport(self)
+    }
+}
+
+/// Helper struct to export a [`HexMapping`] via its [`Display`] implementation.
+pub struct HexMappingExport<'a>(&'a HexMapping);
+
+impl<'a> Display for HexMappingExport<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mos = self.0.mos;
+        writeln!(f, "{}", mos.num_primary_steps())?;
+        writeln!(f, "{}", mos.num_secondary_steps())?;
+        writeln!(f, "{}", mos.primary_step())?;
+        writeln!(f, "{}", mos.secondary_step())?;
+        writeln!(f, "{}", self.0.origin.x)?;
+        writeln!(f, "{}", self.0.origin.y)
+    }
+}
+
+/// Error reported when importing a [`HexMapping`] fails.
+#[derive(Debug)]
+pub enum HexMappingImportError {
+    IoError(io::Error),
+
+    /// Invalid integer value or out of range.
+    ParseError {
+        line_number: usize,
+    },
+
+    /// The file does not contain exactly six values. The number of values found is provided.
+    InconsistentNumberOfItems(usize),
+
+    /// The total size of the [`Mos`] exceeds numeric bounds.
+    MosTooLarge,
+}
+
+impl From<io::Error> for HexMappingImportError {
+    fn from(v: io::Error) -> Self {
+        Self::IoError(v)
+    }
+}
+
+/// Returns `(gcd, x, y)` s.t. `a * x + b * y = gcd`.
+fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (gcd, x, y) = extended_gcd(b, a % b);
+        (gcd, y, x - a / b * y)
+    }
+}
@@ -1,11 +1,14 @@
 //! Find generator chains and keyboard layouts.
 
 use std::{
+    borrow::Cow,
     cmp::Ordering,
     fmt::{self, Display},
 };
 
 use crate::{
+    ji::ApproximationStrategy,
+    math,
     pergen::{Accidentals, AccidentalsFormat, AccidentalsOrder, Mos, NoteFormatter, PerGen},
     pitch::Ratio,
     temperament::Val,
@@ -16,6 +19,7 @@ use crate::{
 pub struct IsomorphicLayout {
     genchain: Genchain,
     b_val: bool,
+    step_size: Ratio,
     pergen: PerGen,
     mos: Mos,
     acc_format: AccidentalsFormat,
@@ -28,7 +32,17 @@ impl IsomorphicLayout {
     }
 
     pub fn find_by_step_size(step_size: Ratio) -> Vec<IsomorphicLayout> {
-        let patent_val = Val::patent(step_size, 5);
+        Self::find_by_step_size_with(step_size, &[])
+    }
+
+    /// Finds layouts for the given `step_size` using the [`Genchain::presets`] followed by the given `extra_genchains`.
+    ///
+    /// See [`Genchain`] for an example.
+    pub fn find_by_step_size_with(
+        step_size: Ratio,
+        extra_genchains: &[Genchain],
+    ) -> Vec<IsomorphicLayout> {
+        let patent_val = Val::patent(step_size, Self::prime_limit(extra_genchains));
 
         let patent_val_errors: Vec<_> = patent_val
             .errors_in_steps()
@@ -42,28 +56,120 @@ impl IsomorphicLayout {
             b_val
         });
 
-        [
-            // Sorted from highest to lowest sharpness within a group
-            Genchain::Mavila9,
-            Genchain::Meantone7,
-            Genchain::Meantone5,
-            Genchain::Porcupine8,
-            Genchain::Tetracot7,
-            Genchain::Hanson7,
-        ]
-        .into_iter()
-        .flat_map(|genchain| {
-            genchain.create_layout(&patent_val, false).or_else(|| {
-                b_val
-                    .as_ref()
-                    .and_then(|b_val| genchain.create_layout(b_val, true))
+        Genchain::presets()
+            .iter()
+            .chain(extra_genchains)
+            .flat_map(|genchain| {
+                genchain.create_layout(&patent_val, false).or_else(|| {
+                    b_val
+                        .as_ref()
+                        .and_then(|b_val| genchain.create_layout(b_val, true))
+                })
             })
-        })
-        .collect()
+            .collect()
+    }
+
+    /// Finds layouts for the rank-2 tuning with `num_steps` notes per `period` generated by `generator`, e.g. a 12-note 1/4-comma meantone scale.
+    ///
+    /// The tuning does not need to be equal-stepped. The note names are determined by the structure of the MOS rather than by the step sizes.
+    /// Only genchains from [`Genchain::presets`] or `extra_genchains` that map `generator` to the same number of steps as the MOS are considered.
+    ///
+    /// Note that the layouts' step size is the equal division of `period` into `num_steps`, not the actual (unequal) step sizes of the rank-2 tuning.
+    /// As a consequence, step-size-based features like [`NoteNotation::Heji`] refer to the corresponding equal temperament.
+    ///
+    /// [`None`] is returned if the given `generator` does not form a MOS with `num_steps` notes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::layout::IsomorphicLayout;
+    /// # use tune::pitch::Ratio;
+    /// let quarter_comma_fifth = Ratio::from_float(5f64.powf(0.25));
+    ///
+    /// let layouts =
+    ///     IsomorphicLayout::find_by_generator(Ratio::octave(), quarter_comma_fifth, 19, &[]).unwrap();
+    /// let meantone = &layouts[0];
+    ///
+    /// assert_eq!(meantone.genchain().to_string(), "Meantone[7]");
+    /// assert_eq!(meantone.get_scale_name(), "diatonic");
+    /// assert_eq!((meantone.mos().primary_step(), meantone.mos().secondary_step()), (3, 2));
+    /// assert_eq!(
+    ///     Vec::from_iter((0..19).map(|degree| meantone.get_note_name(degree))).join(" "),
+    ///     "D D# Eb E E#/Fb F F# Gb G G# Ab A A# Bb B B#/Cb C C# Db"
+    /// );
+    ///
+    /// // 13 notes of 1/4-comma meantone do not form a MOS
+    /// assert!(IsomorphicLayout::find_by_generator(Ratio::octave(), quarter_comma_fifth, 13, &[]).is_none());
+    /// ```
+    pub fn find_by_generator(
+        period: Ratio,
+        generator: Ratio,
+        num_steps: u16,
+        extra_genchains: &[Genchain],
+    ) -> Option<Vec<IsomorphicLayout>> {
+        let generator = generator.as_octaves() / period.as_octaves();
+        if !generator.is_finite() {
+            return None;
+        }
+
+        Mos::<f64>::new_genesis(generator)
+            .children()
+            .find(|mos| mos.num_steps() == u32::from(num_steps))?;
+
+        // The generator spans as many steps as there are MOS notes below it
+        let generator = generator.rem_euclid(1.0);
+        let generator_steps = (1..num_steps)
+            .filter(|&index| (f64::from(index) * generator).rem_euclid(1.0) < generator)
+            .count();
+        let generator_steps = u16::try_from(generator_steps).ok()? + 1;
+
+        let step_size = period.divided_into_equal_steps(num_steps);
+        let patent_val = Val::patent(step_size, Self::prime_limit(extra_genchains));
+        let mut b_val = patent_val.clone();
+        let has_b_val = b_val.pick_alternative(1);
+
+        let matches_generator = |layout: &IsomorphicLayout| {
+            let pergen_generator = layout.pergen.generator() % num_steps;
+            layout.pergen.period() == num_steps
+                && (pergen_generator == generator_steps
+                    || pergen_generator == num_steps - generator_steps)
+        };
+
+        Some(
+            Genchain::presets()
+                .iter()
+                .chain(extra_genchains)
+                .flat_map(|genchain| {
+                    genchain
+                        .create_layout(&patent_val, false)
+                        .filter(matches_generator)
+                        .or_else(|| {
+                            has_b_val
+                                .then(|| genchain.create_layout(&b_val, true))
+                                .flatten()
+                                .filter(matches_generator)
+                        })
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the prime limit required to evaluate all genchains, but at least 5.
+    fn prime_limit(extra_genchains: &[Genchain]) -> u8 {
+        Genchain::presets()
+            .iter()
+            .chain(extra_genchains)
+            .flat_map(|genchain| {
+                [
+                    genchain.period.prime_limit(),
+                    genchain.generator.prime_limit(),
+                ]
+            })
+            .fold(5, u8::max)
     }
 
-    pub fn genchain(&self) -> Genchain {
-        self.genchain
+    pub fn genchain(&self) -> &Genchain {
+        &self.genchain
     }
 
     pub fn b_val(&self) -> bool {
@@ -86,21 +192,11 @@ impl IsomorphicLayout {
         self.mos
     }
 
-    pub fn get_scale_name(&self) -> &'static str {
-        match (self.genchain, self.mos.sharpness().cmp(&0)) {
-            (_, Ordering::Equal) => "equalized",
-            (Genchain::Mavila9, Ordering::Greater) => "armotonic",
-            (Genchain::Mavila9, Ordering::Less) => "balzano",
-            (Genchain::Meantone7, Ordering::Greater) => "diatonic",
-            (Genchain::Meantone7, Ordering::Less) => "antidiatonic",
-            (Genchain::Meantone5, Ordering::Greater) => "antipentic",
-            (Genchain::Meantone5, Ordering::Less) => "pentic",
-            (Genchain::Porcupine8, Ordering::Greater) => "pine",
-            (Genchain::Porcupine8, Ordering::Less) => "antipine",
-            (Genchain::Tetracot7, Ordering::Greater) => "archeotonic",
-            (Genchain::Tetracot7, Ordering::Less) => "onyx",
-            (Genchain::Hanson7, Ordering::Greater) => "smitonic",
-            (Genchain::Hanson7, Ordering::Less) => "mosh",
+    pub fn get_scale_name(&self) -> &str {
+        match self.mos.sharpness().cmp(&0) {
+            Ordering::Equal => "equalized",
+            Ordering::Greater => &self.genchain.scale_names[0],
+            Ordering::Less => &self.genchain.scale_names[1],
         }
     }
 
@@ -128,6 +224,83 @@ impl IsomorphicLayout {
         self.formatter.format(&self.get_accidentals(index))
     }
 
+    /// Obtains the note name for the given degree of the current layout using the given [`NoteNotation`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use tune::layout::IsomorphicLayout;
+    /// # use tune::layout::NoteNotation;
+    /// let edo_31 = &IsomorphicLayout::find_by_edo(31)[0];
+    ///
+    /// let note_names = |notation| {
+    ///     [0, 1, 4, 9, 10, 13]
+    ///         .map(|index| edo_31.get_note_name_with(index, notation))
+    ///         .join(", ")
+    /// };
+    ///
+    /// assert_eq!(
+    ///     note_names(NoteNotation::Accidentals),
+    ///     "D, Ebb, D##/Fbb, E##/Gbb, F#, G"
+    /// );
+    /// assert_eq!(note_names(NoteNotation::UpsAndDowns), "D, ^D, vE, ^F, F#, G");
+    /// assert_eq!(
+    ///     note_names(NoteNotation::Heji { odd_limit: 11 }),
+    ///     "D, ^D, E↓11, F↑11, F#↓, G"
+    /// );
+    ///
+    /// // Distinct degrees never share the same HEJI name
+    /// let heji_names = HashSet::<_>::from_iter(
+    ///     (0..31).map(|index| edo_31.get_note_name_with(index, NoteNotation::Heji { odd_limit: 11 })),
+    /// );
+    /// assert_eq!(heji_names.len(), 31);
+    /// ```
+    pub fn get_note_name_with(&self, index: u16, notation: NoteNotation) -> String {
+        match notation {
+            NoteNotation::Accidentals => self.get_note_name(index),
+            NoteNotation::UpsAndDowns => self
+                .formatter
+                .format_ups_and_downs(&self.pergen.get_ups_and_downs(&self.acc_format, index)),
+            NoteNotation::Heji { odd_limit } => {
+                let ratio = self.step_size.repeated(index);
+                let ji_ratio = ApproximationStrategy::OddLimit { odd_limit }
+                    .best_approximation(ratio)
+                    .and_then(|approximation| approximation.approx_value.to_ji_ratio());
+
+                match ji_ratio {
+                    Some(ji_ratio) => {
+                        let genchain = Genchain::MEANTONE_7;
+                        let acc_format = AccidentalsFormat {
+                            num_symbols: u16::try_from(genchain.mos_size()).unwrap(),
+                            genchain_origin: genchain.genchain_origin,
+                        };
+                        let formatter = NoteFormatter {
+                            note_names: genchain.note_names,
+                            sharp_sign: '#',
+                            flat_sign: 'b',
+                            cycle_sign: '*',
+                            order: AccidentalsOrder::SharpFlat,
+                        };
+                        let num_ji_steps = ji_ratio
+                            .as_ratio()
+                            .num_equal_steps_of_size(self.step_size)
+                            .round();
+                        let num_ups = f64::from(index) - num_ji_steps;
+                        let prefix = if num_ups >= 0.0 { "^" } else { "v" };
+
+                        format!(
+                            "{}{}",
+                            prefix.repeat(num_ups.abs() as usize),
+                            formatter.format_heji(&acc_format, &ji_ratio)
+                        )
+                    }
+                    None => self.get_note_name(index),
+                }
+            }
+        }
+    }
+
     pub fn get_accidentals(&self, index: u16) -> Accidentals {
         self.pergen.get_accidentals(&self.acc_format, index)
     }
@@ -209,6 +382,29 @@ impl IsomorphicLayout {
     }
 }
 
+/// Notation used by [`IsomorphicLayout::get_note_name_with`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NoteNotation {
+    /// Genchain notes with sharps and flats, see [`IsomorphicLayout::get_note_name`].
+    Accidentals,
+    /// Genchain notes with ups (`^`) and downs (`v`) each denoting one step of the tuning, see [`PerGen::get_ups_and_downs`].
+    UpsAndDowns,
+    /// HEJI-style name of the closest JI ratio within the given odd limit, see [`NoteFormatter::format_heji`].
+    ///
+    /// If the JI ratio is closer to a different degree of the tuning, the name is prefixed by ups (`^`) or downs (`v`) s.t. distinct degrees never share the same name.
+    Heji { odd_limit: u16 },
+}
+
+impl Display for NoteNotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoteNotation::Accidentals => write!(f, "Accidentals"),
+            NoteNotation::UpsAndDowns => write!(f, "Ups and downs"),
+            NoteNotation::Heji { odd_limit } => write!(f, "HEJI ({odd_limit}-odd-limit)"),
+        }
+    }
+}
+
 /// A descriptor for a consecutive genchain segment after decomposing a MOS into its color layers.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Layer {
@@ -225,16 +421,81 @@ pub enum Layer {
 /// Genchain used to derive note names, colors and step sizes for a given tuning.
 ///
 /// The name is to be understood as a representative for an entire family of temperaments that share the same genchain.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum Genchain {
-    /// Similar to [`Genchain::Meantone7`] but with 9 natural notes instead of 7.
+/// Besides the built-in presets (see [`Genchain::presets`]), custom genchains can be defined for any other temperament family.
+///
+/// # Examples
+///
+/// ```
+/// # use std::borrow::Cow;
+/// # use tune::layout::Genchain;
+/// # use tune::layout::IsomorphicLayout;
+/// # use tune::layout::ValExpression;
+/// # use tune::pitch::Ratio;
+/// // Seven subminor thirds (~7/6) are equal to one twelfth (3/1)
+/// let orwell = Genchain {
+///     name: Cow::Borrowed("Orwell"),
+///     period: ValExpression::new(&[1][..], 1),
+///     generator: ValExpression::new(&[0, 1][..], 7),
+///     note_names: Cow::Borrowed(&['B', 'D', 'F', 'H', 'A', 'C', 'E', 'G', 'J']),
+///     genchain_origin: 4,
+///     sharp_flat_signs: ['#', 'b'],
+///     negative_sharp_flat_signs: ['-', '+'],
+///     scale_names: [Cow::Borrowed("gramitonic"), Cow::Borrowed("semiquartal")],
+/// };
+///
+/// let step_size = Ratio::octave().divided_into_equal_steps(22);
+/// let layouts = IsomorphicLayout::find_by_step_size_with(step_size, &[orwell]);
+/// let orwell_layout = layouts.last().unwrap();
+///
+/// assert_eq!(orwell_layout.genchain().to_string(), "Orwell[9]");
+/// assert_eq!(orwell_layout.get_scale_name(), "gramitonic");
+/// assert_eq!(
+///     Vec::from_iter((0..22).map(|degree| orwell_layout.get_note_name(degree))).join(" "),
+///     "A A#/Bb B B# Cb C C#/Db D D# Eb E E#/Fb F F# Gb G G#/Hb H H# Jb J J#/Ab"
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Genchain {
+    /// Name of the temperament family, e.g. "Meantone".
+    pub name: Cow<'static, str>,
+
+    /// Number of steps of the period, e.g. the octave, in terms of the val of the tuning.
+    pub period: ValExpression,
+
+    /// Number of steps of the generator in terms of the val of the tuning, e.g. 3/1 minus 2/1 for the Meantone fifth.
+    pub generator: ValExpression,
+
+    /// Natural note letters in genchain order. The number of letters determines the size of the MOS.
+    pub note_names: Cow<'static, [char]>,
+
+    /// Index of the note letter located at degree 0, e.g. 3 for D in [F, C, G, D, A, E, B].
+    pub genchain_origin: u16,
+
+    /// Sharp and flat signs used when the MOS has positive or zero sharpness.
+    pub sharp_flat_signs: [char; 2],
+
+    /// Sharp and flat signs used when the MOS has negative sharpness.
+    pub negative_sharp_flat_signs: [char; 2],
+
+    /// Name of the MOS scale for positive and negative sharpness, e.g. "diatonic" and "antidiatonic".
+    pub scale_names: [Cow<'static, str>; 2],
+}
+
+impl Genchain {
+    /// Similar to [`Genchain::MEANTONE_7`] but with 9 natural notes instead of 7.
     ///
-    /// This genchain can be used when rather flat versions of 3/2 are involved and [`Genchain::Meantone7`] would result in a MOS with negative sharpness.
+    /// This genchain can be used when rather flat versions of 3/2 are involved and [`Genchain::MEANTONE_7`] would result in a MOS with negative sharpness.
     ///
     /// The generated notes are [ &hellip; Fb, B, G, C, H, D, Z, E, A, F, B#, &hellip; ].
     /// Due to the additional notes, the conventional relationships between interval names and just ratios no longer apply.
     /// For instance, a Mavila\[9\] major third will sound similar to a Meantone\[7\] minor third and a Mavila\[9\] minor fourth will sound similar to a Meantone\[7\] major third.
-    Mavila9,
+    pub const MAVILA_9: Genchain = Genchain::preset(
+        "Mavila",
+        ValExpression::new_const(&[-1, 1], 1),
+        &['B', 'φ', 'C', 'G', 'D', 'A', 'E', 'β', 'F'],
+        4,
+        ["armotonic", "balzano"],
+    );
 
     /// Octave-reduced genchain treating four fifths (3/2) to be equal to one major third.
     ///
@@ -244,14 +505,26 @@ pub enum Genchain {
     /// This results in standard music notation with G at one fifth above C and D at two fifths == 1/2 major third == 1 primary step above C.
     ///
     /// This genchain is compatible with other chain-of-fifth-based temperaments like Mavila and Superpyth.
-    Meantone7,
-
-    /// Similar to [`Genchain::Meantone7`] but with 5 natural notes instead of 7.
+    pub const MEANTONE_7: Genchain = Genchain::preset(
+        "Meantone",
+        ValExpression::new_const(&[-1, 1], 1),
+        &['F', 'C', 'G', 'D', 'A', 'E', 'B'],
+        3,
+        ["diatonic", "antidiatonic"],
+    );
+
+    /// Similar to [`Genchain::MEANTONE_7`] but with 5 natural notes instead of 7.
     ///
-    /// This genchain can be used when rather sharp versions of 3/2 are involved and [`Genchain::Meantone7`] would not result in a MOS.
+    /// This genchain can be used when rather sharp versions of 3/2 are involved and [`Genchain::MEANTONE_7`] would not result in a MOS.
     ///
     /// The generated notes are [ &hellip; Eb C G D A E C# &hellip; ].
-    Meantone5,
+    pub const MEANTONE_5: Genchain = Genchain::preset(
+        "Meantone",
+        ValExpression::new_const(&[-1, 1], 1),
+        &['C', 'G', 'D', 'A', 'E'],
+        2,
+        ["antipentic", "pentic"],
+    );
 
     /// Octave-reduced genchain treating three seconds to be equal to one major fourth (4/3).
     ///
@@ -259,50 +532,107 @@ pub enum Genchain {
     ///
     /// Unlike in meantone, the intervals E-F and F-G have the same size of one primary step while G-A is different which has some important consequences.
     /// For instance, a Porcupine\[8\] major third will sound similar to a Meantone\[7\] minor third and a Porcupine\[8\] minor fourth will sound similar to a Meantone\[7\] major third.
-    Porcupine8,
-
-    /// Similar to [`Genchain::Porcupine8`] but with 7 natural notes instead of 8 and with four seconds treated as being equal to one major fifth (3/2).
+    pub const PORCUPINE_8: Genchain = Genchain::preset(
+        "Porcupine",
+        ValExpression::new_const(&[2, -1], 3),
+        &['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H'],
+        3,
+        ["pine", "antipine"],
+    );
+
+    /// Similar to [`Genchain::PORCUPINE_8`] but with 7 natural notes instead of 8 and with four seconds treated as being equal to one major fifth (3/2).
     ///
-    /// This genchain can be used when rather sharp versions of 4/3 are involved and [`Genchain::Porcupine8`] would not result in a MOS.
+    /// This genchain can be used when rather sharp versions of 4/3 are involved and [`Genchain::PORCUPINE_8`] would not result in a MOS.
     ///
     /// The generated notes are [ &hellip; Gb A B C D E F G A# &hellip; ].
-    Tetracot7,
+    pub const TETRACOT_7: Genchain = Genchain::preset(
+        "Tetracot",
+        ValExpression::new_const(&[-1, 1], 4),
+        &['A', 'B', 'C', 'D', 'E', 'F', 'G'],
+        3,
+        ["archeotonic", "onyx"],
+    );
 
     /// Octave-reduced genchain treating six minor thirds to be equal to one major twelfth (3/1).
     ///
     /// The third is split into a major and minor second, corresponding to the *primary step* and *secondary step* sizes.
     ///
     /// The sixth is used to generate the notes [ &hellip; Eb C A F D B G E C# &hellip; ].
-    Hanson7,
-}
+    pub const HANSON_7: Genchain = Genchain::preset(
+        "Hanson",
+        ValExpression::new_const(&[6, -1], 6),
+        &['C', 'A', 'F', 'D', 'B', 'G', 'E'],
+        3,
+        ["smitonic", "mosh"],
+    );
+
+    /// Returns the built-in genchains used by [`IsomorphicLayout::find_by_step_size`].
+    ///
+    /// The genchains are sorted from highest to lowest sharpness within a group.
+    pub fn presets() -> &'static [Genchain] {
+        static PRESETS: [Genchain; 6] = [
+            Genchain::MAVILA_9,
+            Genchain::MEANTONE_7,
+            Genchain::MEANTONE_5,
+            Genchain::PORCUPINE_8,
+            Genchain::TETRACOT_7,
+            Genchain::HANSON_7,
+        ];
+        &PRESETS
+    }
 
-impl Genchain {
-    fn create_layout(self, val: &Val, b_val: bool) -> Option<IsomorphicLayout> {
-        let pergen = self.get_pergen(val)?;
-        let spec = self.get_parameters();
+    const fn preset(
+        name: &'static str,
+        generator: ValExpression,
+        note_names: &'static [char],
+        genchain_origin: u16,
+        scale_names: [&'static str; 2],
+    ) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            period: ValExpression::new_const(&[1], 1),
+            generator,
+            note_names: Cow::Borrowed(note_names),
+            genchain_origin,
+            sharp_flat_signs: ['#', 'b'],
+            negative_sharp_flat_signs: ['-', '+'],
+            scale_names: [Cow::Borrowed(scale_names[0]), Cow::Borrowed(scale_names[1])],
+        }
+    }
+
+    /// Returns the size of the MOS, i.e. the number of natural note letters.
+    pub fn mos_size(&self) -> usize {
+        self.note_names.len()
+    }
+
+    fn create_layout(&self, val: &Val, b_val: bool) -> Option<IsomorphicLayout> {
+        let period = self.period.evaluate(val).filter(|&period| period > 0)?;
+        let generator = self.generator.evaluate(val)?;
+        let pergen = PerGen::new(period, generator);
 
         let mos = pergen.get_moses().find(|mos| {
             usize::from(mos.num_primary_steps()) + usize::from(mos.num_secondary_steps())
-                == spec.genchain.len()
+                == self.mos_size()
         })?;
 
-        let (sharp_sign, flat_sign, order) = if mos.primary_step() >= mos.secondary_step() {
-            ('#', 'b', AccidentalsOrder::SharpFlat)
+        let ([sharp_sign, flat_sign], order) = if mos.primary_step() >= mos.secondary_step() {
+            (self.sharp_flat_signs, AccidentalsOrder::SharpFlat)
         } else {
-            ('-', '+', AccidentalsOrder::FlatSharp)
+            (self.negative_sharp_flat_signs, AccidentalsOrder::FlatSharp)
         };
 
         Some(IsomorphicLayout {
-            genchain: self,
+            genchain: self.clone(),
             b_val,
+            step_size: val.step_size(),
             pergen,
             mos,
             acc_format: AccidentalsFormat {
                 num_symbols: u16::try_from(mos.num_steps()).ok()?,
-                genchain_origin: spec.genchain_origin,
+                genchain_origin: self.genchain_origin,
             },
             formatter: NoteFormatter {
-                note_names: spec.genchain.into(),
+                note_names: self.note_names.clone(),
                 sharp_sign,
                 flat_sign,
                 cycle_sign: '*',
@@ -310,84 +640,85 @@ impl Genchain {
             },
         })
     }
+}
 
-    fn get_pergen(&self, val: &Val) -> Option<PerGen> {
-        let values = val.values();
-        let octave = values[0];
-        let tritave = values[1];
+impl Display for Genchain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]", self.name, self.mos_size())
+    }
+}
 
-        Some(match self {
-            Genchain::Mavila9 | Genchain::Meantone7 | Genchain::Meantone5 => {
-                let fifth = tritave.checked_sub(octave)?;
-                PerGen::new(octave, fifth)
-            }
-            Genchain::Porcupine8 => {
-                let third_fourth = exact_div(octave.checked_mul(2)?.checked_sub(tritave)?, 3)?;
-                PerGen::new(octave, third_fourth)
-            }
-            Genchain::Tetracot7 => {
-                let quarter_fifth = exact_div(tritave.checked_sub(octave)?, 4)?;
-                PerGen::new(octave, quarter_fifth)
-            }
-            Genchain::Hanson7 => {
-                let sixth_twelfth = exact_div(tritave, 6)?;
-                let bright_generator = octave.checked_sub(sixth_twelfth)?;
-                PerGen::new(octave, bright_generator)
-            }
-        })
+/// A number of steps expressed as a linear combination of the values of a [`Val`], divided by `divisor`.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::layout::ValExpression;
+/// # use tune::pitch::Ratio;
+/// # use tune::temperament::Val;
+/// // One quarter of the fifth (3/1 minus 2/1)
+/// let quarter_fifth = ValExpression::new(&[-1, 1][..], 4);
+///
+/// let val_of_41_edo = Val::patent(Ratio::octave().divided_into_equal_steps(41), 5);
+/// assert_eq!(quarter_fifth.evaluate(&val_of_41_edo), Some(6));
+///
+/// // 12-EDO: The fifth (7 steps) is not divisible by 4
+/// let val_of_12_edo = Val::patent(Ratio::octave().divided_into_equal_steps(12), 5);
+/// assert_eq!(quarter_fifth.evaluate(&val_of_12_edo), None);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ValExpression {
+    /// Coefficients by which the values of the [`Val`] are multiplied, e.g. [-1, 1] for 3/1 minus 2/1.
+    pub coefficients: Cow<'static, [i16]>,
+
+    /// Number by which the linear combination is divided.
+    pub divisor: u16,
+}
+
+impl ValExpression {
+    /// Creates a new [`ValExpression`] from the given `coefficients` and `divisor`.
+    pub fn new(coefficients: impl Into<Cow<'static, [i16]>>, divisor: u16) -> Self {
+        Self {
+            coefficients: coefficients.into(),
+            divisor,
+        }
     }
 
-    fn get_parameters(self) -> GenchainParameters {
-        match self {
-            Genchain::Mavila9 => GenchainParameters {
-                genchain: &['B', 'φ', 'C', 'G', 'D', 'A', 'E', 'β', 'F'],
-                genchain_origin: 4,
-            },
-            Genchain::Meantone7 => GenchainParameters {
-                genchain: &['F', 'C', 'G', 'D', 'A', 'E', 'B'],
-                genchain_origin: 3,
-            },
-            Genchain::Meantone5 => GenchainParameters {
-                genchain: &['C', 'G', 'D', 'A', 'E'],
-                genchain_origin: 2,
-            },
-            Genchain::Porcupine8 => GenchainParameters {
-                genchain: &['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H'],
-                genchain_origin: 3,
-            },
-            Genchain::Tetracot7 => GenchainParameters {
-                genchain: &['A', 'B', 'C', 'D', 'E', 'F', 'G'],
-                genchain_origin: 3,
-            },
-            Genchain::Hanson7 => GenchainParameters {
-                genchain: &['C', 'A', 'F', 'D', 'B', 'G', 'E'],
-                genchain_origin: 3,
-            },
+    const fn new_const(coefficients: &'static [i16], divisor: u16) -> Self {
+        Self {
+            coefficients: Cow::Borrowed(coefficients),
+            divisor,
         }
     }
-}
 
-fn exact_div(numer: u16, denom: u16) -> Option<u16> {
-    (numer % denom == 0).then_some(numer / denom)
-}
+    /// Evaluates the expression for the given [`Val`].
+    ///
+    /// [`None`] is returned if the [`Val`] has too few values, if the result is negative or if the linear combination is not divisible by the divisor.
+    pub fn evaluate(&self, val: &Val) -> Option<u16> {
+        let values = val.values();
+        if self.coefficients.len() > values.len() || self.divisor == 0 {
+            return None;
+        }
 
-impl Display for Genchain {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let display_name = match self {
-            Genchain::Mavila9 => "Mavila[9]",
-            Genchain::Meantone7 => "Meantone[7]",
-            Genchain::Meantone5 => "Meantone[5]",
-            Genchain::Porcupine8 => "Porcupine[8]",
-            Genchain::Tetracot7 => "Tetracot[7]",
-            Genchain::Hanson7 => "Hanson[7]",
-        };
-        write!(f, "{display_name}")
+        let linear_combination: i32 = self
+            .coefficients
+            .iter()
+            .zip(values)
+            .map(|(&coefficient, &value)| i32::from(coefficient) * i32::from(value))
+            .sum();
+
+        let divisor = i32::from(self.divisor);
+        (linear_combination % divisor == 0)
+            .then(|| u16::try_from(linear_combination / divisor).ok())
+            .flatten()
     }
-}
 
-struct GenchainParameters {
-    genchain: &'static [char],
-    genchain_origin: u16,
+    fn prime_limit(&self) -> u8 {
+        math::U8_PRIMES
+            .get(self.coefficients.len().saturating_sub(1))
+            .copied()
+            .unwrap_or(u8::MAX)
+    }
 }
 
 #[cfg(test)]
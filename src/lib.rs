@@ -2,6 +2,8 @@
 
 mod parse;
 
+pub mod consonance;
+pub mod ji;
 pub mod key;
 pub mod layout;
 pub mod math;
@@ -12,5 +14,6 @@ pub mod pergen;
 pub mod pitch;
 pub mod scala;
 pub mod temperament;
+pub mod tun;
 pub mod tuner;
 pub mod tuning;
@@ -157,6 +157,26 @@ pub fn gcd_u16(mut x: u16, mut y: u16) -> u16 {
     x.max(1)
 }
 
+/// Calculates the greatest common divisor of two `u64`s.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::math;
+/// assert_eq!(math::gcd_u64(531441, 524288), 1);
+/// assert_eq!(math::gcd_u64(3 << 40, 9 << 20), 3 << 20);
+/// assert_eq!(math::gcd_u64(35, 0), 35);
+/// assert_eq!(math::gcd_u64(0, 0), 1);
+/// ```
+pub fn gcd_u64(mut x: u64, mut y: u64) -> u64 {
+    while y != 0 {
+        let t = y;
+        y = x % y;
+        x = t;
+    }
+    x.max(1)
+}
+
 /// Removes all powers of two from a `u16`.
 ///
 /// # Examples
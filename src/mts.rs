@@ -9,7 +9,7 @@ use std::{collections::HashSet, fmt::Debug, iter};
 use crate::{
     key::PianoKey,
     midi::{ChannelMessage, ChannelMessageType},
-    note::NoteLetter,
+    note::{Note, NoteLetter},
     pitch::{Pitch, Pitched, Ratio},
     tuning::KeyboardMapping,
 };
@@ -24,20 +24,31 @@ const SYSEX_RT: u8 = 0x7f;
 const SYSEX_END: u8 = 0xf7;
 
 // MIDI Tuning Standard
+// 08 00 Bulk Dump Request
+// 08 01 Bulk Tuning Dump
 // 08 02 Single Note Tuning Change
+// 08 03 Bulk Dump Request with Bank Select
+// 08 04 Key-Based Tuning Dump
 // 08 07 Single Note Tuning Change with Bank Select
 // 08 08 Scale/Octave Tuning, 1 byte format
 // 08 09 Scale/Octave Tuning, 2 byte format
 
 const MIDI_TUNING_STANDARD: u8 = 0x08;
 
+const BULK_DUMP_REQUEST: u8 = 0x00;
+const BULK_TUNING_DUMP: u8 = 0x01;
 const SINGLE_NOTE_TUNING_CHANGE: u8 = 0x02;
+const BULK_DUMP_REQUEST_WITH_BANK_SELECT: u8 = 0x03;
+const KEY_BASED_TUNING_DUMP: u8 = 0x04;
 const SINGLE_NOTE_TUNING_CHANGE_WITH_BANK_SELECT: u8 = 0x07;
 const SCALE_OCTAVE_TUNING_1_BYTE_FORMAT: u8 = 0x08;
 const SCALE_OCTAVE_TUNING_2_BYTE_FORMAT: u8 = 0x09;
 
 const DEVICE_ID_BROADCAST: u8 = 0x7f;
 
+const TUNING_NAME_LENGTH: usize = 16;
+const NO_CHANGE: [u8; 3] = [0x7f, 0x7f, 0x7f];
+
 const U7_MASK: u16 = (1 << 7) - 1;
 const U14_UPPER_BOUND_AS_F64: f64 = (1 << 14) as f64;
 
@@ -215,29 +226,12 @@ impl SingleNoteTuningChangeMessage {
         let mut out_of_range_notes = Vec::new();
 
         for tuning_change in tuning_changes {
-            let approximation = tuning_change.target_pitch.find_in_tuning(());
-            let mut target_note = approximation.approx_value;
-
-            let mut detune_in_u14_resolution =
-                (approximation.deviation.as_semitones() * U14_UPPER_BOUND_AS_F64).round();
-
-            // Make sure that the detune range is [0c..100c] instead of [-50c..50c]
-            if detune_in_u14_resolution < 0.0 {
-                target_note = target_note.plus_semitones(-1);
-                detune_in_u14_resolution += U14_UPPER_BOUND_AS_F64;
-            }
-
             if let (Some(source), Some(target)) = (
                 tuning_change.key.checked_midi_number(),
-                target_note.checked_midi_number(),
+                encode_pitch(tuning_change.target_pitch),
             ) {
-                let pitch_msb = (detune_in_u14_resolution as u16 >> 7) as u8;
-                let pitch_lsb = (detune_in_u14_resolution as u16 & U7_MASK) as u8;
-
                 sysex_tuning_list.push(source);
-                sysex_tuning_list.push(target);
-                sysex_tuning_list.push(pitch_msb);
-                sysex_tuning_list.push(pitch_lsb);
+                sysex_tuning_list.extend(target);
 
                 num_retuned_notes += 1;
             } else {
@@ -578,7 +572,7 @@ pub struct ScaleOctaveTuningOptions {
 /// 1-byte or 2-byte form of the *Scale/Octave Tuning* message.
 ///
 /// The 1-byte form supports values in the range [-64cents..63cents], the 2-byte form supports values in the range [-100cents..100cents).
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ScaleOctaveTuningFormat {
     OneByte,
     TwoByte,
@@ -747,7 +741,7 @@ pub enum ScaleOctaveTuningError {
 }
 
 /// The detuning per pitch class within an octave.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct ScaleOctaveTuning {
     pub c: Ratio,
     pub csh: Ratio,
@@ -783,7 +777,7 @@ impl ScaleOctaveTuning {
 }
 
 /// Channels to be affected by the *Scale/Octave Tuning* message.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Channels {
     All,
     Some(HashSet<u8>),
@@ -801,6 +795,813 @@ impl From<u8> for Channels {
     }
 }
 
+/// Properties of the generated *Bulk Tuning Dump* message.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::mts::BulkTuningDumpMessage;
+/// # use tune::mts::BulkTuningDumpOptions;
+/// # use tune::mts::SingleNoteTuningChange;
+/// # use tune::note::NoteLetter;
+/// # use tune::pitch::Pitch;
+/// let a4 = NoteLetter::A.in_octave(4).as_piano_key();
+/// let target_pitch = Pitch::from_hz(445.0);
+///
+/// let tuning_change = SingleNoteTuningChange { key: a4, target_pitch };
+///
+/// // Use default options
+/// let options = BulkTuningDumpOptions {
+///     name: "Test".to_owned(),
+///     ..Default::default()
+/// };
+///
+/// let tuning_message = BulkTuningDumpMessage::from_tuning_changes(
+///     &options,
+///     std::iter::once(tuning_change),
+/// )
+/// .unwrap();
+///
+/// let sysex_bytes = tuning_message.sysex_bytes();
+/// assert_eq!(sysex_bytes.len(), 408);
+/// assert_eq!(
+///     sysex_bytes[..22],
+///     [0xf0, 0x7e, 0x7f, 0x08, 0x01, // Bulk Tuning Dump
+///      0,                            // Tuning program
+///      b'T', b'e', b's', b't', b' ', b' ', b' ', b' ', // Tuning name
+///      b' ', b' ', b' ', b' ', b' ', b' ', b' ', b' ']
+/// );
+/// assert_eq!(sysex_bytes[22 + 3 * 68..][..9], [0x7f, 0x7f, 0x7f, 69, 25, 5, 0x7f, 0x7f, 0x7f]);
+/// assert_eq!(sysex_bytes[406..], [24, 0xf7]); // Checksum / sysex end
+///
+/// // Use custom options
+/// let options = BulkTuningDumpOptions {
+///     device_id: 55,
+///     tuning_program: 66,
+///     with_bank_select: Some(77),
+///     name: "Test".to_owned(),
+/// };
+///
+/// let tuning_message = BulkTuningDumpMessage::from_tuning_changes(
+///     &options,
+///     std::iter::once(tuning_change),
+/// )
+/// .unwrap();
+///
+/// let sysex_bytes = tuning_message.sysex_bytes();
+/// assert_eq!(sysex_bytes.len(), 409);
+/// assert_eq!(
+///     sysex_bytes[..8],
+///     [0xf0, 0x7e, 55, 0x08, 0x04, // Key-Based Tuning Dump
+///      77, 66,                     // Tuning bank / tuning program
+///      b'T']                       // Tuning name
+/// );
+/// assert_eq!(sysex_bytes[23 + 3 * 69..][..3], [69, 25, 5]);
+/// assert_eq!(sysex_bytes[407..], [90, 0xf7]); // Checksum / sysex end
+/// ```
+#[derive(Clone, Debug)]
+pub struct BulkTuningDumpOptions {
+    /// Specifies the device ID (defaults to broadcast/0x7f).
+    pub device_id: u8,
+
+    /// Specifies the tuning program to be affected (defaults to 0).
+    pub tuning_program: u8,
+
+    /// If given, generate a *Key-Based Tuning Dump* message for the given tuning bank.
+    pub with_bank_select: Option<u8>,
+
+    /// The name of the tuning, at most 16 ASCII characters (defaults to an empty name).
+    pub name: String,
+}
+
+impl Default for BulkTuningDumpOptions {
+    fn default() -> Self {
+        Self {
+            device_id: DEVICE_ID_BROADCAST,
+            tuning_program: 0,
+            with_bank_select: None,
+            name: String::new(),
+        }
+    }
+}
+
+/// Retunes all 128 MIDI notes of a tuning program using the *Bulk Tuning Dump* message format.
+#[derive(Clone, Debug)]
+pub struct BulkTuningDumpMessage {
+    sysex_call: Vec<u8>,
+    out_of_range_notes: Vec<SingleNoteTuningChange>,
+}
+
+impl BulkTuningDumpMessage {
+    /// Creates a [`BulkTuningDumpMessage`] from the provided `tuning`.
+    ///
+    /// All MIDI keys are considered. Keys without a pitch are marked as "no change".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::mts::BulkTuningDumpMessage;
+    /// # use tune::mts::MtsMessage;
+    /// # use tune::mts::MtsPayload;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::KbmRoot;
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder()
+    ///     .push_ratio(Ratio::octave().divided_into_equal_steps(7))
+    ///     .build()
+    ///     .unwrap();
+    /// let kbm = KbmRoot::from(NoteLetter::D.in_octave(4)).to_kbm();
+    ///
+    /// let tuning_message = BulkTuningDumpMessage::from_tuning(
+    ///     &Default::default(),
+    ///     (scl, kbm),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(tuning_message.out_of_range_notes().len(), 53);
+    ///
+    /// let decoded = MtsMessage::decode(tuning_message.sysex_bytes()).unwrap();
+    /// let MtsPayload::BulkTuningDump { tuning_changes, .. } = decoded.payload else {
+    ///     panic!("Unexpected payload");
+    /// };
+    /// assert_eq!(tuning_changes.len(), 75);
+    /// ```
+    pub fn from_tuning(
+        options: &BulkTuningDumpOptions,
+        tuning: impl KeyboardMapping<PianoKey>,
+    ) -> Result<Self, BulkTuningDumpError> {
+        let tuning_changes = (0..128).map(PianoKey::from_midi_number).flat_map(|key| {
+            tuning
+                .maybe_pitch_of(key)
+                .map(|target_pitch| SingleNoteTuningChange { key, target_pitch })
+        });
+        Self::from_tuning_changes(options, tuning_changes)
+    }
+
+    /// Creates a [`BulkTuningDumpMessage`] from the provided `tuning_changes`.
+    ///
+    /// Keys that are not affected by any tuning change are marked as "no change".
+    /// If a key is affected multiple times, the last tuning change wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::mts::BulkTuningDumpMessage;
+    /// # use tune::mts::SingleNoteTuningChange;
+    /// # use tune::key::PianoKey;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// let key = NoteLetter::A.in_octave(4).as_piano_key();
+    ///
+    /// let good = SingleNoteTuningChange { key, target_pitch: Pitch::from_hz(445.0) };
+    /// let too_low = SingleNoteTuningChange { key, target_pitch: Pitch::from_hz(1.0) };
+    /// let bad_key = SingleNoteTuningChange {
+    ///     key: PianoKey::from_midi_number(128),
+    ///     target_pitch: Pitch::from_hz(445.0),
+    /// };
+    ///
+    /// let tuning_message = BulkTuningDumpMessage::from_tuning_changes(
+    ///     &Default::default(), [good, too_low, bad_key]
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(tuning_message.sysex_bytes().len(), 408);
+    /// assert_eq!(tuning_message.out_of_range_notes(), [too_low, bad_key]);
+    /// ```
+    pub fn from_tuning_changes(
+        options: &BulkTuningDumpOptions,
+        tuning_changes: impl IntoIterator<Item = SingleNoteTuningChange>,
+    ) -> Result<Self, BulkTuningDumpError> {
+        if options.device_id >= 128 {
+            return Err(BulkTuningDumpError::DeviceIdOutOfRange);
+        }
+        if options.tuning_program >= 128 {
+            return Err(BulkTuningDumpError::TuningProgramOutOfRange);
+        }
+        if options
+            .with_bank_select
+            .filter(|&tuning_bank| tuning_bank >= 128)
+            .is_some()
+        {
+            return Err(BulkTuningDumpError::TuningBankNumberOutOfRange);
+        }
+        if options.name.len() > TUNING_NAME_LENGTH
+            || !options
+                .name
+                .bytes()
+                .all(|byte| (0x20..0x7f).contains(&byte))
+        {
+            return Err(BulkTuningDumpError::InvalidName);
+        }
+
+        let mut tuning_data = [NO_CHANGE; 128];
+        let mut out_of_range_notes = Vec::new();
+
+        for tuning_change in tuning_changes {
+            if let (Some(source), Some(target)) = (
+                tuning_change.key.checked_midi_number(),
+                encode_pitch(tuning_change.target_pitch),
+            ) {
+                tuning_data[usize::from(source)] = target;
+            } else {
+                out_of_range_notes.push(tuning_change);
+            }
+        }
+
+        let mut sysex_call = Vec::with_capacity(409);
+
+        sysex_call.push(SYSEX_START);
+        sysex_call.push(SYSEX_NON_RT);
+        sysex_call.push(options.device_id);
+        sysex_call.push(MIDI_TUNING_STANDARD);
+        sysex_call.push(if options.with_bank_select.is_some() {
+            KEY_BASED_TUNING_DUMP
+        } else {
+            BULK_TUNING_DUMP
+        });
+        if let Some(with_bank_select) = options.with_bank_select {
+            sysex_call.push(with_bank_select);
+        }
+        sysex_call.push(options.tuning_program);
+        sysex_call.extend(
+            options
+                .name
+                .bytes()
+                .chain(iter::repeat(b' '))
+                .take(TUNING_NAME_LENGTH),
+        );
+        sysex_call.extend(tuning_data.iter().flatten());
+        sysex_call.push(checksum(&sysex_call[1..]));
+        sysex_call.push(SYSEX_END);
+
+        Ok(BulkTuningDumpMessage {
+            sysex_call,
+            out_of_range_notes,
+        })
+    }
+
+    /// Returns the tuning message conforming to the MIDI tuning standard.
+    pub fn sysex_bytes(&self) -> &[u8] {
+        &self.sysex_call
+    }
+
+    /// Return notes whose key or target pitch is not representable by the tuning message.
+    pub fn out_of_range_notes(&self) -> &[SingleNoteTuningChange] {
+        &self.out_of_range_notes
+    }
+}
+
+/// Creating a [`BulkTuningDumpMessage`] failed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BulkTuningDumpError {
+    /// The device ID is greater than 127.
+    DeviceIdOutOfRange,
+
+    /// The tuning program number is greater than 127.
+    TuningProgramOutOfRange,
+
+    /// The tuning bank number is greater than 127.
+    TuningBankNumberOutOfRange,
+
+    /// The tuning name is longer than 16 characters or contains non-printable or non-ASCII characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::iter;
+    /// # use tune::mts::BulkTuningDumpError;
+    /// # use tune::mts::BulkTuningDumpMessage;
+    /// # use tune::mts::BulkTuningDumpOptions;
+    /// let create_tuning_message_with_name = |name: &str| {
+    ///     let options = BulkTuningDumpOptions {
+    ///         name: name.to_owned(),
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     BulkTuningDumpMessage::from_tuning_changes(&options, iter::empty())
+    /// };
+    ///
+    /// assert!(create_tuning_message_with_name("Sixteen chars ok").is_ok());
+    /// assert_eq!(
+    ///     create_tuning_message_with_name("Seventeen chars!!").unwrap_err(),
+    ///     BulkTuningDumpError::InvalidName
+    /// );
+    /// assert_eq!(
+    ///     create_tuning_message_with_name("31-EDO ½").unwrap_err(),
+    ///     BulkTuningDumpError::InvalidName
+    /// );
+    /// ```
+    InvalidName,
+}
+
+/// Creates a *Bulk Dump Request* message asking the device to send a *Bulk Tuning Dump* of the given tuning program.
+///
+/// If a `tuning_bank` is given, a *Bulk Dump Request with Bank Select* message is created, to be answered by a *Key-Based Tuning Dump*.
+/// Returns [`None`] if any argument exceeds the range [0..128).
+///
+/// # Examples
+///
+/// ```
+/// # use tune::mts;
+/// # use tune::mts::MtsMessage;
+/// # use tune::mts::MtsPayload;
+/// assert_eq!(
+///     mts::bulk_dump_request(0x7f, None, 5).unwrap(),
+///     [0xf0, 0x7e, 0x7f, 0x08, 0x00, 5, 0xf7]
+/// );
+/// assert_eq!(
+///     mts::bulk_dump_request(55, Some(3), 5).unwrap(),
+///     [0xf0, 0x7e, 55, 0x08, 0x03, 3, 5, 0xf7]
+/// );
+/// assert_eq!(mts::bulk_dump_request(55, Some(128), 5), None);
+///
+/// let decoded = MtsMessage::decode(&mts::bulk_dump_request(55, Some(3), 5).unwrap()).unwrap();
+/// assert_eq!(
+///     decoded.payload,
+///     MtsPayload::BulkDumpRequest { tuning_bank: Some(3), tuning_program: 5 }
+/// );
+/// ```
+pub fn bulk_dump_request(
+    device_id: u8,
+    tuning_bank: Option<u8>,
+    tuning_program: u8,
+) -> Option<Vec<u8>> {
+    if device_id >= 128
+        || tuning_program >= 128
+        || tuning_bank
+            .filter(|&tuning_bank| tuning_bank >= 128)
+            .is_some()
+    {
+        return None;
+    }
+
+    let mut sysex_call = Vec::with_capacity(8);
+
+    sysex_call.push(SYSEX_START);
+    sysex_call.push(SYSEX_NON_RT);
+    sysex_call.push(device_id);
+    sysex_call.push(MIDI_TUNING_STANDARD);
+    sysex_call.push(if tuning_bank.is_some() {
+        BULK_DUMP_REQUEST_WITH_BANK_SELECT
+    } else {
+        BULK_DUMP_REQUEST
+    });
+    sysex_call.extend(tuning_bank);
+    sysex_call.push(tuning_program);
+    sysex_call.push(SYSEX_END);
+
+    Some(sysex_call)
+}
+
+/// A MIDI Tuning Standard message decoded from raw SysEx bytes.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::mts::MtsMessage;
+/// # use tune::mts::MtsPayload;
+/// # use tune::mts::SingleNoteTuningChangeMessage;
+/// # use tune::mts::SingleNoteTuningChangeOptions;
+/// # use tune::scala::KbmRoot;
+/// # use tune::scala::Scl;
+/// # use tune::key::PianoKey;
+/// # use tune::note::Note;
+/// let scl = Scl::builder().push_cents(120.0).build().unwrap();
+/// let kbm_root = KbmRoot::from(Note::from_midi_number(62));
+///
+/// let options = SingleNoteTuningChangeOptions {
+///     realtime: false,
+///     device_id: 55,
+///     tuning_program: 66,
+///     with_bank_select: Some(77),
+/// };
+///
+/// let tuning_message = SingleNoteTuningChangeMessage::from_tuning(
+///     &options,
+///     (scl, kbm_root.to_kbm()),
+///     (60..64).map(PianoKey::from_midi_number),
+/// )
+/// .unwrap();
+///
+/// let sysex_bytes = tuning_message.sysex_bytes().next().unwrap();
+/// let decoded = MtsMessage::decode(sysex_bytes).unwrap();
+///
+/// assert!(!decoded.realtime);
+/// assert_eq!(decoded.device_id, 55);
+///
+/// let MtsPayload::SingleNoteTuningChange { tuning_bank, tuning_program, tuning_changes } = decoded.payload else {
+///     panic!("Unexpected payload");
+/// };
+/// assert_eq!(tuning_bank, Some(77));
+/// assert_eq!(tuning_program, 66);
+/// assert_eq!(tuning_changes.len(), 4);
+/// assert_eq!(tuning_changes[3].key, PianoKey::from_midi_number(63));
+/// assert_approx_eq!(tuning_changes[3].target_pitch.as_hz(), 314.742, 1e-3);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MtsMessage {
+    /// Whether the message is a realtime (`0x7f`) or non-realtime (`0x7e`) SysEx message.
+    pub realtime: bool,
+
+    /// The device ID of the message (0x7f = broadcast).
+    pub device_id: u8,
+
+    /// The tuning data of the message.
+    pub payload: MtsPayload,
+}
+
+/// The tuning data of an [`MtsMessage`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum MtsPayload {
+    /// *Single Note Tuning Change*, optionally with bank select.
+    ///
+    /// Entries marked as "no change" (`0x7f 0x7f 0x7f`) are skipped.
+    SingleNoteTuningChange {
+        tuning_bank: Option<u8>,
+        tuning_program: u8,
+        tuning_changes: Vec<SingleNoteTuningChange>,
+    },
+
+    /// *Scale/Octave Tuning* in 1-byte or 2-byte format.
+    ScaleOctaveTuning {
+        format: ScaleOctaveTuningFormat,
+        channels: Channels,
+        octave_tuning: ScaleOctaveTuning,
+    },
+
+    /// *Bulk Dump Request*, optionally with bank select.
+    BulkDumpRequest {
+        tuning_bank: Option<u8>,
+        tuning_program: u8,
+    },
+
+    /// *Bulk Tuning Dump*, or *Key-Based Tuning Dump* if a tuning bank is given.
+    ///
+    /// The tuning name is stripped of its trailing spaces. Entries marked as "no change" (`0x7f 0x7f 0x7f`) are skipped.
+    BulkTuningDump {
+        tuning_bank: Option<u8>,
+        tuning_program: u8,
+        name: String,
+        tuning_changes: Vec<SingleNoteTuningChange>,
+    },
+}
+
+impl MtsMessage {
+    /// Decodes a single SysEx message, including the leading `0xf0` and the trailing `0xf7` byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::mts::BulkTuningDumpMessage;
+    /// # use tune::mts::Channels;
+    /// # use tune::mts::MtsDecodeError;
+    /// # use tune::mts::MtsMessage;
+    /// # use tune::mts::MtsPayload;
+    /// # use tune::mts::ScaleOctaveTuningFormat;
+    /// let sysex_bytes = [
+    ///     0xf0, 0x7f, 0x7f, 0x08, 0x09,                // RT Scale/Octave Tuning (2-Byte)
+    ///     0b00000010, 0b00100100, 0b01001001,          // Channel bits
+    ///     70, 51, 0, 0, 127, 127, 64, 0, 64, 0, 64, 0, // Tuning changes (C - F)
+    ///     64, 0, 64, 0, 64, 0, 64, 0, 64, 0, 64, 0,    // Tuning changes (F# - B)
+    ///     0xf7,                                        // Sysex end
+    /// ];
+    ///
+    /// let decoded = MtsMessage::decode(&sysex_bytes).unwrap();
+    /// assert!(decoded.realtime);
+    ///
+    /// let MtsPayload::ScaleOctaveTuning { format, channels, octave_tuning } = decoded.payload else {
+    ///     panic!("Unexpected payload");
+    /// };
+    /// assert_eq!(format, ScaleOctaveTuningFormat::TwoByte);
+    /// assert_eq!(channels, Channels::Some(HashSet::from([0, 3, 6, 9, 12, 15])));
+    /// assert_approx_eq!(octave_tuning.c.as_cents(), 9.997559);
+    /// assert_approx_eq!(octave_tuning.csh.as_cents(), -100.0);
+    /// assert_approx_eq!(octave_tuning.d.as_cents(), 99.987793);
+    /// assert_approx_eq!(octave_tuning.b.as_cents(), 0.0);
+    ///
+    /// // Errors
+    /// assert_eq!(
+    ///     MtsMessage::decode(&[0x90, 60, 100]),
+    ///     Err(MtsDecodeError::MissingSysexStart)
+    /// );
+    /// assert_eq!(
+    ///     MtsMessage::decode(&sysex_bytes[..sysex_bytes.len() - 1]),
+    ///     Err(MtsDecodeError::MissingSysexEnd)
+    /// );
+    /// assert_eq!(
+    ///     MtsMessage::decode(&[0xf0, 0x43, 0x10, 0x4c, 0xf7]),
+    ///     Err(MtsDecodeError::NotUniversalSysex(0x43))
+    /// );
+    /// assert_eq!(
+    ///     MtsMessage::decode(&[0xf0, 0x7e, 0x7f, 0x09, 0x01, 0xf7]),
+    ///     Err(MtsDecodeError::NotMidiTuningStandard(0x09))
+    /// );
+    /// assert_eq!(
+    ///     MtsMessage::decode(&[0xf0, 0x7e, 0x7f, 0x08, 0x05, 0xf7]),
+    ///     Err(MtsDecodeError::UnsupportedMessageType(0x05))
+    /// );
+    /// assert_eq!(
+    ///     MtsMessage::decode(&sysex_bytes[..20]
+    ///         .iter()
+    ///         .copied()
+    ///         .chain([0xf7])
+    ///         .collect::<Vec<_>>()),
+    ///     Err(MtsDecodeError::UnexpectedLength { expected: 33, actual: 21 })
+    /// );
+    /// assert_eq!(
+    ///     MtsMessage::decode(&[0xf0, 0x7e, 0x80, 0x08, 0x02, 0, 0, 0xf7]),
+    ///     Err(MtsDecodeError::DataByteOutOfRange { position: 2 })
+    /// );
+    ///
+    /// let mut bulk_dump = BulkTuningDumpMessage::from_tuning_changes(&Default::default(), [])
+    ///     .unwrap()
+    ///     .sysex_bytes()
+    ///     .to_vec();
+    /// bulk_dump[406] ^= 1;
+    /// assert_eq!(
+    ///     MtsMessage::decode(&bulk_dump),
+    ///     Err(MtsDecodeError::ChecksumMismatch { expected: 8, actual: 9 })
+    /// );
+    /// ```
+    pub fn decode(sysex_bytes: &[u8]) -> Result<Self, MtsDecodeError> {
+        let Some(body) = sysex_bytes.strip_prefix(&[SYSEX_START]) else {
+            return Err(MtsDecodeError::MissingSysexStart);
+        };
+        let Some(body) = body.strip_suffix(&[SYSEX_END]) else {
+            return Err(MtsDecodeError::MissingSysexEnd);
+        };
+        if let Some(position) = body.iter().position(|&byte| byte > 0x7f) {
+            return Err(MtsDecodeError::DataByteOutOfRange {
+                position: position + 1,
+            });
+        }
+
+        let realtime = match body.first() {
+            Some(&SYSEX_RT) => true,
+            Some(&SYSEX_NON_RT) => false,
+            Some(&other) => return Err(MtsDecodeError::NotUniversalSysex(other)),
+            None => return Err(MtsDecodeError::UnexpectedEnd),
+        };
+
+        let &[_, device_id, sub_id_1, sub_id_2, ref data @ ..] = body else {
+            return Err(MtsDecodeError::UnexpectedEnd);
+        };
+        if sub_id_1 != MIDI_TUNING_STANDARD {
+            return Err(MtsDecodeError::NotMidiTuningStandard(sub_id_1));
+        }
+
+        let check_length = |expected: usize| {
+            if sysex_bytes.len() == expected {
+                Ok(())
+            } else {
+                Err(MtsDecodeError::UnexpectedLength {
+                    expected,
+                    actual: sysex_bytes.len(),
+                })
+            }
+        };
+
+        let payload = match sub_id_2 {
+            SINGLE_NOTE_TUNING_CHANGE | SINGLE_NOTE_TUNING_CHANGE_WITH_BANK_SELECT => {
+                let (tuning_bank, data) = match sub_id_2 {
+                    SINGLE_NOTE_TUNING_CHANGE => (None, data),
+                    _ => match data {
+                        [tuning_bank, data @ ..] => (Some(*tuning_bank), data),
+                        [] => return Err(MtsDecodeError::UnexpectedEnd),
+                    },
+                };
+                let &[tuning_program, num_changes, ref tuning_list @ ..] = data else {
+                    return Err(MtsDecodeError::UnexpectedEnd);
+                };
+                check_length(sysex_bytes.len() - tuning_list.len() + 4 * usize::from(num_changes))?;
+
+                let tuning_changes = tuning_list
+                    .chunks(4)
+                    .filter_map(|chunk| {
+                        decode_pitch(&chunk[1..]).map(|target_pitch| SingleNoteTuningChange {
+                            key: PianoKey::from_midi_number(chunk[0]),
+                            target_pitch,
+                        })
+                    })
+                    .collect();
+
+                MtsPayload::SingleNoteTuningChange {
+                    tuning_bank,
+                    tuning_program,
+                    tuning_changes,
+                }
+            }
+            SCALE_OCTAVE_TUNING_1_BYTE_FORMAT | SCALE_OCTAVE_TUNING_2_BYTE_FORMAT => {
+                let format = match sub_id_2 {
+                    SCALE_OCTAVE_TUNING_1_BYTE_FORMAT => ScaleOctaveTuningFormat::OneByte,
+                    _ => ScaleOctaveTuningFormat::TwoByte,
+                };
+                let num_bytes_per_value = match format {
+                    ScaleOctaveTuningFormat::OneByte => 1,
+                    ScaleOctaveTuningFormat::TwoByte => 2,
+                };
+                check_length(9 + 12 * num_bytes_per_value)?;
+
+                let channel_bits =
+                    u32::from(data[0]) << 14 | u32::from(data[1]) << 7 | u32::from(data[2]);
+                let channels = match channel_bits {
+                    0xffff => Channels::All,
+                    bits => {
+                        Channels::Some((0..16).filter(|channel| bits & 1 << channel != 0).collect())
+                    }
+                };
+
+                let mut octave_tuning = ScaleOctaveTuning::default();
+                for (letter, value) in NOTE_LETTERS
+                    .into_iter()
+                    .zip(data[3..].chunks(num_bytes_per_value))
+                {
+                    *octave_tuning.as_mut(letter) = match format {
+                        ScaleOctaveTuningFormat::OneByte => {
+                            Ratio::from_cents(f64::from(value[0]) - 64.0)
+                        }
+                        ScaleOctaveTuningFormat::TwoByte => Ratio::from_semitones(
+                            f64::from(u16::from(value[0]) << 7 | u16::from(value[1])) / 8192.0
+                                - 1.0,
+                        ),
+                    };
+                }
+
+                MtsPayload::ScaleOctaveTuning {
+                    format,
+                    channels,
+                    octave_tuning,
+                }
+            }
+            BULK_DUMP_REQUEST | BULK_DUMP_REQUEST_WITH_BANK_SELECT => {
+                let with_bank_select = sub_id_2 == BULK_DUMP_REQUEST_WITH_BANK_SELECT;
+                check_length(7 + usize::from(with_bank_select))?;
+
+                MtsPayload::BulkDumpRequest {
+                    tuning_bank: with_bank_select.then(|| data[0]),
+                    tuning_program: data[data.len() - 1],
+                }
+            }
+            BULK_TUNING_DUMP | KEY_BASED_TUNING_DUMP => {
+                let with_bank_select = sub_id_2 == KEY_BASED_TUNING_DUMP;
+                check_length(408 + usize::from(with_bank_select))?;
+
+                let (&actual, checked_bytes) = body.split_last().unwrap();
+                let expected = checksum(checked_bytes);
+                if actual != expected {
+                    return Err(MtsDecodeError::ChecksumMismatch { expected, actual });
+                }
+
+                let (tuning_bank, data) = match with_bank_select {
+                    false => (None, data),
+                    true => (Some(data[0]), &data[1..]),
+                };
+                let (name, tuning_data) = data[1..].split_at(TUNING_NAME_LENGTH);
+
+                let tuning_changes = (0..)
+                    .zip(tuning_data.chunks(3).take(128))
+                    .filter_map(|(midi_number, chunk)| {
+                        decode_pitch(chunk).map(|target_pitch| SingleNoteTuningChange {
+                            key: PianoKey::from_midi_number(midi_number),
+                            target_pitch,
+                        })
+                    })
+                    .collect();
+
+                MtsPayload::BulkTuningDump {
+                    tuning_bank,
+                    tuning_program: data[0],
+                    name: String::from_utf8_lossy(name).trim_end().to_owned(),
+                    tuning_changes,
+                }
+            }
+            other => return Err(MtsDecodeError::UnsupportedMessageType(other)),
+        };
+
+        Ok(Self {
+            realtime,
+            device_id,
+            payload,
+        })
+    }
+}
+
+/// Splits a stream of raw MIDI bytes, e.g. the content of a `.syx` file, into SysEx messages.
+///
+/// Each yielded message starts with `0xf0` and, if the stream is complete, ends with `0xf7`.
+/// Bytes outside of SysEx messages are skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::mts;
+/// let stream = [0xf0, 0x7e, 0xf7, 0x90, 60, 100, 0xf0, 0x7f, 0x7f, 0xf7, 0xf0, 0x7e];
+///
+/// assert_eq!(
+///     Vec::from_iter(mts::split_sysex_messages(&stream)),
+///     [&[0xf0, 0x7e, 0xf7][..], &[0xf0, 0x7f, 0x7f, 0xf7][..], &[0xf0, 0x7e][..]]
+/// );
+/// ```
+pub fn split_sysex_messages(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut remaining = bytes;
+    iter::from_fn(move || {
+        let start = remaining.iter().position(|&byte| byte == SYSEX_START)?;
+        let message = &remaining[start..];
+        let end = message
+            .iter()
+            .position(|&byte| byte == SYSEX_END)
+            .map_or(message.len(), |end| end + 1);
+        remaining = &message[end..];
+        Some(&message[..end])
+    })
+}
+
+/// Decoding an [`MtsMessage`] failed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MtsDecodeError {
+    /// The message does not start with `0xf0`.
+    MissingSysexStart,
+
+    /// The message does not end with `0xf7`.
+    MissingSysexEnd,
+
+    /// The message ends before all mandatory header fields are read.
+    UnexpectedEnd,
+
+    /// A data byte at the given position exceeds the allowed range [0..128).
+    DataByteOutOfRange { position: usize },
+
+    /// The message is not a universal realtime (`0x7f`) or non-realtime (`0x7e`) SysEx message. The actual ID is provided.
+    NotUniversalSysex(u8),
+
+    /// The message is not a MIDI Tuning Standard (`0x08`) message. The actual sub-ID #1 is provided.
+    NotMidiTuningStandard(u8),
+
+    /// The MIDI Tuning Standard message type is not supported. The actual sub-ID #2 is provided.
+    UnsupportedMessageType(u8),
+
+    /// The total length of the message, including the SysEx start and end bytes, does not match its content.
+    UnexpectedLength { expected: usize, actual: usize },
+
+    /// The checksum of a bulk tuning dump does not match the XOR of the preceding data bytes.
+    ChecksumMismatch { expected: u8, actual: u8 },
+}
+
+/// Encodes the given [`Pitch`] as a MIDI note number followed by a 14-bit fraction of a semitone.
+fn encode_pitch(pitch: Pitch) -> Option<[u8; 3]> {
+    let approximation = pitch.find_in_tuning(());
+    let mut target_note = approximation.approx_value;
+
+    let mut detune_in_u14_resolution =
+        (approximation.deviation.as_semitones() * U14_UPPER_BOUND_AS_F64).round();
+
+    // Make sure that the detune range is [0c..100c] instead of [-50c..50c]
+    if detune_in_u14_resolution < 0.0 {
+        target_note = target_note.plus_semitones(-1);
+        detune_in_u14_resolution += U14_UPPER_BOUND_AS_F64;
+    }
+
+    let target = target_note.checked_midi_number()?;
+    let pitch_msb = (detune_in_u14_resolution as u16 >> 7) as u8;
+    let pitch_lsb = (detune_in_u14_resolution as u16 & U7_MASK) as u8;
+
+    Some([target, pitch_msb, pitch_lsb])
+}
+
+/// Inverse of [`encode_pitch`]. Returns [`None`] for entries marked as "no change".
+fn decode_pitch(bytes: &[u8]) -> Option<Pitch> {
+    (bytes != NO_CHANGE).then(|| {
+        Note::from_midi_number(bytes[0]).pitch()
+            * Ratio::from_semitones(
+                f64::from(u16::from(bytes[1]) << 7 | u16::from(bytes[2])) / U14_UPPER_BOUND_AS_F64,
+            )
+    })
+}
+
+/// XOR of all given bytes, restricted to the range [0..128).
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |checksum, byte| checksum ^ byte) & 0x7f
+}
+
+const NOTE_LETTERS: [NoteLetter; 12] = [
+    NoteLetter::C,
+    NoteLetter::Csh,
+    NoteLetter::D,
+    NoteLetter::Dsh,
+    NoteLetter::E,
+    NoteLetter::F,
+    NoteLetter::Fsh,
+    NoteLetter::G,
+    NoteLetter::Gsh,
+    NoteLetter::A,
+    NoteLetter::Ash,
+    NoteLetter::B,
+];
+
 pub fn channel_fine_tuning(channel: u8, detuning: Ratio) -> Option<[ChannelMessage; 4]> {
     const CHANNEL_FINE_TUNING_MSB: u8 = 0x00;
     const CHANNEL_FINE_TUNING_LSB: u8 = 0x01;
@@ -1,6 +1,9 @@
 //! Abstractions for working with notes, letters and octaves.
 
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
 
 use crate::{
     key::PianoKey,
@@ -263,6 +266,338 @@ impl Display for Note {
     }
 }
 
+/// Parses a [`Note`] from a MIDI number or a note name in scientific pitch notation or Helmholtz notation.
+///
+/// Accidentals can be `#`/`♯` (sharp), `b`/`♭` (flat), `x`/`𝄪` (double sharp) or `𝄫` (double flat) and can be repeated.
+/// In Helmholtz notation, uppercase letters denote the great octave which can be lowered by commas (`C,`) while lowercase letters denote the small octave which can be raised by apostrophes (`c'`).
+///
+/// # Examples
+///
+/// ```
+/// # use tune::note::Note;
+/// # use tune::note::NoteLetter;
+/// assert_eq!("69".parse::<Note>().unwrap(), NoteLetter::A.in_octave(4));
+/// assert_eq!("A4".parse::<Note>().unwrap(), NoteLetter::A.in_octave(4));
+/// assert_eq!("C#4".parse::<Note>().unwrap(), NoteLetter::Csh.in_octave(4));
+/// assert_eq!("Eb3".parse::<Note>().unwrap(), NoteLetter::Dsh.in_octave(3));
+/// assert_eq!("B♭2".parse::<Note>().unwrap(), NoteLetter::Ash.in_octave(2));
+/// assert_eq!("Fx4".parse::<Note>().unwrap(), NoteLetter::G.in_octave(4));
+/// assert_eq!("Cb4".parse::<Note>().unwrap(), NoteLetter::B.in_octave(3));
+/// assert_eq!("C-1".parse::<Note>().unwrap(), Note::from_midi_number(0));
+///
+/// // Helmholtz notation
+/// assert_eq!("c'".parse::<Note>().unwrap(), NoteLetter::C.in_octave(4));
+/// assert_eq!("a''".parse::<Note>().unwrap(), NoteLetter::A.in_octave(5));
+/// assert_eq!("bb".parse::<Note>().unwrap(), NoteLetter::Ash.in_octave(3));
+/// assert_eq!("F#".parse::<Note>().unwrap(), NoteLetter::Fsh.in_octave(2));
+/// assert_eq!("C,,".parse::<Note>().unwrap(), NoteLetter::C.in_octave(0));
+///
+/// // Errors
+/// assert!("H4".parse::<Note>().is_err());
+/// assert!("^D4".parse::<Note>().is_err());
+/// assert!("A4+10c".parse::<Note>().is_err());
+/// ```
+impl FromStr for Note {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match NoteName::parse_prefix(s.trim()) {
+            Some((note_name, "")) if note_name.num_ups == 0 => Ok(note_name.to_note()),
+            _ => Err(
+                "Must be a MIDI number (e.g. 69) or a note name (e.g. C#4, Eb3, c' or C,)"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+/// Parser for note expressions in scientific pitch notation, Helmholtz notation or ups-and-downs notation.
+///
+/// A note expression consists of a note name (see [`Note`]'s [`FromStr`] impl) that can be prefixed by ups (`^`) or downs (`v`) and suffixed by `@<pitch>` or `+<ratio>`/`-<ratio>`.
+/// Each up or down raises or lowers the note by one step of the EDO given to [`NoteNameParser::edo`].
+/// The note letters and accidentals are interpreted as a chain of patent fifths of the same EDO with A4 sounding at 440&nbsp;Hz.
+///
+/// The [`Note`] part of the parsed expression is always the nearest 12-EDO note ignoring any ups or downs.
+///
+/// A `-` directly following the note letter and accidentals is ambiguous: If it is followed by an integer that is terminated by the end of the expression, whitespace, `@`, `+` or `-`, it is read as a negative octave number (e.g. `C-1`).
+/// Otherwise, it starts a delta, s.t. `C-100c` denotes the Helmholtz note `C` (C2) lowered by 100 cents.
+/// To subtract a plain number from a Helmholtz note, separate the `-` from the note name by a space (e.g. `C -2`).
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::note::NoteLetter;
+/// # use tune::note::NoteNameParser;
+/// use tune::note::PitchedNote;
+/// use tune::pitch::Pitched;
+///
+/// let parser = NoteNameParser::default();
+///
+/// let a4_at_432_hz = parser.parse_note("A4@432Hz").unwrap();
+/// assert_eq!(a4_at_432_hz.note(), NoteLetter::A.in_octave(4));
+/// assert_approx_eq!(a4_at_432_hz.pitch().as_hz(), 432.0);
+///
+/// let eb3_altered = parser.parse_note("Eb3+14.2c").unwrap();
+/// assert_eq!(eb3_altered.note(), NoteLetter::Dsh.in_octave(3));
+/// assert_approx_eq!(eb3_altered.pitch().as_hz(), 156.844708);
+///
+/// assert_approx_eq!(parser.parse_pitch("C#4-1\\12").unwrap().as_hz(), 261.625565);
+/// assert_approx_eq!(parser.parse_pitch("^D4").unwrap().as_hz(), 311.126983);
+///
+/// // Ups and downs in 22-EDO
+/// let parser = NoteNameParser::edo(22);
+/// assert_approx_eq!(parser.parse_pitch("A4").unwrap().as_hz(), 440.0);
+/// assert_approx_eq!(parser.parse_pitch("E4").unwrap().as_hz(), 331.363022);
+/// assert_approx_eq!(parser.parse_pitch("vF#4").unwrap().as_hz(), 364.211443);
+///
+/// let up_d4 = parser.parse_note("^D4").unwrap();
+/// assert_eq!(up_d4.note(), NoteLetter::D.in_octave(4));
+/// assert_approx_eq!(up_d4.pitch().as_hz(), 301.477217);
+///
+/// // Negative octave numbers vs. deltas
+/// let parser = NoteNameParser::default();
+/// assert_approx_eq!(parser.parse_pitch("C-1").unwrap().as_hz(), 8.175799);
+/// assert_approx_eq!(parser.parse_pitch("C-1-100c").unwrap().as_hz(), 7.716926);
+/// assert_approx_eq!(parser.parse_pitch("C-100c").unwrap().as_hz(), 61.735413);
+/// assert_approx_eq!(parser.parse_pitch("C -2").unwrap().as_hz(), 32.703196);
+///
+/// // Errors
+/// assert_eq!(
+///     parser.parse_note("A4@432").unwrap_err(),
+///     "Invalid pitch '432': Must end with Hz or hz or be a note expression (e.g. A4, Eb3+14.2c or A4@432Hz)"
+/// );
+/// assert_eq!(
+///     parser.parse_note("A4+x").unwrap_err(),
+///     "Invalid delta 'x': Invalid expression 'x': Must be a float (e.g. 1.5), fraction (e.g. 3/2), \
+///      interval fraction (e.g. 7:12:2), EDO step (e.g. 7\\12), cents value (e.g. 702c) or interval name (e.g. syntonic comma)"
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct NoteNameParser {
+    num_steps_per_octave: u16,
+}
+
+impl NoteNameParser {
+    /// Creates a [`NoteNameParser`] interpreting ups and downs as steps of the given EDO.
+    pub fn edo(num_steps_per_octave: u16) -> Self {
+        Self {
+            num_steps_per_octave,
+        }
+    }
+
+    /// Parses a note expression into a [`NoteAtConcertPitch`].
+    pub fn parse_note(&self, s: &str) -> Result<NoteAtConcertPitch, String> {
+        let s = s.trim();
+
+        let (note_name, rest) = NoteName::parse_prefix(s).ok_or_else(|| {
+            "Must be a MIDI number (e.g. 69) or a note name (e.g. C#4, ^D4 or c'), \
+             optionally followed by @<pitch> (e.g. @432Hz) or +/-<ratio> (e.g. +20c)"
+                .to_string()
+        })?;
+
+        let note = note_name.to_note();
+        let rest = rest.trim();
+
+        if let Some(pitch) = rest.strip_prefix('@') {
+            let pitch = pitch.trim();
+            let pitch = pitch
+                .parse::<Pitch>()
+                .map_err(|e| format!("Invalid pitch '{pitch}': {e}"))?;
+            return Ok(note.at_pitch(pitch));
+        }
+
+        let note_at_pitch = note.at_pitch(self.pitch_of(note_name));
+
+        if let Some(delta) = rest.strip_prefix('+') {
+            let delta = delta.trim();
+            let delta = delta
+                .parse::<Ratio>()
+                .map_err(|e| format!("Invalid delta '{delta}': {e}"))?;
+            Ok(note_at_pitch.alter_pitch_by(delta))
+        } else if let Some(delta) = rest.strip_prefix('-') {
+            let delta = delta.trim();
+            let delta = delta
+                .parse::<Ratio>()
+                .map_err(|e| format!("Invalid delta '{delta}': {e}"))?;
+            Ok(note_at_pitch.alter_pitch_by(delta.inv()))
+        } else if rest.is_empty() {
+            Ok(note_at_pitch)
+        } else {
+            Err(format!(
+                "Unexpected suffix '{rest}': Must be @<pitch> (e.g. @432Hz) or +/-<ratio> (e.g. +20c)"
+            ))
+        }
+    }
+
+    /// Parses a note expression and returns its [`Pitch`].
+    pub fn parse_pitch(&self, s: &str) -> Result<Pitch, String> {
+        self.parse_note(s).map(|note| note.pitch())
+    }
+
+    fn pitch_of(&self, note_name: NoteName) -> Pitch {
+        let num_steps_from_a4 = match note_name.letter {
+            None => return note_name.to_note().pitch(),
+            Some(letter) => {
+                self.num_edo_steps(letter, note_name.num_accidentals, note_name.octave)
+                    + note_name.num_ups
+                    - self.num_edo_steps(NoteLetter::A, 0, 4)
+            }
+        };
+
+        NoteLetter::A.in_octave(4).pitch()
+            * Ratio::from_octaves(
+                f64::from(num_steps_from_a4) / f64::from(self.num_steps_per_octave),
+            )
+    }
+
+    fn num_edo_steps(&self, letter: NoteLetter, num_accidentals: i32, octave: i32) -> i32 {
+        let num_steps_per_octave = i32::from(self.num_steps_per_octave);
+        let num_steps_per_fifth = (f64::from(num_steps_per_octave) * 1.5f64.log2()).round() as i32;
+
+        let num_fifths: i32 = match letter {
+            NoteLetter::F => -1,
+            NoteLetter::C => 0,
+            NoteLetter::G => 1,
+            NoteLetter::D => 2,
+            NoteLetter::A => 3,
+            NoteLetter::E => 4,
+            NoteLetter::B => 5,
+            _ => unreachable!("Note names are always natural"),
+        };
+        let num_octaves = (num_fifths * 7).div_euclid(12);
+        let sharpness = 7 * num_steps_per_fifth - 4 * num_steps_per_octave;
+
+        octave * num_steps_per_octave + num_fifths * num_steps_per_fifth
+            - num_octaves * num_steps_per_octave
+            + num_accidentals * sharpness
+    }
+}
+
+impl Default for NoteNameParser {
+    fn default() -> Self {
+        Self::edo(12)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct NoteName {
+    letter: Option<NoteLetter>,
+    midi_number: i32,
+    num_accidentals: i32,
+    num_ups: i32,
+    octave: i32,
+}
+
+impl NoteName {
+    fn parse_prefix(s: &str) -> Option<(Self, &str)> {
+        // MIDI numbers can be negative
+        let sign_len = usize::from(s.starts_with('-'));
+        let num_digits = s[sign_len..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(s.len() - sign_len);
+        if num_digits > 0 {
+            let midi_number_len = sign_len + num_digits;
+            return Some((
+                Self {
+                    letter: None,
+                    midi_number: s[..midi_number_len].parse().ok()?,
+                    num_accidentals: 0,
+                    num_ups: 0,
+                    octave: 0,
+                },
+                &s[midi_number_len..],
+            ));
+        }
+
+        let mut chars = s.char_indices().peekable();
+
+        let mut num_ups = 0;
+        while let Some(&(_, c)) = chars.peek() {
+            match c {
+                '^' => num_ups += 1,
+                'v' => num_ups -= 1,
+                _ => break,
+            }
+            chars.next();
+        }
+
+        let (_, letter_char) = chars.next()?;
+        let letter = match letter_char.to_ascii_uppercase() {
+            'C' => NoteLetter::C,
+            'D' => NoteLetter::D,
+            'E' => NoteLetter::E,
+            'F' => NoteLetter::F,
+            'G' => NoteLetter::G,
+            'A' => NoteLetter::A,
+            'B' => NoteLetter::B,
+            _ => return None,
+        };
+
+        let mut num_accidentals = 0;
+        while let Some(&(_, c)) = chars.peek() {
+            match c {
+                '#' | '♯' => num_accidentals += 1,
+                'b' | '♭' => num_accidentals -= 1,
+                'x' | '𝄪' => num_accidentals += 2,
+                '𝄫' => num_accidentals -= 2,
+                _ => break,
+            }
+            chars.next();
+        }
+
+        let start = chars.peek().map_or(s.len(), |&(index, _)| index);
+        let rest = &s[start..];
+
+        let (octave, rest) = match parse_octave_number(rest) {
+            Some(octave_and_rest) => octave_and_rest,
+            None if letter_char.is_ascii_uppercase() => {
+                let num_commas = rest.find(|c| c != ',').unwrap_or(rest.len());
+                (2 - num_commas as i32, &rest[num_commas..])
+            }
+            None => {
+                let num_apostrophes = rest.find(|c| c != '\'').unwrap_or(rest.len());
+                (3 + num_apostrophes as i32, &rest[num_apostrophes..])
+            }
+        };
+
+        let midi_number = letter.in_octave(0).midi_number() + 12 * octave + num_accidentals;
+
+        Some((
+            Self {
+                letter: Some(letter),
+                midi_number,
+                num_accidentals,
+                num_ups,
+                octave,
+            },
+            rest,
+        ))
+    }
+
+    fn to_note(self) -> Note {
+        Note::from_midi_number(self.midi_number)
+    }
+}
+
+fn parse_octave_number(s: &str) -> Option<(i32, &str)> {
+    let num_sign_chars = usize::from(s.starts_with('-'));
+    let end = s[num_sign_chars..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(s.len(), |index| index + num_sign_chars);
+
+    let terminates = s[end..]
+        .chars()
+        .next()
+        .map_or(true, |c| matches!(c, '@' | '+' | '-') || c.is_whitespace());
+
+    if terminates {
+        s[..end].parse().ok().map(|octave| (octave, &s[end..]))
+    } else {
+        None
+    }
+}
+
 /// The speaking name of a note within its octave.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub enum NoteLetter {
@@ -4,6 +4,43 @@ pub fn split_balanced(s: &str, split_character: char) -> Vec<&str> {
         .collect::<Vec<_>>()
 }
 
+pub fn split_once_balanced(s: &str, split_character: char) -> Option<(&str, &str)> {
+    s.split_once(balanced(split_character))
+        .map(|(left, right)| (left.trim(), right.trim()))
+}
+
+pub fn rsplit_once_balanced<'a>(
+    s: &'a str,
+    split_characters: &[char],
+) -> Option<(&'a str, char, &'a str)> {
+    let mut num_parens = 0;
+    let mut last_match = None;
+    for (index, c) in s.char_indices() {
+        match c {
+            '(' => num_parens += 1,
+            ')' => num_parens -= 1,
+            other if num_parens == 0 && split_characters.contains(&other) => {
+                last_match = Some((index, other))
+            }
+            _ => {}
+        }
+    }
+    last_match.map(|(index, c)| (s[..index].trim(), c, s[index + c.len_utf8()..].trim()))
+}
+
+pub fn is_balanced(s: &str) -> bool {
+    let mut num_parens = 0;
+    for c in s.chars() {
+        match c {
+            '(' => num_parens += 1,
+            ')' if num_parens == 0 => return false,
+            ')' => num_parens -= 1,
+            _ => {}
+        }
+    }
+    num_parens == 0
+}
+
 fn balanced(character_to_match: char) -> impl FnMut(char) -> bool {
     let mut num_parens = 0;
     move |c| match c {
@@ -7,7 +7,7 @@ use std::{
     ops::{Add, Sub},
 };
 
-use crate::math;
+use crate::{ji::JiRatio, math};
 
 #[derive(Clone, Debug)]
 pub struct PerGen {
@@ -116,6 +116,60 @@ impl PerGen {
         }
     }
 
+    /// Spells the note at `index` in ups-and-downs notation.
+    ///
+    /// The note is spelled as a genchain note with at most one sharp or flat and the lowest possible number of ups/downs where each up (down) raises (lowers) the note by one step.
+    /// If two spellings need the same number of ups/downs the one without accidentals is preferred.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pergen::AccidentalsFormat;
+    /// # use tune::pergen::PerGen;
+    /// let format = AccidentalsFormat {
+    ///     num_symbols: 7,
+    ///     genchain_origin: 3,
+    /// };
+    ///
+    /// // 24-EDO: Two cycles of 12-EDO
+    /// let pergen = PerGen::new(24, 14);
+    ///
+    /// let natural = pergen.get_ups_and_downs(&format, 0);
+    /// assert_eq!(natural.num_ups, 0);
+    /// assert_eq!(natural.accidentals.sharp_index, 3);
+    ///
+    /// let up = pergen.get_ups_and_downs(&format, 1);
+    /// assert_eq!(up.num_ups, 1);
+    /// assert_eq!(up.accidentals.sharp_index, 3);
+    ///
+    /// let down = pergen.get_ups_and_downs(&format, 3);
+    /// assert_eq!(down.num_ups, -1);
+    /// assert_eq!(down.accidentals.sharp_index, 5);
+    /// ```
+    pub fn get_ups_and_downs(&self, format: &AccidentalsFormat, index: u16) -> UpsAndDowns {
+        let max_num_ups = i32::from(self.period / 2);
+
+        let (_, num_ups, mut accidentals) =
+            (0..=max_num_ups)
+                .flat_map(|num_ups| [num_ups, -num_ups])
+                .filter_map(|num_ups| {
+                    let target_index = math::i32_rem_u(i32::from(index) - num_ups, self.period);
+                    let accidentals = self.get_accidentals(format, target_index);
+                    let num_accidentals = accidentals.sharp_count.min(accidentals.flat_count);
+                    (accidentals.cycle.unwrap_or_default() == 0 && num_accidentals <= 1)
+                        .then_some((num_accidentals, num_ups, accidentals))
+                })
+                .min_by_key(|&(num_accidentals, num_ups, _)| (num_ups.abs(), num_accidentals))
+                .unwrap();
+
+        accidentals.cycle = None;
+
+        UpsAndDowns {
+            num_ups: i16::try_from(num_ups).unwrap(),
+            accidentals,
+        }
+    }
+
     pub fn get_moses(&self) -> impl Iterator<Item = Mos> {
         Mos::<u16>::new_genesis(self.period, self.generator).children()
     }
@@ -158,6 +212,14 @@ pub struct Accidentals {
     pub flat_count: u16,
 }
 
+/// Note spelling in ups-and-downs notation, see [`PerGen::get_ups_and_downs`].
+#[derive(Clone, Debug)]
+pub struct UpsAndDowns {
+    /// Number of single steps by which the genchain note is raised (positive) or lowered (negative).
+    pub num_ups: i16,
+    pub accidentals: Accidentals,
+}
+
 #[derive(Clone, Debug)]
 pub struct NoteFormatter {
     pub note_names: Cow<'static, [char]>,
@@ -203,6 +265,140 @@ impl NoteFormatter {
         }
     }
 
+    /// Renders the given [`UpsAndDowns`] with `^` denoting an up and `v` denoting a down.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pergen::AccidentalsFormat;
+    /// # use tune::pergen::AccidentalsOrder;
+    /// # use tune::pergen::NoteFormatter;
+    /// # use tune::pergen::PerGen;
+    /// let format = AccidentalsFormat {
+    ///     num_symbols: 7,
+    ///     genchain_origin: 3,
+    /// };
+    /// let formatter = NoteFormatter {
+    ///     note_names: ['F', 'C', 'G', 'D', 'A', 'E', 'B'][..].into(),
+    ///     sharp_sign: '#',
+    ///     flat_sign: 'b',
+    ///     cycle_sign: '*',
+    ///     order: AccidentalsOrder::SharpFlat,
+    /// };
+    ///
+    /// let pergen = PerGen::new(24, 14);
+    /// let note_names: Vec<_> = (0..6)
+    ///     .map(|index| formatter.format_ups_and_downs(&pergen.get_ups_and_downs(&format, index)))
+    ///     .collect();
+    /// assert_eq!(note_names, ["D", "^D", "D#/Eb", "vE", "E", "^E"]);
+    ///
+    /// let pergen = PerGen::new(41, 24);
+    /// let note_names: Vec<_> = (0..8)
+    ///     .map(|index| formatter.format_ups_and_downs(&pergen.get_ups_and_downs(&format, index)))
+    ///     .collect();
+    /// assert_eq!(note_names, ["D", "^D", "vEb", "Eb", "D#", "^D#", "Fb", "E"]);
+    /// ```
+    pub fn format_ups_and_downs(&self, ups_and_downs: &UpsAndDowns) -> String {
+        let num_ups = usize::from(ups_and_downs.num_ups.unsigned_abs());
+        let prefix = if ups_and_downs.num_ups >= 0 {
+            "^".repeat(num_ups)
+        } else {
+            "v".repeat(num_ups)
+        };
+
+        self.format(&ups_and_downs.accidentals)
+            .split('/')
+            .map(|note_name| format!("{prefix}{note_name}"))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Renders the given [`JiRatio`] in a textual Helmholtz-Ellis (HEJI) style, assuming that the genchain is a chain of fifths.
+    ///
+    /// The 3-limit part of the ratio is spelled as a Pythagorean note relative to the genchain origin.
+    /// Every higher prime is spelled as a nearby Pythagorean interval altered by a comma.
+    /// The syntonic comma (prime 5) is rendered as `↑`/`↓` while the commas of higher primes are rendered as `↑p`/`↓p`, e.g. `↓7` for 63/64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// # use tune::pergen::AccidentalsFormat;
+    /// # use tune::pergen::AccidentalsOrder;
+    /// # use tune::pergen::NoteFormatter;
+    /// let format = AccidentalsFormat {
+    ///     num_symbols: 7,
+    ///     genchain_origin: 1,
+    /// };
+    /// let formatter = NoteFormatter {
+    ///     note_names: ['F', 'C', 'G', 'D', 'A', 'E', 'B'][..].into(),
+    ///     sharp_sign: '#',
+    ///     flat_sign: 'b',
+    ///     cycle_sign: '*',
+    ///     order: AccidentalsOrder::SharpFlat,
+    /// };
+    ///
+    /// let heji = |numer, denom| formatter.format_heji(&format, &JiRatio::from_fraction(numer, denom).unwrap());
+    ///
+    /// assert_eq!(heji(1, 1), "C");
+    /// assert_eq!(heji(3, 2), "G");
+    /// assert_eq!(heji(5, 4), "E↓");
+    /// assert_eq!(heji(6, 5), "Eb↑");
+    /// assert_eq!(heji(25, 16), "G#↓↓");
+    /// assert_eq!(heji(7, 4), "Bb↓7");
+    /// assert_eq!(heji(11, 8), "F↑11");
+    /// assert_eq!(heji(13, 8), "A↓13");
+    /// assert_eq!(heji(35, 32), "D↓↓7");
+    /// assert_eq!(heji(2187, 2048), "C#");
+    /// ```
+    pub fn format_heji(&self, format: &AccidentalsFormat, ji_ratio: &JiRatio) -> String {
+        let prime_factors = ji_ratio.prime_factors();
+
+        let mut num_fifths = prime_factors.get(1).copied().unwrap_or_default();
+        let mut commas = String::new();
+
+        for (&prime, &exponent) in math::U8_PRIMES.iter().zip(prime_factors).skip(2) {
+            if exponent == 0 {
+                continue;
+            }
+
+            let prime_fifths = pythagorean_fifths_for_prime(prime);
+            num_fifths += prime_fifths * exponent;
+
+            let num_octaves = f64::from(prime).log2() - f64::from(prime_fifths) * 3f64.log2();
+            let raises = (num_octaves - num_octaves.round() > 0.0) == (exponent > 0);
+            let arrow = if raises { '↑' } else { '↓' };
+
+            for _ in 0..exponent.unsigned_abs() {
+                commas.push(arrow);
+                if prime > 5 {
+                    commas.push_str(&prime.to_string());
+                }
+            }
+        }
+
+        let (num_accidentals, index) = math::i32_dr_u(
+            i32::from(format.genchain_origin) + num_fifths,
+            format.num_symbols,
+        );
+        let accidental = if num_accidentals >= 0 {
+            self.sharp_sign
+        } else {
+            self.flat_sign
+        };
+
+        let mut formatted = String::new();
+        self.write_note(
+            &mut formatted,
+            index,
+            u16::try_from(num_accidentals.unsigned_abs()).unwrap_or(u16::MAX),
+            accidental,
+        );
+        formatted.push_str(&commas);
+
+        formatted
+    }
+
     fn render_note_with_cycle(
         &self,
         cycle: Option<u16>,
@@ -270,6 +466,30 @@ impl NoteFormatter {
     }
 }
 
+/// Number of fifths of the Pythagorean interval that is altered by a comma to represent the given prime in HEJI notation.
+fn pythagorean_fifths_for_prime(prime: u8) -> i32 {
+    match prime {
+        5 => 4,
+        7 => -2,
+        11 => -1,
+        13 => 3,
+        17 => 7,
+        19 => -3,
+        23 => 6,
+        29 => -2,
+        31 => 0,
+        _ => (-6..=6)
+            .min_by(|&a: &i32, &b: &i32| {
+                let deviation = |num_fifths| {
+                    let num_octaves = f64::from(prime).log2() - f64::from(num_fifths) * 3f64.log2();
+                    (num_octaves - num_octaves.round()).abs()
+                };
+                deviation(a).total_cmp(&deviation(b))
+            })
+            .unwrap(),
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum AccidentalsOrder {
     SharpFlat,
@@ -430,6 +650,161 @@ impl<StepSize: MosParam> Mos<StepSize, u16> {
             size: num_large_steps,
         }
     }
+
+    /// Returns the number of large and small steps of the MOS.
+    ///
+    /// If both step sizes are equal, the primary steps are considered to be the large steps.
+    pub fn num_large_and_small_steps(self) -> (u16, u16) {
+        if self.primary_step >= self.secondary_step {
+            (self.num_primary_steps, self.num_secondary_steps)
+        } else {
+            (self.num_secondary_steps, self.num_primary_steps)
+        }
+    }
+
+    /// Lists all modes, i.e. rotations, of the MOS ordered from brightest to darkest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pergen::Mos;
+    /// let diatonic_mos = Mos::new(5, 2, 2, 1).unwrap();
+    ///
+    /// let modes = diatonic_mos.modes();
+    /// assert_eq!(
+    ///     Vec::from_iter(modes.iter().map(|mode| format!("{} {}", mode.udp(), mode.pattern))),
+    ///     [
+    ///         "6|0 LLLsLLs", // Lydian
+    ///         "5|1 LLsLLLs", // Ionian
+    ///         "4|2 LLsLLsL", // Mixolydian
+    ///         "3|3 LsLLLsL", // Dorian
+    ///         "2|4 LsLLsLL", // Aeolian
+    ///         "1|5 sLLLsLL", // Phrygian
+    ///         "0|6 sLLsLLL", // Locrian
+    ///     ]
+    /// );
+    ///
+    /// // Modes of multi-period MOSes are only distinct within one period
+    /// let diminished_mos = Mos::new(4, 4, 2, 1).unwrap();
+    ///
+    /// let modes = diminished_mos.modes();
+    /// assert_eq!(
+    ///     Vec::from_iter(modes.iter().map(|mode| format!("{} {}", mode.udp(), mode.pattern))),
+    ///     ["4|0(4) LsLsLsLs", "0|4(4) sLsLsLsL"]
+    /// );
+    /// ```
+    pub fn modes(self) -> Vec<MosMode> {
+        let (num_large_steps, num_small_steps) = self.num_large_and_small_steps();
+        let num_periods = math::gcd_u16(num_large_steps, num_small_steps);
+        if num_periods == 0 {
+            return Vec::new();
+        }
+
+        let num_large_steps_per_period = num_large_steps / num_periods;
+        let num_small_steps_per_period = num_small_steps / num_periods;
+        let num_steps_per_period = num_large_steps_per_period + num_small_steps_per_period;
+
+        let mut step_offset = 0;
+        let brightest_pattern = Vec::from_iter((0..num_steps_per_period).map(|_| {
+            if step_offset < num_large_steps_per_period {
+                step_offset += num_small_steps_per_period;
+                'L'
+            } else {
+                step_offset -= num_large_steps_per_period;
+                's'
+            }
+        }));
+
+        let mut patterns = Vec::from_iter((0..brightest_pattern.len()).map(|rotation| {
+            let mut pattern = brightest_pattern.clone();
+            pattern.rotate_left(rotation);
+            pattern
+        }));
+        // Within a MOS, the lexicographic order of the modes (with L > s) is the brightness order
+        patterns.sort_by(|a, b| {
+            b.iter()
+                .map(|&step| step == 'L')
+                .cmp(a.iter().map(|&step| step == 'L'))
+        });
+        patterns.dedup();
+
+        patterns
+            .into_iter()
+            .zip((0..num_steps_per_period).rev())
+            .map(|(pattern, brightness)| MosMode {
+                pattern: pattern
+                    .iter()
+                    .cycle()
+                    .take(pattern.len() * usize::from(num_periods))
+                    .collect(),
+                num_up: brightness * num_periods,
+                num_down: (num_steps_per_period - 1 - brightness) * num_periods,
+                num_periods,
+            })
+            .collect()
+    }
+
+    /// Returns the TAMNAMS name of the octave-equivalent MOS with up to 10 notes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pergen::Mos;
+    /// assert_eq!(Mos::new(5, 2, 2, 1).unwrap().tamnams_name(), Some("diatonic"));
+    /// assert_eq!(Mos::new(5, 2, 1, 2).unwrap().tamnams_name(), Some("antidiatonic"));
+    /// assert_eq!(Mos::new(4, 4, 2, 1).unwrap().tamnams_name(), Some("tetrawood"));
+    /// assert_eq!(Mos::new(5, 7, 2, 1).unwrap().tamnams_name(), None);
+    /// ```
+    pub fn tamnams_name(self) -> Option<&'static str> {
+        Some(match self.num_large_and_small_steps() {
+            (1, 1) => "monowood",
+            (1, 2) => "antrial",
+            (2, 1) => "trial",
+            (1, 3) => "antetric",
+            (2, 2) => "biwood",
+            (3, 1) => "tetric",
+            (1, 4) => "pedal",
+            (2, 3) => "pentic",
+            (3, 2) => "antipentic",
+            (4, 1) => "manual",
+            (1, 5) => "antimachinoid",
+            (2, 4) => "malic",
+            (3, 3) => "triwood",
+            (4, 2) => "citric",
+            (5, 1) => "machinoid",
+            (1, 6) => "onyx",
+            (2, 5) => "antidiatonic",
+            (3, 4) => "mosh",
+            (4, 3) => "smitonic",
+            (5, 2) => "diatonic",
+            (6, 1) => "archeotonic",
+            (1, 7) => "antipine",
+            (2, 6) => "subaric",
+            (3, 5) => "checkertonic",
+            (4, 4) => "tetrawood",
+            (5, 3) => "oneirotonic",
+            (6, 2) => "ekic",
+            (7, 1) => "pine",
+            (1, 8) => "antisubneutralic",
+            (2, 7) => "balzano",
+            (3, 6) => "tcherepnin",
+            (4, 5) => "gramitonic",
+            (5, 4) => "semiquartal",
+            (6, 3) => "hyrulic",
+            (7, 2) => "armotonic",
+            (8, 1) => "subneutralic",
+            (1, 9) => "antisinatonic",
+            (2, 8) => "jaric",
+            (3, 7) => "sephiroid",
+            (4, 6) => "lime",
+            (5, 5) => "pentawood",
+            (6, 4) => "lemon",
+            (7, 3) => "dicoid",
+            (8, 2) => "taric",
+            (9, 1) => "sinatonic",
+            _ => return None,
+        })
+    }
 }
 
 impl Mos<u16, u16> {
@@ -469,6 +844,44 @@ impl Mos<u16, u16> {
     }
 }
 
+impl Mos<f64, u16> {
+    /// Creates a custom *x*L*y*s [`Mos<f64>`] with the step sizes rescaled s.t. the total size of the MOS is 1.
+    ///
+    /// Returns [`None`] if the total size of the provided steps is not positive and finite.
+    ///
+    /// # Example
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::pergen::Mos;
+    /// let diatonic_mos = Mos::new_normalized(5, 2, 200.0, 100.0).unwrap();
+    /// assert_eq!(diatonic_mos.size(), 1);
+    /// assert_eq!(diatonic_mos.num_steps(), 7);
+    /// assert_eq!(diatonic_mos.num_primary_steps(), 5);
+    /// assert_eq!(diatonic_mos.num_secondary_steps(), 2);
+    /// assert_approx_eq!(diatonic_mos.primary_step(), 2.0 / 12.0);
+    /// assert_approx_eq!(diatonic_mos.secondary_step(), 1.0 / 12.0);
+    ///
+    /// let empty_mos = Mos::new_normalized(0, 0, 1.0, 1.0);
+    /// assert!(empty_mos.is_none());
+    /// ```
+    pub fn new_normalized(
+        num_primary_steps: u16,
+        num_secondary_steps: u16,
+        primary_step: f64,
+        secondary_step: f64,
+    ) -> Option<Self> {
+        let total_size = f64::from(num_primary_steps) * primary_step
+            + f64::from(num_secondary_steps) * secondary_step;
+        (total_size > 0.0 && total_size.is_finite()).then(|| Self {
+            num_primary_steps,
+            num_secondary_steps,
+            primary_step: primary_step / total_size,
+            secondary_step: secondary_step / total_size,
+            size: 1,
+        })
+    }
+}
+
 impl<StepSize: MosParam, StepCount: MosParam> Mos<StepSize, StepCount> {
     /// Returns the current MOS' child MOS if possible.
     ///
@@ -948,6 +1361,84 @@ impl Mos<u16, u16> {
     }
 }
 
+/// A mode, i.e. a rotation, of a [`Mos`] as returned by [`Mos::modes`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MosMode {
+    /// Step pattern with `L` denoting a large and `s` denoting a small step, e.g. `LLsLLLs` for the Ionian mode.
+    pub pattern: String,
+
+    /// Number of generators stacked upwards from the tonic.
+    pub num_up: u16,
+
+    /// Number of generators stacked downwards from the tonic.
+    pub num_down: u16,
+
+    /// Number of periods the MOS consists of.
+    pub num_periods: u16,
+}
+
+impl MosMode {
+    /// Formats the mode in UDP notation, e.g. `5|1` for the Ionian mode or `4|0(4)` for a diminished mode.
+    pub fn udp(&self) -> String {
+        match self.num_periods {
+            1 => format!("{}|{}", self.num_up, self.num_down),
+            num_periods => format!("{}|{}({num_periods})", self.num_up, self.num_down),
+        }
+    }
+}
+
+/// Returns the TAMNAMS name of the step ratio `large_step`:`small_step`, e.g. "basic" for 2:1 or "soft" for 3:2.
+///
+/// Step ratios without a name of their own are named after the range they are located in, e.g. "quasisoft" for ratios between 3:2 and 5:3.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::pergen;
+/// assert_eq!(pergen::tamnams_step_ratio_name(2.0, 1.0), "basic");
+/// assert_eq!(pergen::tamnams_step_ratio_name(3.0, 2.0), "soft");
+/// assert_eq!(pergen::tamnams_step_ratio_name(5.0, 2.0), "semihard");
+/// assert_eq!(pergen::tamnams_step_ratio_name(1.0, 0.0), "collapsed");
+///
+/// // 1/4-comma meantone
+/// assert_eq!(pergen::tamnams_step_ratio_name(193.157, 117.108), "quasisoft");
+/// ```
+pub fn tamnams_step_ratio_name(large_step: f64, small_step: f64) -> &'static str {
+    const NAMED_STEP_RATIOS: [(f64, &str, &str); 8] = [
+        (1.0, "equalized", "ultrasoft"),
+        (4.0 / 3.0, "supersoft", "parasoft"),
+        (3.0 / 2.0, "soft", "quasisoft"),
+        (5.0 / 3.0, "semisoft", "minisoft"),
+        (2.0, "basic", "minihard"),
+        (5.0 / 2.0, "semihard", "quasihard"),
+        (3.0, "hard", "parahard"),
+        (4.0, "superhard", "ultrahard"),
+    ];
+
+    let (large_step, small_step) = if large_step >= small_step {
+        (large_step.abs(), small_step.abs())
+    } else {
+        (small_step.abs(), large_step.abs())
+    };
+
+    if small_step < 1e-9 * large_step.max(1.0) {
+        return "collapsed";
+    }
+
+    let step_ratio = large_step / small_step;
+
+    let mut range_name = "ultrasoft";
+    for (named_ratio, name, name_of_range_above) in NAMED_STEP_RATIOS {
+        if (step_ratio - named_ratio).abs() < 1e-6 {
+            return name;
+        }
+        if step_ratio > named_ratio {
+            range_name = name_of_range_above;
+        }
+    }
+    range_name
+}
+
 trait NumBase: Copy + Default + PartialOrd + Add<Output = Self> + Sub<Output = Self> {}
 
 impl<T: Copy + Default + PartialOrd + Add<Output = Self> + Sub<Output = Self>> NumBase for T {}
@@ -5,10 +5,14 @@ use std::{
     fmt::{self, Display, Formatter},
     ops::{Div, Mul},
     str::FromStr,
+    sync::OnceLock,
 };
 
 use crate::{
-    math, parse,
+    math,
+    note::NoteNameParser,
+    parse,
+    temperament::{self, Comma, CommaCatalog},
     tuning::{Approximation, Tuning},
 };
 
@@ -48,18 +52,48 @@ impl Pitch {
     }
 }
 
+/// Parses a [`Pitch`] from a frequency or a note expression (see [`NoteNameParser`]).
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::pitch::Pitch;
+/// assert_approx_eq!("432Hz".parse::<Pitch>().unwrap().as_hz(), 432.0);
+/// assert_approx_eq!("A4".parse::<Pitch>().unwrap().as_hz(), 440.0);
+/// assert_approx_eq!("c'".parse::<Pitch>().unwrap().as_hz(), 261.625565);
+/// assert_approx_eq!("A4+1\\12".parse::<Pitch>().unwrap().as_hz(), 466.163762);
+/// assert_approx_eq!("C4@256Hz".parse::<Pitch>().unwrap().as_hz(), 256.0);
+///
+/// assert_eq!(
+///     "A4+".parse::<Pitch>().unwrap_err(),
+///     "Invalid delta '': Invalid expression '': Must be a float (e.g. 1.5), fraction (e.g. 3/2), \
+///      interval fraction (e.g. 7:12:2), EDO step (e.g. 7\\12), cents value (e.g. 702c) or interval name (e.g. syntonic comma)"
+/// );
+/// assert_eq!(
+///     "440".parse::<Pitch>().unwrap_err(),
+///     "Must end with Hz or hz or be a note expression (e.g. A4, Eb3+14.2c or A4@432Hz)"
+/// );
+/// ```
 impl FromStr for Pitch {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.ends_with("Hz") || s.ends_with("hz") {
+        if s.trim()
+            .starts_with(|c: char| c.is_ascii_alphabetic() || c == '^')
+        {
+            NoteNameParser::default().parse_pitch(s)
+        } else if s.ends_with("Hz") || s.ends_with("hz") {
             let freq = &s[..s.len() - 2];
             let freq = freq
                 .parse::<Ratio>()
                 .map_err(|e| format!("Invalid frequency: '{freq}': {e}"))?;
             Ok(Pitch::from_hz(freq.as_float()))
         } else {
-            Err("Must end with Hz or hz".to_string())
+            Err(
+                "Must end with Hz or hz or be a note expression (e.g. A4, Eb3+14.2c or A4@432Hz)"
+                    .to_string(),
+            )
         }
     }
 }
@@ -494,7 +528,18 @@ impl Display for Ratio {
 /// assert_approx_eq!("3/2".parse::<Ratio>().unwrap().as_float(), 1.5);
 /// assert_approx_eq!("7:12:2".parse::<Ratio>().unwrap().as_semitones(), 7.0);
 /// assert_approx_eq!("702c".parse::<Ratio>().unwrap().as_cents(), 702.0);
-/// assert_eq!("foo".parse::<Ratio>().unwrap_err(), "Invalid expression \'foo\': Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2) or cents value (e.g. 702c)");
+/// assert_approx_eq!("7\\12".parse::<Ratio>().unwrap().as_semitones(), 7.0);
+/// assert_approx_eq!("syntonic comma".parse::<Ratio>().unwrap().as_float(), 81.0 / 80.0);
+///
+/// // Operators can be combined
+/// assert_approx_eq!("3/2 * 5/4".parse::<Ratio>().unwrap().as_float(), 15.0 / 8.0);
+/// assert_approx_eq!("(3/2)^4 / 4".parse::<Ratio>().unwrap().as_float(), 81.0 / 64.0);
+/// assert_approx_eq!("700c + 3/2".parse::<Ratio>().unwrap().as_cents(), 1401.955001);
+///
+/// assert_eq!("foo".parse::<Ratio>().unwrap_err(), "Invalid expression \'foo\': Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2), EDO step (e.g. 7\\12), cents value (e.g. 702c) or interval name (e.g. syntonic comma)");
+///
+/// // Errors in sub-expressions report the byte range of the sub-expression
+/// assert!("(3/2)^(1/0)".parse::<Ratio>().unwrap_err().contains("Invalid exponent '(1/0)' at 6..11"));
 impl FromStr for Ratio {
     type Err = String;
 
@@ -526,7 +571,7 @@ impl FromStr for RatioExpression {
 
     fn from_str(mut s: &str) -> Result<Self, Self::Err> {
         s = s.trim();
-        parse_ratio(s)
+        parse_ratio(s, s)
             .and_then(|representation| {
                 representation.as_ratio().map(|ratio| Self {
                     ratio,
@@ -555,6 +600,24 @@ pub enum RatioExpressionVariant {
     Cents {
         cents_value: f64,
     },
+    Composite {
+        operator: RatioOperator,
+        left: f64,
+        right: f64,
+    },
+}
+
+/// The top-level operator of a [`RatioExpressionVariant::Composite`] expression.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RatioOperator {
+    /// `a + b`, stacks two intervals, i.e. multiplies their ratios.
+    Stack,
+    /// `a * b`
+    Multiply,
+    /// `a / b` where `a` is a product or quotient itself. Plain fractions are parsed as [`RatioExpressionVariant::Fraction`].
+    Divide,
+    /// `a ^ b`
+    Power,
 }
 
 impl RatioExpressionVariant {
@@ -577,6 +640,15 @@ impl RatioExpressionVariant {
                 interval,
             } => interval.powf(numer / denom),
             Self::Cents { cents_value } => Ratio::from_cents(cents_value).as_float(),
+            Self::Composite {
+                operator,
+                left,
+                right,
+            } => match operator {
+                RatioOperator::Stack | RatioOperator::Multiply => left * right,
+                RatioOperator::Divide => left / right,
+                RatioOperator::Power => left.powf(right),
+            },
         };
         if as_float.is_finite() {
             Ok(as_float)
@@ -586,40 +658,144 @@ impl RatioExpressionVariant {
     }
 }
 
-fn parse_ratio(s: &str) -> Result<RatioExpressionVariant, String> {
+/// Parses the sub-expression `s` of the full expression `expr`.
+fn parse_ratio(expr: &str, s: &str) -> Result<RatioExpressionVariant, String> {
     let s = s.trim();
-    if let [numer, denom, interval] = parse::split_balanced(s, ':').as_slice() {
+    if !parse::is_balanced(s) {
+        Err("Unbalanced parentheses".to_string())
+    } else if let Some(interval) = find_named_interval(s) {
+        Ok(match interval.as_fraction() {
+            Some((numer, denom)) => RatioExpressionVariant::Fraction {
+                numer: numer as f64,
+                denom: denom as f64,
+            },
+            None => RatioExpressionVariant::Float {
+                float_value: interval.as_ratio().as_float(),
+            },
+        })
+    } else if let [numer, denom, interval] = parse::split_balanced(s, ':').as_slice() {
         Ok(RatioExpressionVariant::IntervalFraction {
-            numer: parse_ratio_as_float(numer, "interval numerator")?,
-            denom: parse_ratio_as_float(denom, "interval denominator")?,
-            interval: parse_ratio_as_float(interval, "interval")?,
+            numer: parse_ratio_as_float(expr, numer, "interval numerator")?,
+            denom: parse_ratio_as_float(expr, denom, "interval denominator")?,
+            interval: parse_ratio_as_float(expr, interval, "interval")?,
         })
-    } else if let [numer, denom] = parse::split_balanced(s, '/').as_slice() {
-        Ok(RatioExpressionVariant::Fraction {
-            numer: parse_ratio_as_float(numer, "numerator")?,
-            denom: parse_ratio_as_float(denom, "denominator")?,
+    } else if let Some((left, right)) = rsplit_summand(s) {
+        Ok(RatioExpressionVariant::Composite {
+            operator: RatioOperator::Stack,
+            left: parse_ratio_as_float(expr, left, "summand")?,
+            right: parse_ratio_as_float(expr, right, "summand")?,
+        })
+    } else if let Some((left, operator, right)) = parse::rsplit_once_balanced(s, &['*', '/']) {
+        let (left_name, right_name) = match operator {
+            '/' => ("numerator", "denominator"),
+            _ => ("factor", "factor"),
+        };
+        let left_value = parse_ratio_as_float(expr, left, left_name)?;
+        let right_value = parse_ratio_as_float(expr, right, right_name)?;
+        match operator {
+            '/' if parse::rsplit_once_balanced(left, &['*', '/']).is_none() => {
+                Ok(RatioExpressionVariant::Fraction {
+                    numer: left_value,
+                    denom: right_value,
+                })
+            }
+            '/' => Ok(RatioExpressionVariant::Composite {
+                operator: RatioOperator::Divide,
+                left: left_value,
+                right: right_value,
+            }),
+            _ => Ok(RatioExpressionVariant::Composite {
+                operator: RatioOperator::Multiply,
+                left: left_value,
+                right: right_value,
+            }),
+        }
+    } else if let Some((num_steps, divisions)) = parse::split_once_balanced(s, '\\') {
+        let (num_divisions, interval) = match divisions
+            .strip_suffix('>')
+            .and_then(|divisions| divisions.rsplit_once('<'))
+        {
+            Some((num_divisions, interval)) => (
+                num_divisions,
+                parse_ratio_as_float(expr, interval, "interval")?,
+            ),
+            None => (divisions, 2.0),
+        };
+        Ok(RatioExpressionVariant::IntervalFraction {
+            numer: parse_ratio_as_float(expr, num_steps, "number of steps")?,
+            denom: parse_ratio_as_float(expr, num_divisions, "number of divisions")?,
+            interval,
+        })
+    } else if let Some((base, exponent)) = parse::split_once_balanced(s, '^') {
+        Ok(RatioExpressionVariant::Composite {
+            operator: RatioOperator::Power,
+            left: parse_ratio_as_float(expr, base, "base")?,
+            right: parse_ratio_as_float(expr, exponent, "exponent")?,
         })
     } else if let [cents_value, ""] = parse::split_balanced(s, 'c').as_slice() {
         Ok(RatioExpressionVariant::Cents {
-            cents_value: parse_ratio_as_float(cents_value, "cents value")?,
+            cents_value: parse_ratio_as_float(expr, cents_value, "cents value")?,
         })
     } else if s.starts_with('(') && s.ends_with(')') {
-        parse_ratio(&s[1..s.len() - 1])
+        parse_ratio(expr, &s[1..s.len() - 1])
     } else {
         Ok(RatioExpressionVariant::Float {
             float_value: s.parse().map_err(|_| {
                 "Must be a float (e.g. 1.5), fraction (e.g. 3/2), \
-                 interval fraction (e.g. 7:12:2) or cents value (e.g. 702c)"
+                 interval fraction (e.g. 7:12:2), EDO step (e.g. 7\\12), \
+                 cents value (e.g. 702c) or interval name (e.g. syntonic comma)"
                     .to_string()
             })?,
         })
     }
 }
 
-fn parse_ratio_as_float(s: &str, name: &str) -> Result<f64, String> {
-    parse_ratio(s)
+fn find_named_interval(s: &str) -> Option<&'static Comma> {
+    static CATALOG: OnceLock<CommaCatalog> = OnceLock::new();
+
+    if !s.starts_with(char::is_alphabetic) && !s.ends_with(char::is_alphabetic) {
+        return None;
+    }
+
+    CATALOG
+        .get_or_init(|| CommaCatalog::new(temperament::huygens_fokker_intervals()))
+        .comma_for_name(s)
+}
+
+fn parse_ratio_as_float(expr: &str, s: &str, name: &str) -> Result<f64, String> {
+    parse_ratio(expr, s)
         .and_then(RatioExpressionVariant::as_float)
-        .map_err(|e| format!("Invalid {name} '{s}': {e}"))
+        .map_err(|e| {
+            // `s` is always a subslice of `expr`
+            let start = s.as_ptr() as usize - expr.as_ptr() as usize;
+            let end = start + s.len();
+            format!("Invalid {name} '{s}' at {start}..{end}: {e}")
+        })
+}
+
+/// Splits off the last summand of `s`, ignoring parenthesized `+` signs and exponent signs of float literals, e.g. `1e+2`.
+fn rsplit_summand(s: &str) -> Option<(&str, &str)> {
+    let mut num_parens = 0;
+    let mut last_match = None;
+    let mut preceding_chars: (Option<char>, Option<char>) = (None, None);
+    for (index, c) in s.char_indices() {
+        match c {
+            '(' => num_parens += 1,
+            ')' => num_parens -= 1,
+            '+' if num_parens == 0 => {
+                let is_exponent_sign = matches!(
+                    preceding_chars,
+                    (Some(mantissa), Some('e' | 'E')) if mantissa.is_ascii_digit() || mantissa == '.'
+                );
+                if !is_exponent_sign {
+                    last_match = Some(index);
+                }
+            }
+            _ => {}
+        }
+        preceding_chars = (preceding_chars.1, Some(c));
+    }
+    last_match.map(|index| (s[..index].trim(), s[index + 1..].trim()))
 }
 
 /// An odd-limit nearest-fraction approximation fo a given [`Ratio`].
@@ -736,6 +912,29 @@ mod test {
             ("702c/3", 0.5000),    // 2^(702/1200)/3 - 702 cents divided by 3
             ("3/702c", 2.0000),    // 3/2^(702/1200) - 3 divided by 702 cents
             ("(1404/2)c", 1.5000), // 2^(702/1200) - 1402/2 cents
+            ("3/2 * 5/4", 1.8750),
+            ("3/2*5/4", 1.8750),
+            ("3/2 * 5/4 / 2", 0.9375),
+            ("3/2 / 5/4", 0.0750),   // ((3/2)/5)/4 - left associative
+            ("3/2 / (5/4)", 1.2000), // (3/2)/(5/4)
+            ("2^(7/12)", 1.4983),    // 2^(7/12) - 12-edo perfect fifth
+            ("(3/2)^4 / 4", 1.2656), // (3/2)^4/4 - pythagorean major third
+            ("2^3^2", 512.0000),     // 2^(3^2) - right associative
+            ("2 * 3^2", 18.0000),
+            ("7\\12", 1.4983), // 2^(7/12) - 12-edo perfect fifth
+            ("-12\\12", 0.5000),
+            ("7\\12 * 2", 2.9966),
+            ("6\\13<3>", 1.6604),   // 3^(6/13) - Bohlen-Pierce
+            ("700c + 3/2", 2.2474), // 2^(700/1200)*(3/2)
+            ("1200c + 1200c + 1200c", 8.0000),
+            ("syntonic comma", 1.0125),
+            ("Syntonic Comma", 1.0125),
+            ("3/4-tone", 1.0909), // 12/11
+            ("(3/2)^4 / syntonic comma / 4", 1.2500),
+            ("perfect fifth + major third", 1.8750),
+            ("1e+2c", 1.0595), // 2^(100/1200) - float literal with exponent sign
+            ("1E+2c + 1e+2c", 1.1225),
+            ("(1.5e+0)^2", 2.2500),
         ];
 
         for (input, expected) in test_cases.iter() {
@@ -761,17 +960,43 @@ mod test {
             ("1/0", "Invalid expression '1/0': Evaluates to inf"),
             (
                 "(1/0)c",
-                "Invalid expression '(1/0)c': Invalid cents value '(1/0)': Evaluates to inf",
+                "Invalid expression '(1/0)c': Invalid cents value '(1/0)' at 0..5: Evaluates to inf",
             ),
             (
                 "(1/x)c",
-                "Invalid expression '(1/x)c': Invalid cents value '(1/x)': Invalid denominator 'x': \
-                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2) or cents value (e.g. 702c)",
+                "Invalid expression '(1/x)c': Invalid cents value '(1/x)' at 0..5: Invalid denominator 'x' at 3..4: \
+                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2), \
+                 EDO step (e.g. 7\\12), cents value (e.g. 702c) or interval name (e.g. syntonic comma)",
             ),
             (
                 "   (1   /x )c ",
-                "Invalid expression '(1   /x )c': Invalid cents value '(1   /x )': Invalid denominator 'x': \
-                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2) or cents value (e.g. 702c)",
+                "Invalid expression '(1   /x )c': Invalid cents value '(1   /x )' at 0..9: Invalid denominator 'x' at 6..7: \
+                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2), \
+                 EDO step (e.g. 7\\12), cents value (e.g. 702c) or interval name (e.g. syntonic comma)",
+            ),
+            (
+                "3/2 * (5/4",
+                "Invalid expression '3/2 * (5/4': Unbalanced parentheses",
+            ),
+            (
+                "3/2 * 5/0",
+                "Invalid expression '3/2 * 5/0': Evaluates to inf",
+            ),
+            (
+                "(3/2)^(1/0)",
+                "Invalid expression '(3/2)^(1/0)': Invalid exponent '(1/0)' at 6..11: Evaluates to inf",
+            ),
+            (
+                "7\\x",
+                "Invalid expression '7\\x': Invalid number of divisions 'x' at 2..3: \
+                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2), \
+                 EDO step (e.g. 7\\12), cents value (e.g. 702c) or interval name (e.g. syntonic comma)",
+            ),
+            (
+                "700c + serial comma",
+                "Invalid expression '700c + serial comma': Invalid summand 'serial comma' at 7..19: \
+                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2), \
+                 EDO step (e.g. 7\\12), cents value (e.g. 702c) or interval name (e.g. syntonic comma)",
             ),
         ];
 
@@ -803,6 +1028,47 @@ mod test {
             "(0/3)c".parse::<RatioExpression>().unwrap().variant(),
             RatioExpressionVariant::Cents { .. }
         ));
+        assert!(matches!(
+            "7\\12".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::IntervalFraction { .. }
+        ));
+        assert!(matches!(
+            "3/2 * 5/4".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Composite { .. }
+        ));
+        assert!(matches!(
+            "(3/2)^4".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Composite { .. }
+        ));
+        assert!(matches!(
+            "700c + 3/2".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Composite {
+                operator: RatioOperator::Stack,
+                right: 1.5,
+                ..
+            }
+        ));
+        assert!(matches!(
+            "3/2 * 5/4 / 2"
+                .parse::<RatioExpression>()
+                .unwrap()
+                .variant(),
+            RatioExpressionVariant::Composite {
+                operator: RatioOperator::Divide,
+                right: 2.0,
+                ..
+            }
+        ));
+        assert!(matches!(
+            "syntonic comma"
+                .parse::<RatioExpression>()
+                .unwrap()
+                .variant(),
+            RatioExpressionVariant::Fraction {
+                numer: 81.0,
+                denom: 80.0
+            }
+        ));
     }
 
     #[test]
@@ -0,0 +1,193 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::pitch::Pitch;
+
+/// Metadata of Ableton's scale format (`.ascl`) stored as `! @ABL` directives in an [`Scl`](super::Scl) file.
+///
+/// Only the `REFERENCE_PITCH`, `NOTE_NAMES` and `SOURCE` directives are supported. Other directives are ignored on import.
+/// Since `! @ABL` directives are Scala comments, malformed directives are ignored as well instead of failing the import.
+///
+/// Double-quoted arguments can contain escaped quotes (`\"`) and backslashes (`\\`). On export, all arguments are quoted and escaped accordingly.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::scala::Scl;
+/// let ascl_file = [
+///     "! @ABL REFERENCE_PITCH 3 0 293.665",
+///     "! @ABL NOTE_NAMES D \"E half-flat\" F",
+///     "! @ABL SOURCE \"Example collection\"",
+///     "! @ABL LINK https://example.org",
+///     "Neutral triad",
+///     "3",
+///     "11/9",
+///     "3/2",
+///     "2",
+/// ];
+///
+/// let scl = Scl::import(ascl_file.join("\n").as_bytes()).unwrap();
+/// let abl_metadata = scl.abl_metadata();
+///
+/// let reference_pitch = abl_metadata.reference_pitch.unwrap();
+/// assert_eq!(reference_pitch.octave, 3);
+/// assert_eq!(reference_pitch.degree, 0);
+/// assert_approx_eq!(reference_pitch.pitch.as_hz(), 293.665);
+/// assert_eq!(abl_metadata.note_names.as_deref(), Some(&["D", "E half-flat", "F"].map(String::from)[..]));
+/// assert_eq!(abl_metadata.source.as_deref(), Some("Example collection"));
+///
+/// assert_eq!(
+///     scl.export().to_string().lines().collect::<Vec<_>>(),
+///     [
+///         "Neutral triad",
+///         "3",
+///         "11/9",
+///         "3/2",
+///         "2",
+///         "! @ABL REFERENCE_PITCH 3 0 293.665000",
+///         "! @ABL NOTE_NAMES \"D\" \"E half-flat\" \"F\"",
+///         "! @ABL SOURCE \"Example collection\"",
+///     ]
+/// );
+///
+/// // Malformed directives are ignored while escape sequences are resolved
+/// let ascl_file = [
+///     "! @ABL REFERENCE_PITCH 3 0",
+///     "! @ABL NOTE_NAMES \"D",
+///     "! @ABL SOURCE \"Quotes (\\\") and backslashes (\\\\)\"",
+///     "Octave",
+///     "1",
+///     "2",
+/// ];
+///
+/// let scl = Scl::import(ascl_file.join("\n").as_bytes()).unwrap();
+/// let abl_metadata = scl.abl_metadata();
+///
+/// assert!(abl_metadata.reference_pitch.is_none());
+/// assert!(abl_metadata.note_names.is_none());
+/// assert_eq!(abl_metadata.source.as_deref(), Some("Quotes (\") and backslashes (\\)"));
+///
+/// assert_eq!(
+///     scl.export().to_string().lines().last(),
+///     Some("! @ABL SOURCE \"Quotes (\\\") and backslashes (\\\\)\"")
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AblMetadata {
+    /// The `REFERENCE_PITCH` directive.
+    pub reference_pitch: Option<AblReferencePitch>,
+
+    /// The `NOTE_NAMES` directive listing one name per scale degree.
+    pub note_names: Option<Vec<String>>,
+
+    /// The `SOURCE` directive.
+    pub source: Option<String>,
+}
+
+/// Defines the absolute [`Pitch`] of the given scale degree in the given octave.
+///
+/// The octave number follows Ableton's convention where MIDI note 60 is in octave 3.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AblReferencePitch {
+    pub octave: i32,
+    pub degree: u16,
+    pub pitch: Pitch,
+}
+
+impl AblMetadata {
+    /// Returns `true` if no directive is set.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Parses the given comment line (without the leading `!`). Malformed directives are ignored.
+    pub(crate) fn consume_comment(&mut self, comment: &str) {
+        let _ = self.try_consume_comment(comment);
+    }
+
+    fn try_consume_comment(&mut self, comment: &str) -> Option<()> {
+        let Some(directive) = comment.trim().strip_prefix("@ABL") else {
+            return Some(());
+        };
+        let tokens = tokenize(directive)?;
+        let Some((name, args)) = tokens.split_first() else {
+            return Some(());
+        };
+
+        match name.as_str() {
+            "REFERENCE_PITCH" => {
+                let [octave, degree, hz] = args else {
+                    return None;
+                };
+                self.reference_pitch = Some(AblReferencePitch {
+                    octave: octave.parse().ok()?,
+                    degree: degree.parse().ok()?,
+                    pitch: Pitch::from_hz(hz.parse().ok()?),
+                });
+            }
+            "NOTE_NAMES" => self.note_names = Some(args.to_vec()),
+            "SOURCE" => self.source = Some(args.join(" ")),
+            _ => {}
+        }
+
+        Some(())
+    }
+}
+
+/// Splits the given string at whitespace respecting double-quoted sections with escape sequences. Returns `None` if a quote is not closed.
+fn tokenize(s: &str) -> Option<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = s.trim().chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => token.push(chars.next()?),
+                    c => token.push(c),
+                }
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                token.push(c);
+            }
+            tokens.push(token);
+        }
+    }
+    Some(tokens)
+}
+
+impl Display for AblMetadata {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(reference_pitch) = &self.reference_pitch {
+            writeln!(
+                f,
+                "! @ABL REFERENCE_PITCH {} {} {:.6}",
+                reference_pitch.octave,
+                reference_pitch.degree,
+                reference_pitch.pitch.as_hz()
+            )?;
+        }
+        if let Some(note_names) = &self.note_names {
+            write!(f, "! @ABL NOTE_NAMES")?;
+            for note_name in note_names {
+                write!(f, " \"{}\"", escape(note_name))?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(source) = &self.source {
+            writeln!(f, "! @ABL SOURCE \"{}\"", escape(source))?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
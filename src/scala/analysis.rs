@@ -0,0 +1,226 @@
+use crate::{
+    ji::{ApproximationStrategy, Fraction},
+    pergen::Mos,
+    pitch::Ratio,
+    tuning::Approximation,
+};
+
+use super::Scl;
+
+/// Structural properties of an [`Scl`] as computed by [`Scl::analyze`].
+///
+/// All properties refer to the scale degrees in ascending order, i.e. to the pitches returned by [`Scl::sorted_relative_pitch_of`].
+#[derive(Clone, Debug)]
+pub struct SclAnalysis {
+    /// Intervals between all pairs of scale degrees where `interval_matrix[degree][num_steps]` is the interval spanning `num_steps` steps upward from `degree`.
+    ///
+    /// `num_steps` ranges from 0 to the number of scale items (inclusive).
+    pub interval_matrix: Vec<Vec<Ratio>>,
+
+    /// Distinct step sizes of the scale in descending order.
+    pub step_sizes: Vec<Ratio>,
+
+    /// Position of each step of the scale within [`SclAnalysis::step_sizes`], e.g. `[0, 0, 1, 0, 0, 0, 1]` for the major scale.
+    pub step_pattern: Vec<usize>,
+
+    /// The *x*L*y*s [`Mos`] formed by the scale. The primary step is the large step.
+    ///
+    /// [`None`] if the scale does not consist of exactly two step sizes or if any generic interval comes in more than two sizes.
+    pub mos: Option<Mos<f64>>,
+
+    /// Rothenberg propriety of the scale.
+    pub propriety: Propriety,
+
+    /// Each generic interval, except for the period, comes in exactly two sizes.
+    pub has_myhill_property: bool,
+
+    /// The scale is an equal-step scale or a MOS whose large and small steps are consecutive multiples of a common unit.
+    pub is_maximally_even: bool,
+
+    /// The best JI interpretation of each scale degree, starting at degree 1 and ending at the period.
+    ///
+    /// [`None`] if the [`ApproximationStrategy`] does not provide any candidate.
+    pub ji_interpretations: Vec<Option<Approximation<Fraction>>>,
+}
+
+/// Rothenberg propriety, i.e. the relationship between the sizes of different generic intervals.
+///
+/// The variants are ordered from the strongest to the weakest property.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Propriety {
+    /// Every *k*-step interval is smaller than every (*k*+1)-step interval.
+    StrictlyProper,
+    /// Every *k*-step interval is smaller than or equal to every (*k*+1)-step interval.
+    Proper,
+    /// Some *k*-step interval is larger than some (*k*+1)-step interval.
+    Improper,
+}
+
+impl Scl {
+    /// Analyzes the structural properties of the current scale.
+    ///
+    /// The `strategy` is used to find the best JI interpretation of each scale degree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::ji::ApproximationStrategy;
+    /// # use tune::scala::Propriety;
+    /// # use tune::scala::Scl;
+    /// let strategy = ApproximationStrategy::PrimeLimit { prime_limit: 5, odd_limit: 15 };
+    ///
+    /// let edo_12_major = Scl::builder()
+    ///     .push_cents(200.0)
+    ///     .push_cents(400.0)
+    ///     .push_cents(500.0)
+    ///     .push_cents(700.0)
+    ///     .push_cents(900.0)
+    ///     .push_cents(1100.0)
+    ///     .push_cents(1200.0)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let analysis = edo_12_major.analyze(&strategy);
+    /// assert_approx_eq!(analysis.interval_matrix[1][3].as_cents(), 500.0);
+    /// assert_approx_eq!(analysis.interval_matrix[3][3].as_cents(), 600.0);
+    /// assert_eq!(analysis.step_sizes.len(), 2);
+    /// assert_eq!(analysis.step_pattern, [0, 0, 1, 0, 0, 0, 1]);
+    /// assert_eq!(analysis.mos.unwrap().num_primary_steps(), 5);
+    /// assert_eq!(analysis.mos.unwrap().num_secondary_steps(), 2);
+    /// assert_eq!(analysis.propriety, Propriety::Proper);
+    /// assert!(analysis.has_myhill_property);
+    /// assert!(analysis.is_maximally_even);
+    ///
+    /// let ji_interpretations = Vec::from_iter(analysis.ji_interpretations.iter().map(
+    ///     |approximation| approximation.as_ref().unwrap().approx_value.to_string(),
+    /// ));
+    /// assert_eq!(ji_interpretations, ["9/8", "5/4", "4/3", "3/2", "5/3", "15/8", "2/1"]);
+    ///
+    /// let ji_major = Scl::import(
+    ///     ["Ptolemy", "7", "9/8", "5/4", "4/3", "3/2", "5/3", "15/8", "2"]
+    ///         .join("\n")
+    ///         .as_bytes(),
+    /// )
+    /// .unwrap();
+    ///
+    /// let analysis = ji_major.analyze(&strategy);
+    /// assert_eq!(analysis.step_sizes.len(), 3);
+    /// assert_eq!(analysis.step_pattern, [0, 1, 2, 0, 1, 0, 2]);
+    /// assert!(analysis.mos.is_none());
+    /// assert_eq!(analysis.propriety, Propriety::StrictlyProper);
+    /// assert!(!analysis.has_myhill_property);
+    /// assert!(!analysis.is_maximally_even);
+    ///
+    /// let improper = Scl::builder()
+    ///     .push_cents(50.0)
+    ///     .push_cents(700.0)
+    ///     .push_cents(1200.0)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let analysis = improper.analyze(&strategy);
+    /// assert_eq!(analysis.step_sizes.len(), 3);
+    /// assert_eq!(analysis.propriety, Propriety::Improper);
+    /// ```
+    pub fn analyze(&self, strategy: &ApproximationStrategy) -> SclAnalysis {
+        let num_items = i32::from(self.num_items());
+
+        let interval_matrix = Vec::from_iter((0..num_items).map(|degree| {
+            let root = self.sorted_relative_pitch_of(degree);
+            Vec::from_iter((0..=num_items).map(|num_steps| {
+                self.sorted_relative_pitch_of(degree + num_steps)
+                    .deviation_from(root)
+            }))
+        }));
+
+        // Distinct sizes of each generic interval in descending order
+        let interval_sizes = Vec::from_iter((0..=num_items).map(|num_steps| {
+            distinct_sizes(
+                interval_matrix
+                    .iter()
+                    .map(|intervals| intervals[num_steps as usize]),
+            )
+        }));
+        let generic_intervals = interval_sizes
+            .get(1..usize::from(self.num_items()))
+            .unwrap_or_default();
+
+        let step_sizes = interval_sizes.get(1).cloned().unwrap_or_default();
+        let step_pattern = Vec::from_iter(interval_matrix.iter().map(|intervals| {
+            step_sizes
+                .iter()
+                .position(|&step_size| is_same_size(step_size, intervals[1]))
+                .unwrap()
+        }));
+
+        let mos = match step_sizes[..] {
+            [large_step, small_step] if generic_intervals.iter().all(|sizes| sizes.len() <= 2) => {
+                let num_large_steps = step_pattern.iter().filter(|&&step| step == 0).count();
+                Mos::new_normalized(
+                    u16::try_from(num_large_steps).unwrap(),
+                    u16::try_from(step_pattern.len() - num_large_steps).unwrap(),
+                    large_step.as_octaves(),
+                    small_step.as_octaves(),
+                )
+            }
+            _ => None,
+        };
+
+        let propriety = interval_sizes[1..]
+            .windows(2)
+            .map(|sizes| {
+                let largest_interval = sizes[0][0];
+                let smallest_interval = *sizes[1].last().unwrap();
+                if is_same_size(largest_interval, smallest_interval) {
+                    Propriety::Proper
+                } else if largest_interval > smallest_interval {
+                    Propriety::Improper
+                } else {
+                    Propriety::StrictlyProper
+                }
+            })
+            .max()
+            .unwrap_or(Propriety::StrictlyProper);
+
+        let has_myhill_property =
+            !generic_intervals.is_empty() && generic_intervals.iter().all(|sizes| sizes.len() == 2);
+
+        let is_maximally_even = match step_sizes[..] {
+            [_] => true,
+            [large_step, small_step] if mos.is_some() => {
+                let num_units =
+                    small_step.as_cents() / (large_step.as_cents() - small_step.as_cents());
+                num_units.round() >= 1.0 && (num_units - num_units.round()).abs() < 1e-6
+            }
+            _ => false,
+        };
+
+        let ji_interpretations = Vec::from_iter(
+            (1..=num_items)
+                .map(|degree| strategy.best_approximation(self.sorted_relative_pitch_of(degree))),
+        );
+
+        SclAnalysis {
+            interval_matrix,
+            step_sizes,
+            step_pattern,
+            mos,
+            propriety,
+            has_myhill_property,
+            is_maximally_even,
+            ji_interpretations,
+        }
+    }
+}
+
+fn distinct_sizes(intervals: impl IntoIterator<Item = Ratio>) -> Vec<Ratio> {
+    let mut sizes = Vec::from_iter(intervals);
+    sizes.sort_by(|a, b| b.total_cmp(a));
+    sizes.dedup_by(|a, b| is_same_size(*a, *b));
+    sizes
+}
+
+fn is_same_size(a: Ratio, b: Ratio) -> bool {
+    a.deviation_from(b).is_negligible()
+}
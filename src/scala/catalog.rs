@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+
+use super::{PitchValue, Scl, SclBuildError};
+
+/// A scale with a name, e.g. a historical temperament, that can be stored in a [`ScaleCatalog`].
+#[derive(Clone, Debug)]
+pub struct NamedScale {
+    description: String,
+    pitch_values: Vec<PitchValue>,
+}
+
+impl NamedScale {
+    /// Creates a [`NamedScale`] with the given `description` and `pitch_values`.
+    ///
+    /// The `description` can contain multiple comma-separated names. The `pitch_values` exclude the implicit unison, i.e. the last value is the period of the scale.
+    pub fn new(description: impl Into<String>, pitch_values: impl Into<Vec<PitchValue>>) -> Self {
+        Self {
+            description: description.into(),
+            pitch_values: pitch_values.into(),
+        }
+    }
+
+    /// Returns the name/description of the [`NamedScale`].
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the primary name of the [`NamedScale`], i.e. the first comma-separated name of the description.
+    pub fn name(&self) -> &str {
+        self.description
+            .split(',')
+            .next()
+            .unwrap_or_default()
+            .trim()
+    }
+
+    /// Returns the [`PitchValue`]s of the [`NamedScale`], excluding the implicit unison.
+    pub fn pitch_values(&self) -> &[PitchValue] {
+        &self.pitch_values
+    }
+
+    /// Creates an [`Scl`] using the primary name as the description.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala;
+    /// # use tune::scala::ScaleCatalog;
+    /// let catalog = ScaleCatalog::new(scala::historical_temperaments());
+    ///
+    /// let scl = catalog.scale_for_name("Werckmeister III").unwrap().to_scl().unwrap();
+    ///
+    /// assert_eq!(
+    ///     scl.export().to_string().lines().collect::<Vec<_>>(),
+    ///     ["Werckmeister III", "12",
+    ///      "90.225", "192.180", "294.135", "390.225", "498.045", "588.270",
+    ///      "696.090", "792.180", "888.270", "996.090", "1092.180", "2"]
+    /// );
+    /// ```
+    pub fn to_scl(&self) -> Result<Scl, SclBuildError> {
+        self.pitch_values
+            .iter()
+            .fold(Scl::builder(), |builder, &pitch_value| {
+                builder.push_pitch_value(pitch_value)
+            })
+            .build_with_description(self.name())
+    }
+}
+
+/// A collection of [`NamedScale`]s that can be looked up by name.
+pub struct ScaleCatalog {
+    scales: Vec<NamedScale>,
+    scale_index_by_name: HashMap<String, usize>,
+}
+
+impl ScaleCatalog {
+    /// Creates a [`ScaleCatalog`] from a given set of [`NamedScale`]s.
+    pub fn new(scales: Vec<NamedScale>) -> Self {
+        let mut scale_index_by_name = HashMap::new();
+
+        for (index, scale) in scales.iter().enumerate() {
+            for name in scale.description().split(',') {
+                scale_index_by_name.insert(normalize(name), index);
+            }
+        }
+
+        Self {
+            scales,
+            scale_index_by_name,
+        }
+    }
+}
+
+impl ScaleCatalog {
+    /// Returns all [`NamedScale`]s of the catalog in the order of their declaration.
+    pub fn scales(&self) -> &[NamedScale] {
+        &self.scales
+    }
+
+    /// Returns the [`NamedScale`] for the given `name`.
+    ///
+    /// Names are compared case-insensitively. Spaces, hyphens and underscores are treated as equivalent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala;
+    /// # use tune::scala::ScaleCatalog;
+    /// let catalog = ScaleCatalog::new(scala::historical_temperaments());
+    ///
+    /// assert_eq!(
+    ///     catalog.scale_for_name("quarter-comma meantone").unwrap().description(),
+    ///     "1/4-comma meantone, quarter-comma meantone"
+    /// );
+    /// assert_eq!(
+    ///     catalog.scale_for_name("QUARTER_COMMA_MEANTONE").unwrap().description(),
+    ///     "1/4-comma meantone, quarter-comma meantone"
+    /// );
+    /// assert!(catalog.scale_for_name("Werckmeister VII").is_none());
+    /// ```
+    pub fn scale_for_name(&self, name: &str) -> Option<&NamedScale> {
+        let &index = self.scale_index_by_name.get(&normalize(name))?;
+        self.scales.get(index)
+    }
+
+    /// Returns all [`NamedScale`]s where any of the names contains `query`.
+    ///
+    /// The same normalization rules as in [`ScaleCatalog::scale_for_name`] apply.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala;
+    /// # use tune::scala::ScaleCatalog;
+    /// let catalog = ScaleCatalog::new(scala::historical_temperaments());
+    ///
+    /// assert_eq!(
+    ///     catalog.search("kirnberger").map(|scale| scale.name()).collect::<Vec<_>>(),
+    ///     ["Kirnberger II", "Kirnberger III"]
+    /// );
+    /// assert_eq!(catalog.search("meantone").count(), 5);
+    /// assert_eq!(catalog.search("serial").count(), 0);
+    /// ```
+    pub fn search<'a>(&'a self, query: &str) -> impl Iterator<Item = &'a NamedScale> + 'a {
+        let query = normalize(query);
+        self.scales.iter().filter(move |scale| {
+            scale
+                .description()
+                .split(',')
+                .any(|name| normalize(name).contains(&query))
+        })
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace(['-', '_'], " ")
+}
+
+/// Returns a selection of well-known 12-note historical temperaments, meantones and Pythagorean tuning.
+///
+/// All scales start on C. The chain of fifths of the meantones and of Pythagorean tuning ranges from E♭ to G♯.
+pub fn historical_temperaments() -> Vec<NamedScale> {
+    let scales: &[(&str, [f64; 11])] = &[
+        (
+            "Werckmeister III, Werckmeister",
+            [
+                90.225, 192.180, 294.135, 390.225, 498.045, 588.270, 696.090, 792.180, 888.270,
+                996.090, 1092.180,
+            ],
+        ),
+        (
+            "Kirnberger II",
+            [
+                90.225, 203.910, 294.135, 386.314, 498.045, 590.224, 701.955, 792.180, 895.112,
+                996.090, 1088.269,
+            ],
+        ),
+        (
+            "Kirnberger III, Kirnberger",
+            [
+                90.225, 193.157, 294.135, 386.314, 498.045, 590.224, 696.578, 792.180, 889.735,
+                996.090, 1088.269,
+            ],
+        ),
+        (
+            "Vallotti, Vallotti-Barca",
+            [
+                94.135, 196.090, 298.045, 392.180, 501.955, 592.180, 698.045, 796.090, 894.135,
+                1000.000, 1090.225,
+            ],
+        ),
+        (
+            "Young II, Young",
+            [
+                90.225, 196.090, 294.135, 392.180, 498.045, 588.270, 698.045, 792.180, 894.135,
+                996.090, 1090.225,
+            ],
+        ),
+        (
+            "1/3-comma meantone, third-comma meantone",
+            [
+                63.504, 189.572, 315.641, 379.145, 505.214, 568.717, 694.786, 758.290, 884.359,
+                1010.428, 1073.931,
+            ],
+        ),
+        (
+            "2/7-comma meantone, Zarlino",
+            [
+                70.672, 191.621, 312.569, 383.241, 504.190, 574.862, 695.810, 766.483, 887.431,
+                1008.379, 1079.052,
+            ],
+        ),
+        (
+            "1/4-comma meantone, quarter-comma meantone",
+            [
+                76.049, 193.157, 310.265, 386.314, 503.422, 579.471, 696.578, 772.627, 889.735,
+                1006.843, 1082.892,
+            ],
+        ),
+        (
+            "1/5-comma meantone, fifth-comma meantone",
+            [
+                83.576, 195.307, 307.039, 390.615, 502.346, 585.922, 697.654, 781.230, 892.961,
+                1004.693, 1088.269,
+            ],
+        ),
+        (
+            "1/6-comma meantone, sixth-comma meantone",
+            [
+                88.594, 196.741, 304.888, 393.482, 501.629, 590.224, 698.371, 786.965, 895.112,
+                1003.259, 1091.853,
+            ],
+        ),
+        (
+            "12-EDO, equal temperament",
+            [
+                100.0, 200.0, 300.0, 400.0, 500.0, 600.0, 700.0, 800.0, 900.0, 1000.0, 1100.0,
+            ],
+        ),
+    ];
+
+    let pythagorean = NamedScale::new(
+        "Pythagorean, Pythagorean tuning",
+        [
+            (2187, 2048),
+            (9, 8),
+            (32, 27),
+            (81, 64),
+            (4, 3),
+            (729, 512),
+            (3, 2),
+            (6561, 4096),
+            (27, 16),
+            (16, 9),
+            (243, 128),
+        ]
+        .map(|(numer, denom)| PitchValue::Fraction(numer, Some(denom)))
+        .into_iter()
+        .chain([PitchValue::Fraction(2, None)])
+        .collect::<Vec<_>>(),
+    );
+
+    scales
+        .iter()
+        .map(|(description, cents_values)| {
+            NamedScale::new(
+                *description,
+                cents_values
+                    .iter()
+                    .map(|&cents_value| PitchValue::Cents(cents_value))
+                    .chain([PitchValue::Fraction(2, None)])
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .chain([pythagorean])
+        .collect()
+}
@@ -0,0 +1,236 @@
+use std::iter;
+
+use crate::{ji::JiRatio, math, temperament::Comma};
+
+use super::{Scl, SclBuildError};
+
+/// Creates a Fokker periodicity block spanned by the given `commas` (unison vectors).
+///
+/// The first element of `basis` is the prime used as the equivalence interval, e.g. 2. The remaining primes span the lattice from which the notes of the block are selected.
+/// Thus, the number of `commas` needs to be one less than the number of primes in `basis`.
+///
+/// A lattice point is part of the block if its coordinate *t<sub>i</sub>* relative to the *i*-th comma satisfies `shift[i] <= t_i < shift[i] + 1`.
+/// Missing `shift` values are treated as 0. A value of -0.5 centers the block around 1/1 in the direction of the corresponding comma.
+///
+/// The number of notes in the block equals the absolute value of the determinant of the comma matrix.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::scala;
+/// # use tune::temperament::Comma;
+/// let syntonic_comma = Comma::new("syntonic comma", &[-4, 4, -1][..]);
+/// let diesis = Comma::new("diesis", &[7, 0, -3][..]);
+///
+/// let block = scala::create_fokker_block_scale(
+///     None,
+///     &[syntonic_comma, diesis],
+///     &[2, 3, 5],
+///     &[-0.5, -0.5],
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     block.export().to_string().lines().collect::<Vec<_>>(),
+///     ["Fokker block of 81/80, 128/125",
+///      "12", "16/15", "10/9", "6/5", "5/4", "4/3", "25/18",
+///      "3/2", "8/5", "5/3", "16/9", "15/8", "2"]
+/// );
+/// ```
+pub fn create_fokker_block_scale(
+    description: impl Into<Option<String>>,
+    commas: &[Comma],
+    basis: &[u8],
+    shift: &[f64],
+) -> Result<Scl, FokkerBlockError> {
+    let (&period, lattice_primes) = basis.split_first().ok_or(FokkerBlockError::InvalidBasis)?;
+    if commas.len() != lattice_primes.len() {
+        return Err(FokkerBlockError::DimensionMismatch);
+    }
+
+    let prime_indexes = basis
+        .iter()
+        .map(|prime| math::U8_PRIMES.iter().position(|p| p == prime))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(FokkerBlockError::InvalidBasis)?;
+    let (&period_index, lattice_prime_indexes) = prime_indexes.split_first().unwrap();
+
+    let comma_matrix =
+        commas
+            .iter()
+            .map(|comma| {
+                let prime_factors = comma.prime_factors();
+                let is_within_basis = prime_factors
+                    .iter()
+                    .enumerate()
+                    .all(|(index, &power)| power == 0 || prime_indexes.contains(&index));
+                is_within_basis.then(|| {
+                    Vec::from_iter(lattice_prime_indexes.iter().map(|&index| {
+                        f64::from(prime_factors.get(index).copied().unwrap_or_default())
+                    }))
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(FokkerBlockError::PrimeOutsideBasis)?;
+
+    let inverse = invert(&comma_matrix).ok_or(FokkerBlockError::LinearlyDependent)?;
+
+    let shift = Vec::from_iter(
+        (0..commas.len()).map(|index| shift.get(index).copied().unwrap_or_default()),
+    );
+    if shift.iter().any(|&shift| !(-1.0 < shift && shift <= 0.0)) {
+        return Err(FokkerBlockError::InvalidShift);
+    }
+
+    // Bounding box of the parallelepiped spanned by the shifted commas
+    let bounds = Vec::from_iter((0..lattice_primes.len()).map(|dim| {
+        comma_matrix
+            .iter()
+            .zip(&shift)
+            .fold((0.0, 0.0), |(min, max), (row, &shift)| {
+                let lower = shift * row[dim];
+                let upper = (shift + 1.0) * row[dim];
+                (min + lower.min(upper), max + lower.max(upper))
+            })
+    }));
+    let ranges = Vec::from_iter(
+        bounds
+            .iter()
+            .map(|&(min, max)| (min.floor() as i32, max.ceil() as i32)),
+    );
+
+    let mut ratios = Vec::new();
+    let mut lattice_point = Vec::from_iter(ranges.iter().map(|&(min, _)| min));
+    loop {
+        let is_within_block = (0..commas.len()).all(|comma_index| {
+            let coordinate: f64 = lattice_point
+                .iter()
+                .zip(&inverse)
+                .map(|(&exponent, row)| f64::from(exponent) * row[comma_index])
+                .sum();
+            let shift = shift[comma_index];
+            coordinate >= shift - EPSILON && coordinate < shift + 1.0 - EPSILON
+        });
+
+        if is_within_block && lattice_point.iter().any(|&exponent| exponent != 0) {
+            let mut prime_factors =
+                vec![0; lattice_prime_indexes.iter().max().map_or(0, |&max| max + 1)];
+            for (&index, &exponent) in lattice_prime_indexes.iter().zip(&lattice_point) {
+                prime_factors[index] = exponent;
+            }
+            ratios.push(reduce_into_period(
+                JiRatio::from_prime_factors(prime_factors).unwrap(),
+                period,
+                period_index,
+            ));
+        }
+
+        if !advance(&mut lattice_point, &ranges) {
+            break;
+        }
+    }
+    ratios.sort_by(|a, b| a.as_ratio().total_cmp(&b.as_ratio()));
+
+    let description = description.into().unwrap_or_else(|| {
+        format!(
+            "Fokker block of {}",
+            Vec::from_iter(commas.iter().map(|comma| match comma.as_fraction() {
+                Some((numer, denom)) => format!("{numer}/{denom}"),
+                None => comma.description().to_owned(),
+            }))
+            .join(", ")
+        )
+    });
+
+    let period = JiRatio::from_int(period.into()).unwrap();
+    ratios
+        .iter()
+        .chain(iter::once(&period))
+        .fold(Scl::builder(), |builder, ratio| {
+            builder.push_ji_ratio(ratio)
+        })
+        .build_with_description(description)
+        .map_err(FokkerBlockError::BuildError)
+}
+
+/// Error reported when creating a Fokker periodicity block fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FokkerBlockError {
+    /// The basis is empty or contains a number that is not a prime number of type [`u8`].
+    InvalidBasis,
+
+    /// The number of commas does not match the number of lattice primes in the basis.
+    DimensionMismatch,
+
+    /// A comma contains a prime factor that is not part of the basis.
+    PrimeOutsideBasis,
+
+    /// The commas do not span the full lattice.
+    LinearlyDependent,
+
+    /// A shift value is outside of the range (-1, 0], i.e. the block would not contain 1/1.
+    InvalidShift,
+
+    /// The resulting scale could not be built.
+    BuildError(SclBuildError),
+}
+
+const EPSILON: f64 = 1e-9;
+
+fn reduce_into_period(ratio: JiRatio, period: u8, period_index: usize) -> JiRatio {
+    let num_periods = (ratio.as_ratio().as_octaves() / f64::from(period).log2() + EPSILON).floor();
+    let mut period_factors = vec![0; period_index + 1];
+    period_factors[period_index] = -(num_periods as i32);
+    ratio * JiRatio::from_prime_factors(period_factors).unwrap()
+}
+
+/// Inverts the given square matrix using Gauss-Jordan elimination.
+fn invert(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let size = matrix.len();
+    let mut augmented = Vec::from_iter(matrix.iter().enumerate().map(|(row_index, row)| {
+        Vec::from_iter(
+            row.iter()
+                .copied()
+                .chain((0..size).map(|col| f64::from(u8::from(col == row_index)))),
+        )
+    }));
+
+    for col in 0..size {
+        let pivot = (col..size)
+            .max_by(|&a, &b| augmented[a][col].abs().total_cmp(&augmented[b][col].abs()))?;
+        if augmented[pivot][col].abs() < EPSILON {
+            return None;
+        }
+        augmented.swap(col, pivot);
+
+        let pivot_value = augmented[col][col];
+        for value in &mut augmented[col] {
+            *value /= pivot_value;
+        }
+        for row in 0..size {
+            if row != col {
+                let factor = augmented[row][col];
+                let pivot_row = augmented[col].clone();
+                for (value, pivot_value) in augmented[row].iter_mut().zip(pivot_row) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+    }
+
+    Some(Vec::from_iter(
+        augmented.into_iter().map(|row| row[size..].to_vec()),
+    ))
+}
+
+/// Moves `point` to the next lattice point within `ranges`. Returns `false` if all points have been visited.
+fn advance(point: &mut [i32], ranges: &[(i32, i32)]) -> bool {
+    for (coordinate, &(min, max)) in point.iter_mut().zip(ranges) {
+        if *coordinate < max {
+            *coordinate += 1;
+            return true;
+        }
+        *coordinate = min;
+    }
+    false
+}
@@ -6,14 +6,20 @@ use std::{
 
 use crate::{key::PianoKey, pitch::Pitch};
 
-use super::{Kbm, KbmBuildError, KbmBuilder, KbmRoot, Scl, SclBuildError, SclBuilder};
+use super::{AblMetadata, Kbm, KbmBuildError, KbmBuilder, KbmRoot, Scl, SclBuildError, SclBuilder};
 
 pub(crate) fn import_scl(reader: impl Read) -> Result<Scl, SclImportError> {
     let importer = SclImporter::ExpectingDescription;
-    consume_lines(importer, reader, |i, line_number, line| {
-        i.consume(line_number, line)
-    })
-    .and_then(|i| i.finalize())
+    let mut abl_metadata = AblMetadata::default();
+    let mut scl = consume_lines(
+        importer,
+        reader,
+        |i, line_number, line| i.consume(line_number, line),
+        |comment| abl_metadata.consume_comment(comment),
+    )
+    .and_then(|i| i.finalize())?;
+    scl.set_abl_metadata(abl_metadata);
+    Ok(scl)
 }
 
 enum SclImporter {
@@ -128,9 +134,12 @@ impl From<SclBuildError> for SclImportError {
 
 pub(crate) fn import_kbm(reader: impl Read) -> Result<Kbm, KbmImportError> {
     let importer = KbmImporter::ExpectingMapSize;
-    consume_lines(importer, reader, |i, line_number, line| {
-        i.consume(line_number, line)
-    })
+    consume_lines(
+        importer,
+        reader,
+        |i, line_number, line| i.consume(line_number, line),
+        |_| {},
+    )
     .and_then(|i| i.finalize())
 }
 
@@ -291,11 +300,14 @@ pub(crate) fn consume_lines<I, R: From<io::Error>>(
     mut importer: I,
     reader: impl Read,
     mut consume: impl FnMut(I, usize, &str) -> Result<I, R>,
+    mut consume_comment: impl FnMut(&str),
 ) -> Result<I, R> {
     for (line_number, line) in BufReader::new(reader).lines().enumerate() {
         let line = line?;
         let trimmed = line.trim();
-        if !trimmed.is_empty() && !trimmed.starts_with('!') {
+        if let Some(comment) = trimmed.strip_prefix('!') {
+            consume_comment(comment);
+        } else if !trimmed.is_empty() {
             importer = consume(importer, line_number + 1, trimmed)?;
         }
     }
@@ -1,6 +1,12 @@
 //! Interop with [Scala](http://www.huygens-fokker.org/scala/) tuning files.
 
+mod ableton;
+mod analysis;
+mod catalog;
+mod fokker;
 mod import;
+mod products;
+mod transform;
 
 use std::{
     borrow::Borrow,
@@ -11,15 +17,16 @@ use std::{
 };
 
 use crate::{
-    key::PianoKey,
+    ji::JiRatio,
+    key::{HexKey, HexMapping, PianoKey},
     math,
-    note::{Note, PitchedNote},
-    parse,
+    note::{NoteNameParser, PitchedNote},
+    pergen::MosMode,
     pitch::{Pitch, Ratio},
     tuning::{Approximation, KeyboardMapping, Scale, Tuning},
 };
 
-pub use self::import::*;
+pub use self::{ableton::*, analysis::*, catalog::*, fokker::*, import::*, products::*};
 
 /// Scale format according to <http://www.huygens-fokker.org/scala/scl_format.html>.
 ///
@@ -54,6 +61,7 @@ pub struct Scl {
     num_items: u16,
     pitch_values: Vec<PitchValue>,
     sorted_pitch_values: Vec<SortedPitchValue>,
+    abl_metadata: AblMetadata,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -79,6 +87,15 @@ impl Scl {
         &self.description
     }
 
+    /// Returns the Ableton metadata (`! @ABL` directives) of the scale, see [`AblMetadata`].
+    pub fn abl_metadata(&self) -> &AblMetadata {
+        &self.abl_metadata
+    }
+
+    pub fn set_abl_metadata(&mut self, abl_metadata: AblMetadata) {
+        self.abl_metadata = abl_metadata
+    }
+
     pub fn period(&self) -> Ratio {
         self.period
     }
@@ -87,6 +104,32 @@ impl Scl {
         self.num_items
     }
 
+    /// Returns the [`PitchValue`]s as declared in the scale file, i.e. excluding the implicit unison.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::PitchValue;
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder()
+    ///     .push_fraction(5, 4)
+    ///     .push_cents(700.0)
+    ///     .push_int(2)
+    ///     .build().unwrap();
+    ///
+    /// assert_eq!(
+    ///     scl.pitch_values(),
+    ///     [
+    ///         PitchValue::Fraction(5, Some(4)),
+    ///         PitchValue::Cents(700.0),
+    ///         PitchValue::Fraction(2, None)
+    ///     ]
+    /// );
+    /// ```
+    pub fn pitch_values(&self) -> &[PitchValue] {
+        &self.pitch_values[1..]
+    }
+
     /// Retrieves relative pitches without requiring any [`Kbm`] reference.
     ///
     /// # Examples
@@ -395,7 +438,35 @@ impl SclBuilder {
         self.push_pitch_value(PitchValue::Fraction(numer, Some(denom)))
     }
 
-    fn push_pitch_value(mut self, pitch_value: PitchValue) -> Self {
+    /// Pushes a [`JiRatio`] as an exact fraction if it fits into a [`PitchValue::Fraction`] and as a cents value otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// # use tune::scala::Scl;
+    /// let fifth = JiRatio::from_fraction(3, 2).unwrap();
+    /// let scl = Scl::builder()
+    ///     .push_ji_ratio(&fifth.pow(2).unwrap().octave_reduced())
+    ///     .push_ji_ratio(&fifth.pow(30).unwrap().octave_reduced())
+    ///     .push_ji_ratio(&JiRatio::octave())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     scl.export().to_string().lines().collect::<Vec<_>>(),
+    ///     ["Custom scale", "3", "9/8", "658.650", "2"]
+    /// );
+    /// ```
+    pub fn push_ji_ratio(self, ji_ratio: &JiRatio) -> Self {
+        match ji_ratio.to_pitch_value() {
+            Some(pitch_value) => self.push_pitch_value(pitch_value),
+            None => self.push_ratio(ji_ratio.as_ratio()),
+        }
+    }
+
+    /// Pushes a Scala [`PitchValue`] as is, i.e. without converting it to a cents value.
+    pub fn push_pitch_value(mut self, pitch_value: PitchValue) -> Self {
         self.pitch_values.push(pitch_value);
         self
     }
@@ -469,6 +540,7 @@ impl SclBuilder {
             num_items,
             pitch_values: self.pitch_values,
             sorted_pitch_values,
+            abl_metadata: AblMetadata::default(),
         })
     }
 }
@@ -511,16 +583,41 @@ pub enum SclBuildError {
     /// assert_eq!(above.build().unwrap_err(), SclBuildError::ScaleTooLarge);
     /// ```
     ScaleTooLarge,
+
+    /// The steps of a MOS scale do not add up to a positive, finite size, e.g. because the MOS is empty or the step sizes are zero.
+    ///
+    /// ```
+    /// # use tune::pergen::{Mos, MosMode};
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala;
+    /// # use tune::scala::SclBuildError;
+    /// let empty_mode = MosMode { pattern: String::new(), num_up: 0, num_down: 0, num_periods: 1 };
+    /// assert_eq!(
+    ///     scala::create_mos_mode_scale(None, &empty_mode, 2.0, 1.0, Ratio::octave()).unwrap_err(),
+    ///     SclBuildError::InvalidMosSteps
+    /// );
+    ///
+    /// let ionian = &Mos::new(5, 2, 2, 1).unwrap().modes()[1];
+    /// assert_eq!(
+    ///     scala::create_mos_mode_scale(None, ionian, 0.0, 0.0, Ratio::octave()).unwrap_err(),
+    ///     SclBuildError::InvalidMosSteps
+    /// );
+    /// ```
+    InvalidMosSteps,
 }
 
-#[derive(Copy, Clone, Debug)]
-enum PitchValue {
+/// A single pitch line of an [`Scl`] file.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PitchValue {
+    /// A pitch given in cents, e.g. `701.955`.
     Cents(f64),
+    /// A pitch given as an integer or a fraction, e.g. `3` or `3/2`.
     Fraction(u32, Option<u32>),
 }
 
 impl PitchValue {
-    fn as_ratio(self) -> Ratio {
+    /// Converts the [`PitchValue`] into a float-based [`Ratio`].
+    pub fn as_ratio(self) -> Ratio {
         match self {
             PitchValue::Cents(cents_value) => Ratio::from_cents(cents_value),
             PitchValue::Fraction(numer, denom) => {
@@ -551,7 +648,7 @@ impl<'a> Display for SclExport<'a> {
         for pitch_value in pitch_values_to_export {
             writeln!(f, "{pitch_value}")?;
         }
-        Ok(())
+        write!(f, "{}", self.0.abl_metadata)
     }
 }
 
@@ -856,44 +953,50 @@ impl<N: PitchedNote> From<N> for KbmRoot {
     }
 }
 
+/// Parses a [`KbmRoot`] from a note expression (see [`NoteNameParser`]).
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::key::PianoKey;
+/// # use tune::scala::KbmRoot;
+/// let kbm_root = "69@432Hz".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(69));
+/// assert_approx_eq!(kbm_root.ref_pitch.as_hz(), 432.0);
+///
+/// let kbm_root = "C#4+20c".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(61));
+/// assert_approx_eq!(kbm_root.ref_pitch.as_hz(), 280.403338);
+///
+/// let kbm_root = "^D4".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(62));
+/// assert_approx_eq!(kbm_root.ref_pitch.as_hz(), 311.126983);
+///
+/// // Negative MIDI numbers
+/// let kbm_root = "-3".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(-3));
+/// assert_approx_eq!(kbm_root.ref_pitch.as_hz(), 6.875);
+///
+/// let kbm_root = "-3-1200c".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(-3));
+/// assert_approx_eq!(kbm_root.ref_pitch.as_hz(), 3.4375);
+///
+/// let kbm_root = "-3@10Hz".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(-3));
+/// assert_approx_eq!(kbm_root.ref_pitch.as_hz(), 10.0);
+///
+/// assert_eq!(
+///     "X4".parse::<KbmRoot>().unwrap_err(),
+///     "Must be a MIDI number (e.g. 69) or a note name (e.g. C#4, ^D4 or c'), \
+///      optionally followed by @<pitch> (e.g. @432Hz) or +/-<ratio> (e.g. +20c)"
+/// );
+/// ```
 impl FromStr for KbmRoot {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let [note, pitch] = parse::split_balanced(s, '@').as_slice() {
-            let midi_number = note
-                .parse::<i32>()
-                .map_err(|_| format!("Invalid note '{note}': Must be an integer"))?;
-            let pitch: Pitch = pitch
-                .parse()
-                .map_err(|e| format!("Invalid pitch '{pitch}': {e}"))?;
-            Ok(Note::from_midi_number(midi_number).at_pitch(pitch).into())
-        } else if let [note, delta] = parse::split_balanced(s, '+').as_slice() {
-            let midi_number = note
-                .parse::<i32>()
-                .map_err(|_| format!("Invalid note '{note}': Must be an integer"))?;
-            let delta = delta
-                .parse()
-                .map_err(|e| format!("Invalid delta '{delta}': {e}"))?;
-            Ok(Note::from_midi_number(midi_number)
-                .alter_pitch_by(delta)
-                .into())
-        } else if let [note, delta] = parse::split_balanced(s, '-').as_slice() {
-            let midi_number = note
-                .parse::<i32>()
-                .map_err(|_| format!("Invalid note '{note}': Must be an integer"))?;
-            let delta = delta
-                .parse::<Ratio>()
-                .map_err(|e| format!("Invalid delta '{delta}': {e}"))?;
-            Ok(Note::from_midi_number(midi_number)
-                .alter_pitch_by(delta.inv())
-                .into())
-        } else {
-            let note_number = s
-                .parse::<i32>()
-                .map_err(|_| "Must be an expression of type 69, 69@440Hz or 69+100c".to_string())?;
-            Ok(Note::from_midi_number(note_number).into())
-        }
+        NoteNameParser::default().parse_note(s).map(KbmRoot::from)
     }
 }
 
@@ -1055,6 +1158,83 @@ impl<S: Borrow<Scl>, K: Borrow<KbmRoot>> Tuning<i32> for (S, K) {
     }
 }
 
+/// Maps [`HexKey`]s to scale degrees using a [`HexMapping`].
+///
+/// The `origin` of the [`HexMapping`] plays the role of the `ref_key` of the [`KbmRoot`].
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::key::HexKey;
+/// # use tune::key::HexMapping;
+/// # use tune::note::NoteLetter;
+/// # use tune::pergen::Mos;
+/// # use tune::pitch::Pitch;
+/// # use tune::pitch::Ratio;
+/// # use tune::scala::KbmRoot;
+/// # use tune::scala::Scl;
+/// use tune::tuning::Tuning;
+///
+/// let scl = Scl::builder()
+///     .push_ratio(Ratio::octave().divided_into_equal_steps(31))
+///     .build()
+///     .unwrap();
+/// let kbm_root = KbmRoot::from(NoteLetter::D.in_octave(4));
+/// let mapping = HexMapping::new(Mos::new(5, 2, 5, 3).unwrap(), HexKey::from_coords(0, 0));
+///
+/// let tuning = (scl, kbm_root, mapping);
+///
+/// assert_approx_eq!(tuning.pitch_of(HexKey::from_coords(0, 0)).as_hz(), 293.664768);
+/// assert_approx_eq!(tuning.pitch_of(HexKey::from_coords(1, 0)).as_hz(), 328.401454);
+/// assert_approx_eq!(tuning.pitch_of(HexKey::from_coords(-1, 2)).as_hz(), 300.304950);
+///
+/// let approximation = tuning.find_by_pitch(Pitch::from_hz(330.0));
+/// assert_eq!(approximation.approx_value, HexKey::from_coords(1, 0));
+/// assert_approx_eq!(approximation.deviation.as_cents(), 8.406614);
+/// ```
+impl<S: Borrow<Scl>, K: Borrow<KbmRoot>, M: Borrow<HexMapping>> Tuning<HexKey> for (S, K, M) {
+    fn pitch_of(&self, key: HexKey) -> Pitch {
+        // Degrees beyond the range of i32 are far outside the audible range
+        let degree = self.2.borrow().degree_of(key);
+        let degree = degree.clamp(i32::MIN.into(), i32::MAX.into()) as i32;
+        (self.0.borrow(), self.1.borrow()).pitch_of(degree)
+    }
+
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<HexKey> {
+        let tuning = (self.0.borrow(), self.1.borrow());
+        let mapping = self.2.borrow();
+
+        // Only multiples of num_cycles are reachable
+        let num_cycles = i32::from(mapping.mos().num_cycles());
+        let degree: Approximation<i32> = tuning.find_by_pitch(pitch);
+        let lower_degree = match num_cycles {
+            0 => 0,
+            _ => degree.approx_value.div_euclid(num_cycles) * num_cycles,
+        };
+
+        [Some(lower_degree), lower_degree.checked_add(num_cycles)]
+            .into_iter()
+            .flatten()
+            .filter_map(|degree| {
+                Some(Approximation {
+                    approx_value: mapping.find_key(degree.into())?,
+                    deviation: Ratio::between_pitches(tuning.pitch_of(degree), pitch),
+                })
+            })
+            .min_by(|a, b| {
+                a.deviation
+                    .abs()
+                    .as_octaves()
+                    .total_cmp(&b.deviation.abs().as_octaves())
+            })
+            .unwrap_or_else(|| Approximation {
+                approx_value: mapping.origin(),
+                deviation: Ratio::between_pitches(tuning.pitch_of(0), pitch),
+            })
+    }
+}
+
 impl<S: Borrow<Scl>, K: Borrow<KbmRoot>> Scale for (S, K) {
     fn sorted_pitch_of(&self, degree: i32) -> Pitch {
         let scl = self.0.borrow();
@@ -1172,6 +1352,62 @@ pub fn create_rank2_temperament_scale(
     builder.build_with_description(description)
 }
 
+/// Creates a scale from the given [`MosMode`] with large and small steps in the ratio `large_step`:`small_step`.
+///
+/// The step sizes are scaled s.t. the scale spans exactly one `period`.
+/// [`SclBuildError::InvalidMosSteps`] is returned if a step size is negative or if the steps do not add up to a positive, finite size.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::pergen::Mos;
+/// # use tune::pitch::Ratio;
+/// # use tune::scala;
+/// let ionian = &Mos::new(5, 2, 2, 1).unwrap().modes()[1];
+///
+/// let soft_ionian = scala::create_mos_mode_scale(None, ionian, 3.0, 2.0, Ratio::octave()).unwrap();
+///
+/// assert_eq!(
+///     format!("{}", soft_ionian.export()).lines().collect::<Vec<_>>(),
+///     ["LLsLLLs (5|1) with step ratio 3:2 and period 2.0000",
+///      "7", "189.474", "378.947", "505.263", "694.737", "884.211", "1073.684", "1200.000"]
+/// );
+/// ```
+pub fn create_mos_mode_scale(
+    description: impl Into<Option<String>>,
+    mode: &MosMode,
+    large_step: f64,
+    small_step: f64,
+    period: Ratio,
+) -> Result<Scl, SclBuildError> {
+    let num_large_steps = mode.pattern.chars().filter(|&step| step == 'L').count() as f64;
+    let num_small_steps = mode.pattern.chars().filter(|&step| step == 's').count() as f64;
+    let total_size = num_large_steps * large_step + num_small_steps * small_step;
+
+    if !(large_step >= 0.0 && small_step >= 0.0 && total_size.is_finite() && total_size > 0.0) {
+        return Err(SclBuildError::InvalidMosSteps);
+    }
+
+    let mut builder = Scl::builder();
+    let mut current_size = 0.0;
+    for step in mode.pattern.chars() {
+        current_size += match step {
+            'L' => large_step,
+            _ => small_step,
+        };
+        builder = builder.push_ratio(period.repeated(current_size / total_size));
+    }
+
+    let description = description.into().unwrap_or_else(|| {
+        format!(
+            "{} ({}) with step ratio {large_step}:{small_step} and period {period}",
+            mode.pattern,
+            mode.udp(),
+        )
+    });
+    builder.build_with_description(description)
+}
+
 /// Creates a harmonics or subharmonics scale.
 ///
 /// # Examples
@@ -0,0 +1,292 @@
+use std::iter;
+
+use crate::ji::JiRatio;
+
+use super::{Scl, SclBuildError};
+
+/// Creates a Wilson combination product set (CPS) from all products of `num_chosen` out of the given `factors`.
+///
+/// The product of the first `num_chosen` factors is used as the root of the scale. All pitches are reduced to the octave.
+/// Repeated factors are treated as distinct elements, i.e. `[1, 3, 3]` allows for the product 9.
+///
+/// [`SclBuildError::ScaleTooLarge`] is returned if the number of combinations exceeds the maximum number of scale items.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::ji::JiRatio;
+/// # use tune::scala;
+/// # use tune::scala::SclBuildError;
+/// let factors = [1, 3, 5, 7].map(|factor| JiRatio::from_int(factor).unwrap());
+///
+/// let hexany = scala::create_cps_scale(None, &factors, 2).unwrap();
+///
+/// assert_eq!(
+///     hexany.export().to_string().lines().collect::<Vec<_>>(),
+///     ["2)4 combination product set of 1.3.5.7",
+///      "6", "7/6", "5/4", "35/24", "5/3", "7/4", "2"]
+/// );
+///
+/// let tetrad = scala::create_cps_scale("Otonal tetrad".to_owned(), &factors, 1).unwrap();
+///
+/// assert_eq!(
+///     tetrad.export().to_string().lines().collect::<Vec<_>>(),
+///     ["Otonal tetrad", "4", "5/4", "3/2", "7/4", "2"]
+/// );
+///
+/// let many_factors = vec![JiRatio::from_int(3).unwrap(); 100];
+///
+/// assert_eq!(
+///     scala::create_cps_scale(None, &many_factors, 50).unwrap_err(),
+///     SclBuildError::ScaleTooLarge
+/// );
+/// ```
+pub fn create_cps_scale(
+    description: impl Into<Option<String>>,
+    factors: &[JiRatio],
+    num_chosen: u16,
+) -> Result<Scl, SclBuildError> {
+    check_num_products(num_combinations(factors.len(), usize::from(num_chosen)))?;
+
+    let products = combinations(factors, usize::from(num_chosen));
+    let root = products.first().cloned().unwrap_or_default();
+
+    let description = description.into().unwrap_or_else(|| {
+        format!(
+            "{num_chosen}){} combination product set of {}",
+            factors.len(),
+            format_factors(factors)
+        )
+    });
+    build_octave_reduced_scale(products.iter().map(|product| product / &root), description)
+}
+
+/// Creates a Partch-style tonality diamond from all ratios between the given `identities`.
+///
+/// All pitches are reduced to the octave.
+/// [`SclBuildError::ScaleTooLarge`] is returned if the number of ratios exceeds the maximum number of scale items.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::ji::JiRatio;
+/// # use tune::scala;
+/// let odd_limit = 5;
+/// let identities = Vec::from_iter(
+///     (1..=odd_limit)
+///         .step_by(2)
+///         .map(|identity| JiRatio::from_int(identity).unwrap()),
+/// );
+///
+/// let diamond = scala::create_diamond_scale(None, &identities).unwrap();
+///
+/// assert_eq!(
+///     diamond.export().to_string().lines().collect::<Vec<_>>(),
+///     ["Tonality diamond of 1.3.5",
+///      "7", "6/5", "5/4", "4/3", "3/2", "8/5", "5/3", "2"]
+/// );
+/// ```
+pub fn create_diamond_scale(
+    description: impl Into<Option<String>>,
+    identities: &[JiRatio],
+) -> Result<Scl, SclBuildError> {
+    check_num_products(identities.len().checked_mul(identities.len()))?;
+
+    let description = description
+        .into()
+        .unwrap_or_else(|| format!("Tonality diamond of {}", format_factors(identities)));
+    build_octave_reduced_scale(
+        identities
+            .iter()
+            .flat_map(|otonal| identities.iter().map(move |utonal| otonal / utonal)),
+        description,
+    )
+}
+
+/// Creates an Euler-Fokker genus from all products of any sub-multiset of the given `factors`.
+///
+/// All pitches are reduced to the octave.
+/// [`SclBuildError::ScaleTooLarge`] is returned if the number of sub-multisets exceeds the maximum number of scale items.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::ji::JiRatio;
+/// # use tune::scala;
+/// # use tune::scala::SclBuildError;
+/// let factors = [3, 3, 5].map(|factor| JiRatio::from_int(factor).unwrap());
+///
+/// let genus = scala::create_euler_genus_scale(None, &factors).unwrap();
+///
+/// assert_eq!(
+///     genus.export().to_string().lines().collect::<Vec<_>>(),
+///     ["Euler-Fokker genus of 3.3.5",
+///      "6", "9/8", "5/4", "45/32", "3/2", "15/8", "2"]
+/// );
+///
+/// let distinct_factors = [3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59]
+///     .map(|factor| JiRatio::from_int(factor).unwrap());
+///
+/// assert_eq!(
+///     scala::create_euler_genus_scale(None, &distinct_factors).unwrap_err(),
+///     SclBuildError::ScaleTooLarge
+/// );
+///
+/// let repeated_factors = vec![JiRatio::from_int(3).unwrap(); 1000];
+///
+/// assert_eq!(
+///     scala::create_euler_genus_scale(None, &repeated_factors).unwrap().num_items(),
+///     1001
+/// );
+/// ```
+pub fn create_euler_genus_scale(
+    description: impl Into<Option<String>>,
+    factors: &[JiRatio],
+) -> Result<Scl, SclBuildError> {
+    let description = description
+        .into()
+        .unwrap_or_else(|| format!("Euler-Fokker genus of {}", format_factors(factors)));
+
+    let mut distinct_factors: Vec<(&JiRatio, usize)> = Vec::new();
+    for factor in factors {
+        match distinct_factors
+            .iter_mut()
+            .find(|(distinct, _)| *distinct == factor)
+        {
+            Some((_, multiplicity)) => *multiplicity += 1,
+            None => distinct_factors.push((factor, 1)),
+        }
+    }
+
+    check_num_products(
+        distinct_factors
+            .iter()
+            .try_fold(1usize, |num_products, &(_, multiplicity)| {
+                num_products.checked_mul(multiplicity + 1)
+            }),
+    )?;
+
+    let mut products = vec![JiRatio::unison()];
+    for (factor, multiplicity) in distinct_factors {
+        products = Vec::from_iter(products.iter().flat_map(|product| {
+            iter::successors(Some(product.clone()), |power| Some(power * factor))
+                .take(multiplicity + 1)
+        }));
+    }
+
+    build_octave_reduced_scale(products, description)
+}
+
+/// Creates a cross-set scale from all products of an element of `first_set` and an element of `second_set`.
+///
+/// The product of the first elements of both sets is used as the root of the scale. All pitches are reduced to the octave.
+/// [`SclBuildError::ScaleTooLarge`] is returned if the number of products exceeds the maximum number of scale items.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::ji::JiRatio;
+/// # use tune::scala;
+/// let major_triad = [(1, 1), (5, 4), (3, 2)].map(|(numer, denom)| {
+///     JiRatio::from_fraction(numer, denom).unwrap()
+/// });
+///
+/// let cross_set = scala::create_cross_set_scale(None, &major_triad, &major_triad).unwrap();
+///
+/// assert_eq!(
+///     cross_set.export().to_string().lines().collect::<Vec<_>>(),
+///     ["Cross-set of 1.5/4.3/2 and 1.5/4.3/2",
+///      "6", "9/8", "5/4", "3/2", "25/16", "15/8", "2"]
+/// );
+/// ```
+pub fn create_cross_set_scale(
+    description: impl Into<Option<String>>,
+    first_set: &[JiRatio],
+    second_set: &[JiRatio],
+) -> Result<Scl, SclBuildError> {
+    check_num_products(first_set.len().checked_mul(second_set.len()))?;
+
+    let root = &match (first_set.first(), second_set.first()) {
+        (Some(first), Some(second)) => first * second,
+        _ => JiRatio::unison(),
+    };
+
+    let description = description.into().unwrap_or_else(|| {
+        format!(
+            "Cross-set of {} and {}",
+            format_factors(first_set),
+            format_factors(second_set)
+        )
+    });
+    build_octave_reduced_scale(
+        first_set.iter().flat_map(|first| {
+            second_set
+                .iter()
+                .map(move |second| &(first * second) / root)
+        }),
+        description,
+    )
+}
+
+/// Products of all combinations of `num_chosen` elements in the order of their first occurrence.
+fn combinations(factors: &[JiRatio], num_chosen: usize) -> Vec<JiRatio> {
+    if num_chosen == 0 {
+        return vec![JiRatio::unison()];
+    }
+
+    let mut products = Vec::new();
+    for (index, factor) in factors.iter().enumerate() {
+        for product in combinations(&factors[index + 1..], num_chosen - 1) {
+            products.push(factor * &product);
+        }
+    }
+    products
+}
+
+/// Number of ways to choose `k` out of `n` elements. Returns `None` on overflow.
+fn num_combinations(n: usize, k: usize) -> Option<usize> {
+    if k > n {
+        return Some(0);
+    }
+    (0..k.min(n - k)).try_fold(1usize, |num_combinations, i| {
+        Some(num_combinations.checked_mul(n - i)? / (i + 1))
+    })
+}
+
+/// Rejects inputs that would result in more products than the maximum number of scale items before generating them.
+fn check_num_products(num_products: Option<usize>) -> Result<(), SclBuildError> {
+    match num_products {
+        Some(num_products) if num_products <= usize::from(u16::MAX) => Ok(()),
+        _ => Err(SclBuildError::ScaleTooLarge),
+    }
+}
+
+fn build_octave_reduced_scale(
+    ratios: impl IntoIterator<Item = JiRatio>,
+    description: String,
+) -> Result<Scl, SclBuildError> {
+    let mut ratios = Vec::from_iter(
+        ratios
+            .into_iter()
+            .map(|ratio| ratio.octave_reduced())
+            .filter(|ratio| ratio != &JiRatio::unison()),
+    );
+    ratios.sort_by(|a, b| a.as_ratio().total_cmp(&b.as_ratio()));
+    ratios.dedup();
+
+    ratios
+        .iter()
+        .chain(iter::once(&JiRatio::octave()))
+        .fold(Scl::builder(), |builder, ratio| {
+            builder.push_ji_ratio(ratio)
+        })
+        .build_with_description(description)
+}
+
+fn format_factors(factors: &[JiRatio]) -> String {
+    Vec::from_iter(factors.iter().map(|factor| match factor.as_fraction() {
+        Some((numer, 1)) => numer.to_string(),
+        _ => factor.to_string(),
+    }))
+    .join(".")
+}
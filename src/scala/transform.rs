@@ -0,0 +1,388 @@
+use crate::{math, pitch::Ratio};
+
+use super::{PitchValue, Scl, SclBuildError, SclBuilder};
+
+/// Transformations producing new [`Scl`]s.
+///
+/// Exact [`PitchValue::Fraction`]s are preserved as long as the arithmetic does not require a cents value or overflows.
+impl Scl {
+    /// Rotates the scale s.t. the given degree becomes the new root.
+    ///
+    /// Degrees outside of the first period are equivalent to their counterparts within the first period.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let major = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(5, 3)
+    ///     .push_fraction(15, 8)
+    ///     .push_int(2)
+    ///     .build_with_description("Major")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     major.mode(5).export().to_string().lines().collect::<Vec<_>>(),
+    ///     ["Major, mode 5", "7", "9/8", "6/5", "27/20", "3/2", "8/5", "9/5", "2"]
+    /// );
+    /// assert_eq!(
+    ///     major.mode(-100_000_000).export().to_string().lines().collect::<Vec<_>>(),
+    ///     ["Major, mode -100000000", "7", "9/8", "6/5", "27/20", "3/2", "8/5", "9/5", "2"]
+    /// );
+    /// ```
+    pub fn mode(&self, degree: i32) -> Scl {
+        let num_items = i32::from(self.num_items);
+        let degree_in_period = degree.rem_euclid(num_items);
+        let root = self.exact_pitch_of(degree_in_period);
+        let mut builder = Scl::builder();
+        for index in 1..=num_items {
+            builder =
+                builder.push_pitch_value(div(self.exact_pitch_of(degree_in_period + index), root));
+        }
+        self.build_transformed(builder, format!("mode {degree}"))
+            .unwrap()
+    }
+
+    /// Mirrors the scale s.t. its steps appear in reverse order, e.g. an otonal scale becomes utonal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala;
+    /// # use tune::scala::SegmentType;
+    /// let harmonics = scala::create_harmonics_scale(None, SegmentType::Otonal, 4, 4, None).unwrap();
+    ///
+    /// assert_eq!(
+    ///     harmonics.inverted().export().to_string().lines().collect::<Vec<_>>(),
+    ///     ["JI scale 4:5:6:7:8, inverted", "4", "8/7", "4/3", "8/5", "2"]
+    /// );
+    /// ```
+    pub fn inverted(&self) -> Scl {
+        let period = self.exact_pitch_of(i32::from(self.num_items));
+        let mut builder = Scl::builder();
+        for index in (0..i32::from(self.num_items)).rev() {
+            builder = builder.push_pitch_value(div(period, self.exact_pitch_of(index)));
+        }
+        self.build_transformed(builder, "inverted".to_owned())
+            .unwrap()
+    }
+
+    /// Stretches or compresses all pitches s.t. the period matches the given [`Ratio`].
+    ///
+    /// [`None`] is returned if the period of `self` is 1/1 since it cannot be stretched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder()
+    ///     .push_cents(600.0)
+    ///     .push_int(2)
+    ///     .build_with_description("Tritone")
+    ///     .unwrap();
+    ///
+    /// let stretched = scl.stretched(Ratio::from_cents(1210.0)).unwrap();
+    ///
+    /// assert_eq!(stretched.description(), "Tritone, stretched to +1210.0c");
+    /// assert_approx_eq!(stretched.relative_pitch_of(1).as_cents(), 605.0);
+    /// assert_approx_eq!(stretched.period().as_cents(), 1210.0);
+    ///
+    /// let unison_period = Scl::builder().push_cents(100.0).push_cents(0.0).build().unwrap();
+    /// assert!(unison_period.stretched(Ratio::octave()).is_none());
+    /// ```
+    pub fn stretched(&self, period: Ratio) -> Option<Scl> {
+        let orig_period = self.period.as_octaves();
+        if orig_period == 0.0 {
+            return None;
+        }
+
+        let stretch_factor = period.as_octaves() / orig_period;
+        let mut builder = Scl::builder();
+        for pitch_value in self.pitch_values() {
+            builder = builder.push_cents(pitch_value.as_ratio().as_cents() * stretch_factor);
+        }
+        Some(
+            self.build_transformed(builder, format!("stretched to {period:#}"))
+                .unwrap(),
+        )
+    }
+
+    /// Transposes all degrees except for the root and the period by the given interval.
+    ///
+    /// The transposed degrees are reduced to the first period, sorted and deduplicated, see [`Scl::sorted`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::PitchValue;
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_int(2)
+    ///     .build_with_description("Trichord")
+    ///     .unwrap();
+    ///
+    /// let transposed = |numer, denom| {
+    ///     scl.transposed(PitchValue::Fraction(numer, Some(denom)))
+    ///         .unwrap()
+    ///         .export()
+    ///         .to_string()
+    ///         .lines()
+    ///         .map(str::to_owned)
+    ///         .collect::<Vec<_>>()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     transposed(81, 80),
+    ///     ["Trichord, transposed by 81/80", "3", "729/640", "81/64", "2"]
+    /// );
+    /// assert_eq!(
+    ///     transposed(3, 2),
+    ///     ["Trichord, transposed by 3/2", "3", "27/16", "15/8", "2"]
+    /// );
+    /// ```
+    pub fn transposed(&self, interval: PitchValue) -> Result<Scl, SclBuildError> {
+        let num_items = usize::from(self.num_items);
+        let mut builder = Scl::builder();
+        for (index, &pitch_value) in self.pitch_values().iter().enumerate() {
+            builder = builder.push_pitch_value(if index + 1 < num_items {
+                mul(pitch_value, interval)
+            } else {
+                pitch_value
+            });
+        }
+        let transposed = builder.build_with_description(self.description.clone())?;
+        self.build_transformed(
+            transposed.sorted_builder(),
+            format!("transposed by {interval}"),
+        )
+    }
+
+    /// Creates a scale consisting of the given degrees. The root and the period are always included.
+    ///
+    /// The selected degrees are reduced to the first period, sorted and deduplicated, see [`Scl::sorted`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_int(2)
+    ///     .build_with_description("Pentachord")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     scl.subset(&[0, 4, 2, 7, 3]).unwrap().export().to_string().lines().collect::<Vec<_>>(),
+    ///     ["Pentachord, subset 0,4,2,7,3", "4", "5/4", "4/3", "3/2", "2"]
+    /// );
+    /// ```
+    pub fn subset(&self, degrees: &[i32]) -> Result<Scl, SclBuildError> {
+        let period_degree = i32::from(self.num_items);
+        let mut builder = Scl::builder();
+        for &degree in degrees {
+            if degree != 0 && degree != period_degree {
+                builder = builder.push_pitch_value(self.exact_pitch_of(degree));
+            }
+        }
+        builder = builder.push_pitch_value(self.exact_pitch_of(period_degree));
+
+        let degrees = degrees
+            .iter()
+            .map(|degree| degree.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let subset = builder.build_with_description(self.description.clone())?;
+        self.build_transformed(subset.sorted_builder(), format!("subset {degrees}"))
+    }
+
+    /// Merges the pitches of `other` into the current scale. The result is sorted and deduplicated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let major_triad = Scl::builder()
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(3, 2)
+    ///     .push_int(2)
+    ///     .build_with_description("Major triad")
+    ///     .unwrap();
+    /// let minor_triad = Scl::builder()
+    ///     .push_fraction(6, 5)
+    ///     .push_fraction(3, 2)
+    ///     .push_int(2)
+    ///     .build_with_description("Minor triad")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     major_triad.merged(&minor_triad).unwrap().export().to_string().lines().collect::<Vec<_>>(),
+    ///     ["Major triad, merged with Minor triad, sorted", "4", "6/5", "5/4", "3/2", "2"]
+    /// );
+    /// ```
+    pub fn merged(&self, other: &Scl) -> Result<Scl, SclBuildError> {
+        let mut builder = Scl::builder();
+        for &pitch_value in &self.pitch_values()[..usize::from(self.num_items) - 1] {
+            builder = builder.push_pitch_value(pitch_value);
+        }
+        for &pitch_value in other.pitch_values() {
+            builder = builder.push_pitch_value(pitch_value);
+        }
+        builder = builder.push_pitch_value(self.exact_pitch_of(i32::from(self.num_items)));
+
+        Ok(self
+            .build_transformed(builder, format!("merged with {}", other.description))?
+            .sorted())
+    }
+
+    /// Reduces all pitches to the first period, sorts them in ascending order and removes duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder()
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(9, 4)
+    ///     .push_fraction(1, 1)
+    ///     .push_cents(701.955)
+    ///     .push_int(2)
+    ///     .build_with_description("Unsorted")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     scl.sorted().export().to_string().lines().collect::<Vec<_>>(),
+    ///     ["Unsorted, sorted", "3", "9/8", "3/2", "2"]
+    /// );
+    /// ```
+    pub fn sorted(&self) -> Scl {
+        self.build_transformed(self.sorted_builder(), "sorted".to_owned())
+            .unwrap()
+    }
+
+    fn sorted_builder(&self) -> SclBuilder {
+        let period = self.exact_pitch_of(i32::from(self.num_items));
+        let period_in_octaves = self.period.as_octaves();
+
+        let mut pitch_values = Vec::new();
+        for &pitch_value in &self.pitch_values()[..usize::from(self.num_items) - 1] {
+            let reduced = if self.period.is_negligible() {
+                pitch_value
+            } else {
+                let num_periods = pitch_value
+                    .as_ratio()
+                    .as_octaves()
+                    .div_euclid(period_in_octaves);
+                div(pitch_value, pow(period, num_periods as i32))
+            };
+            let ratio = reduced.as_ratio();
+            if !ratio.is_negligible() && !ratio.deviation_from(self.period).is_negligible() {
+                pitch_values.push(reduced);
+            }
+        }
+        pitch_values.sort_by(|a, b| a.as_ratio().total_cmp(&b.as_ratio()));
+
+        let mut deduplicated: Vec<PitchValue> = Vec::new();
+        for pitch_value in pitch_values {
+            match deduplicated.last_mut() {
+                Some(last)
+                    if last
+                        .as_ratio()
+                        .deviation_from(pitch_value.as_ratio())
+                        .is_negligible() =>
+                {
+                    // Prefer exact fractions over cents values
+                    if let PitchValue::Cents(_) = last {
+                        *last = pitch_value;
+                    }
+                }
+                _ => deduplicated.push(pitch_value),
+            }
+        }
+
+        let mut builder = Scl::builder();
+        for pitch_value in deduplicated {
+            builder = builder.push_pitch_value(pitch_value);
+        }
+        builder.push_pitch_value(period)
+    }
+
+    fn exact_pitch_of(&self, degree: i32) -> PitchValue {
+        let (num_periods, pitch_index) = self.num_periods_and_pitch_index_for_degree(degree);
+        let period = self.pitch_values[usize::from(self.num_items)];
+        mul(self.pitch_values[pitch_index], pow(period, num_periods))
+    }
+
+    fn build_transformed(
+        &self,
+        builder: SclBuilder,
+        transformation: String,
+    ) -> Result<Scl, SclBuildError> {
+        builder.build_with_description(format!("{}, {transformation}", self.description))
+    }
+}
+
+fn as_fraction(pitch_value: PitchValue) -> Option<(u64, u64)> {
+    match pitch_value {
+        PitchValue::Fraction(numer, denom) => Some((numer.into(), denom.unwrap_or(1).into())),
+        PitchValue::Cents(cents_value) if Ratio::from_cents(cents_value).is_negligible() => {
+            Some((1, 1))
+        }
+        PitchValue::Cents(_) => None,
+    }
+}
+
+fn from_fraction(numer: u64, denom: u64) -> Option<PitchValue> {
+    let gcd = math::gcd_u64(numer, denom);
+    let numer = u32::try_from(numer / gcd).ok()?;
+    let denom = u32::try_from(denom / gcd).ok()?;
+    Some(PitchValue::Fraction(numer, (denom != 1).then_some(denom)))
+}
+
+fn mul(a: PitchValue, b: PitchValue) -> PitchValue {
+    as_fraction(a)
+        .zip(as_fraction(b))
+        .and_then(|((numer_a, denom_a), (numer_b, denom_b))| {
+            from_fraction(numer_a.checked_mul(numer_b)?, denom_a.checked_mul(denom_b)?)
+        })
+        .unwrap_or_else(|| PitchValue::Cents(a.as_ratio().as_cents() + b.as_ratio().as_cents()))
+}
+
+fn inv(pitch_value: PitchValue) -> PitchValue {
+    as_fraction(pitch_value)
+        .filter(|&(numer, _)| numer != 0)
+        .and_then(|(numer, denom)| from_fraction(denom, numer))
+        .unwrap_or_else(|| PitchValue::Cents(-pitch_value.as_ratio().as_cents()))
+}
+
+fn div(a: PitchValue, b: PitchValue) -> PitchValue {
+    mul(a, inv(b))
+}
+
+fn pow(pitch_value: PitchValue, exponent: i32) -> PitchValue {
+    let base = if exponent < 0 {
+        inv(pitch_value)
+    } else {
+        pitch_value
+    };
+    let exponent = exponent.unsigned_abs();
+    as_fraction(base)
+        .and_then(|(numer, denom)| {
+            let gcd = math::gcd_u64(numer, denom);
+            from_fraction(
+                (numer / gcd).checked_pow(exponent)?,
+                (denom / gcd).checked_pow(exponent)?,
+            )
+        })
+        .unwrap_or_else(|| PitchValue::Cents(base.as_ratio().as_cents() * f64::from(exponent)))
+}
@@ -0,0 +1,237 @@
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    num::{IntErrorKind, ParseIntError},
+    str::FromStr,
+};
+
+use crate::{ji::JiRatio, math};
+
+use super::Comma;
+
+/// Imports a list of [`Comma`]s from a file in the format of Scala's `intnam.par` interval name database.
+///
+/// Each entry consists of a ratio, e.g. `81/80` or `3`, followed by whitespace and one or more comma-separated names.
+/// Lines starting with `!` are comments. A single line containing only the number of entries may precede the entries.
+/// Entries that cannot be represented as a [`Comma`], e.g. cents values or ratios with prime factors above 251, are skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::temperament;
+/// let intnam_par = [
+///     "! intnam.par",
+///     "!",
+///     "4",
+///     "1/1          unison, perfect prime",
+///     "81/80        syntonic comma, Didymus comma",
+///     "257/256      257th harmonic",
+///     "1/340282366920938463463374607431768211456  2^-128",
+///     "",
+/// ];
+///
+/// let commas = temperament::import_intnam_par(intnam_par.join("\n").as_bytes()).unwrap();
+///
+/// assert_eq!(commas.len(), 2);
+/// assert_eq!(commas[1].description(), "syntonic comma, Didymus comma");
+/// assert_eq!(commas[1].prime_factors(), &[-4, 4, -1]);
+/// ```
+pub fn import_intnam_par(reader: impl Read) -> Result<Vec<Comma>, CommaImportError> {
+    let mut commas = Vec::new();
+    let mut expecting_number_of_entries = true;
+
+    for (line_number, line) in lines(reader, "!") {
+        let line = line?;
+        let (ratio, names) = line.split_once(char::is_whitespace).unwrap_or((&line, ""));
+
+        if expecting_number_of_entries {
+            expecting_number_of_entries = false;
+            if names.trim().is_empty() && ratio.parse::<u32>().is_ok() {
+                continue;
+            }
+        }
+
+        if let Some(comma) = parse_entry(line_number, ratio, names)? {
+            commas.push(comma);
+        }
+    }
+
+    Ok(commas)
+}
+
+/// Imports a list of [`Comma`]s from a CSV file.
+///
+/// Each line consists of a ratio, e.g. `81/80` or `[-4 4 -1>`, followed by a comma and one or more comma-separated names which can optionally be enclosed in double quotes.
+/// Lines starting with `#` are comments. A header line starting with `ratio` is ignored.
+/// Entries that cannot be represented as a [`Comma`], e.g. cents values or ratios with prime factors above 251, are skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::temperament;
+/// let csv = [
+///     "ratio,names",
+///     "# Our own names",
+///     "81/80,\"syntonic comma, Didymus comma\"",
+///     "[-17 2 0 0 4 0>, Alpharabian comma",
+/// ];
+///
+/// let commas = temperament::import_comma_csv(csv.join("\n").as_bytes()).unwrap();
+///
+/// assert_eq!(commas.len(), 2);
+/// assert_eq!(commas[0].description(), "syntonic comma, Didymus comma");
+/// assert_eq!(commas[1].description(), "Alpharabian comma");
+/// assert_eq!(commas[1].as_fraction(), Some((131769, 131072)));
+/// assert_eq!(commas[1].prime_limit(), 11);
+///
+/// assert!(temperament::import_comma_csv("81/80 syntonic comma".as_bytes()).is_err());
+/// ```
+pub fn import_comma_csv(reader: impl Read) -> Result<Vec<Comma>, CommaImportError> {
+    let mut commas = Vec::new();
+    let mut expecting_header = true;
+
+    for (line_number, line) in lines(reader, "#") {
+        let line = line?;
+        let (ratio, names) = line.split_once(',').unwrap_or((&line, ""));
+
+        if expecting_header {
+            expecting_header = false;
+            if ratio.trim().eq_ignore_ascii_case("ratio") {
+                continue;
+            }
+        }
+
+        let names = names.trim();
+        let names = names
+            .strip_prefix('"')
+            .and_then(|names| names.strip_suffix('"'))
+            .unwrap_or(names);
+
+        if let Some(comma) = parse_entry(line_number, ratio, names)? {
+            commas.push(comma);
+        }
+    }
+
+    Ok(commas)
+}
+
+/// Creates a [`Comma`] from a single catalog entry consisting of a `ratio`, e.g. `81/80`, `3` or `[-4 4 -1>`, and its `names`.
+///
+/// This function can be used to support further catalog formats, e.g. YAML.
+/// `Ok(None)` is returned if the entry cannot be represented as a [`Comma`], e.g. cents values or ratios with prime factors above 251.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::temperament;
+/// # use tune::temperament::CommaParseErrorKind;
+/// let comma = temperament::parse_comma_entry("[-17 2 0 0 4 0>", "Alpharabian comma").unwrap().unwrap();
+/// assert_eq!(comma.description(), "Alpharabian comma");
+/// assert_eq!(comma.as_fraction(), Some((131769, 131072)));
+///
+/// // Not representable
+/// assert!(temperament::parse_comma_entry("21.5", "quarter-tone").unwrap().is_none());
+/// assert!(temperament::parse_comma_entry("257/256", "257th harmonic").unwrap().is_none());
+/// assert!(temperament::parse_comma_entry("[1000 -1>", "huge").unwrap().is_none());
+/// assert!(temperament::parse_comma_entry("340282366920938463463374607431768211457", "huge").unwrap().is_none());
+///
+/// // Malformed
+/// assert!(matches!(temperament::parse_comma_entry("81/80", " "), Err(CommaParseErrorKind::Name)));
+/// assert!(matches!(temperament::parse_comma_entry("81:80", "syntonic comma"), Err(CommaParseErrorKind::Ratio)));
+/// ```
+pub fn parse_comma_entry(ratio: &str, names: &str) -> Result<Option<Comma>, CommaParseErrorKind> {
+    let names = names.trim();
+    if names.is_empty() {
+        return Err(CommaParseErrorKind::Name);
+    }
+
+    let ratio = ratio.trim();
+    if let Some(monzo) = ratio.strip_prefix('[') {
+        let Some(mut prime_factors) = monzo
+            .trim_end_matches('>')
+            .split_whitespace()
+            .map(parse_in_range)
+            .collect::<Result<Option<Vec<i8>>, _>>()?
+        else {
+            return Ok(None);
+        };
+        while prime_factors.last() == Some(&0) {
+            prime_factors.pop();
+        }
+        return Ok((prime_factors.len() <= math::U8_PRIMES.len())
+            .then(|| Comma::new(names.to_owned(), prime_factors)));
+    }
+    if ratio.contains('.') {
+        return Ok(None);
+    }
+
+    let (numer, denom) = ratio.split_once('/').unwrap_or((ratio, "1"));
+    let (Some(numer), Some(denom)) = (parse_in_range(numer)?, parse_in_range(denom)?) else {
+        return Ok(None);
+    };
+
+    Ok(JiRatio::from_fraction(numer, denom).and_then(|ji_ratio| ji_ratio.to_comma(names)))
+}
+
+/// Error reported when importing a list of [`Comma`]s fails.
+#[derive(Debug)]
+pub enum CommaImportError {
+    IoError(io::Error),
+    ParseError {
+        line_number: usize,
+        kind: CommaParseErrorKind,
+    },
+}
+
+/// Specifies which kind of item is suspected to be malformed.
+#[derive(Clone, Debug)]
+pub enum CommaParseErrorKind {
+    /// Invalid fraction, integer or monzo.
+    Ratio,
+
+    /// Missing name.
+    Name,
+}
+
+impl From<io::Error> for CommaImportError {
+    fn from(v: io::Error) -> Self {
+        Self::IoError(v)
+    }
+}
+
+/// Returns all non-blank lines with their 1-based line numbers, skipping comments.
+fn lines<'a>(
+    reader: impl Read + 'a,
+    comment_prefix: &'a str,
+) -> impl Iterator<Item = (usize, io::Result<String>)> + 'a {
+    BufReader::new(reader)
+        .lines()
+        .enumerate()
+        .map(|(line_number, line)| (line_number + 1, line.map(|line| line.trim().to_owned())))
+        .filter(move |(_, line)| {
+            line.as_ref().map_or(true, |line| {
+                !line.is_empty() && !line.starts_with(comment_prefix)
+            })
+        })
+}
+
+fn parse_entry(
+    line_number: usize,
+    ratio: &str,
+    names: &str,
+) -> Result<Option<Comma>, CommaImportError> {
+    parse_comma_entry(ratio, names)
+        .map_err(|kind| CommaImportError::ParseError { line_number, kind })
+}
+
+/// Parses an integer, returning [`None`] if it is well-formed but out of range.
+fn parse_in_range<T: FromStr<Err = ParseIntError>>(
+    s: &str,
+) -> Result<Option<T>, CommaParseErrorKind> {
+    match s.trim().parse() {
+        Ok(value) => Ok(Some(value)),
+        Err(err) => match err.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => Ok(None),
+            _ => Err(CommaParseErrorKind::Ratio),
+        },
+    }
+}
@@ -1,16 +1,26 @@
 //! Prime-number based representation of just intervals.
 
-use std::{borrow::Cow, collections::HashMap};
+mod import;
+mod regular;
+mod search;
+mod subgroup;
 
-use crate::{math, pitch::Ratio};
+use std::{borrow::Cow, collections::HashMap, iter, str::FromStr};
 
-/// A [`Val`] is a step size and a sequence of step numbers that, multiplied component-wise, are to be considered equivalent to the prime number sequence [2, 3, 5, 7, ...].
+use crate::{ji::JiRatio, math, pitch::Ratio};
+
+pub use self::{import::*, regular::*, search::*, subgroup::*};
+
+/// A [`Val`] is a step size and a sequence of step numbers that, multiplied component-wise, are to be considered equivalent to the elements of a [`Subgroup`].
+///
+/// By default, the [`Subgroup`] is the prime number sequence [2, 3, 5, 7, ...].
 ///
 /// Treating a number of steps to be equivalent to a specific total ratio is the core idea of tempering.
 /// That said, a val is an irreducible representation of the arithmetic properties of a temperament's generator.
 #[derive(Clone, Debug)]
 pub struct Val {
     step_size: Ratio,
+    basis: Subgroup,
     values: Vec<u16>,
 }
 
@@ -35,10 +45,44 @@ impl Val {
         if values.len() > math::U8_PRIMES.len() {
             None
         } else {
-            Some(Self { step_size, values })
+            Some(Self {
+                step_size,
+                basis: Subgroup::from_num_primes(values.len()),
+                values,
+            })
         }
     }
 
+    /// Creates a [`Val`] from the given values which are to be considered equivalent to the elements of `basis`.
+    ///
+    /// [`None`] is returned if the number of values does not match the number of basis elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Ratio;
+    /// # use tune::temperament::Subgroup;
+    /// # use tune::temperament::Val;
+    /// let subgroup = "2.3.7".parse::<Subgroup>().unwrap();
+    ///
+    /// let val = Val::create_in_subgroup(Ratio::from_semitones(1), subgroup.clone(), [12, 19, 34]).unwrap();
+    /// assert_eq!(val.basis(), &subgroup);
+    ///
+    /// assert!(Val::create_in_subgroup(Ratio::from_semitones(1), subgroup, [12, 19]).is_none());
+    /// ```
+    pub fn create_in_subgroup(
+        step_size: Ratio,
+        basis: Subgroup,
+        values: impl Into<Vec<u16>>,
+    ) -> Option<Self> {
+        let values = values.into();
+        (values.len() == basis.elements().len()).then_some(Self {
+            step_size,
+            basis,
+            values,
+        })
+    }
+
     /// Calculates the patent [`Val`] for the given `step_size`.
     ///
     /// The patent val is the sequence of steps which, multiplied by `step_size`, provide the *best approximation* for the prime number ratios [2, 3, 5, 7, ..., `prime_limit`].
@@ -58,17 +102,42 @@ impl Val {
     /// assert_eq!(val_of_13_edt.values(), &[8, 13, 19, 23]);
     /// ```
     pub fn patent(step_size: Ratio, prime_limit: u8) -> Self {
+        Self::patent_in_subgroup(step_size, Subgroup::primes_up_to(prime_limit))
+    }
+
+    /// Calculates the patent [`Val`] for the given `step_size` within the given `basis`.
+    ///
+    /// The patent val is the sequence of steps which, multiplied by `step_size`, provide the *best approximation* for each of the basis elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Ratio;
+    /// # use tune::temperament::Subgroup;
+    /// # use tune::temperament::Val;
+    /// let subgroup = "2.3.7".parse::<Subgroup>().unwrap();
+    /// let val_of_17_edo = Val::patent_in_subgroup(Ratio::octave().divided_into_equal_steps(17), subgroup);
+    /// assert_eq!(val_of_17_edo.values(), &[17, 27, 48]);
+    ///
+    /// // The patent val for 5/3 differs from 5 minus 3
+    /// let subgroup = "2.5/3.7/3".parse::<Subgroup>().unwrap();
+    /// let val_of_17_edo = Val::patent_in_subgroup(Ratio::octave().divided_into_equal_steps(17), subgroup);
+    /// assert_eq!(val_of_17_edo.values(), &[17, 13, 21]);
+    /// ```
+    pub fn patent_in_subgroup(step_size: Ratio, basis: Subgroup) -> Self {
         Self {
             step_size,
-            values: math::U8_PRIMES
+            values: basis
+                .elements()
                 .iter()
-                .filter(|&&prime_number| prime_number <= prime_limit)
-                .map(|&prime_number| {
-                    Ratio::from_float(prime_number)
+                .map(|element| {
+                    element
+                        .as_ratio()
                         .num_equal_steps_of_size(step_size)
                         .round() as u16
                 })
                 .collect(),
+            basis,
         }
     }
 
@@ -102,7 +171,21 @@ impl Val {
         &self.values
     }
 
-    /// Calculates the alternative step size for the given [`Val`] at the given `index`.
+    /// Returns the [`Subgroup`] whose elements are mapped by the values of this [`Val`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Ratio;
+    /// # use tune::temperament::Val;
+    /// let val_of_12_edo = Val::patent(Ratio::octave().divided_into_equal_steps(12), 7);
+    /// assert_eq!(val_of_12_edo.basis().to_string(), "2.3.5.7");
+    /// ```
+    pub fn basis(&self) -> &Subgroup {
+        &self.basis
+    }
+
+    /// Calculates the alternative step size for the given [`Val`] at the given basis element `index`.
     ///
     /// # Examples
     ///
@@ -135,29 +218,35 @@ impl Val {
     /// ```
     pub fn pick_alternative(&mut self, index: u8) -> bool {
         let index = usize::from(index);
-        if let (Some(value), Some(&prime_number)) =
-            (self.values.get_mut(index), math::U8_PRIMES.get(index))
+        if let (Some(value), Some(element)) =
+            (self.values.get_mut(index), self.basis.elements().get(index))
         {
             let deviation = self
                 .step_size
                 .repeated(*value)
-                .deviation_from(Ratio::from_float(prime_number));
+                .deviation_from(element.as_ratio());
 
-            if deviation.is_negligible() {
-                return false;
+            let alternative = if deviation.is_negligible() {
+                None
             } else if deviation >= Ratio::default() {
-                *value -= 1;
+                value.checked_sub(1)
             } else {
-                *value += 1;
-            }
+                value.checked_add(1)
+            };
 
-            true
+            match alternative {
+                Some(alternative) => {
+                    *value = alternative;
+                    true
+                }
+                None => false,
+            }
         } else {
             false
         }
     }
 
-    /// Returns the prime limit of this [`Val`].
+    /// Returns the prime limit of this [`Val`], i.e. the prime limit of its [`Subgroup`].
     ///
     /// # Examples
     ///
@@ -168,14 +257,10 @@ impl Val {
     /// assert_eq!(custom_val.prime_limit(), 11);
     /// ```
     pub fn prime_limit(&self) -> u8 {
-        if self.values.is_empty() {
-            1
-        } else {
-            math::U8_PRIMES[self.values.len() - 1]
-        }
+        self.basis.prime_limit()
     }
 
-    /// Returns the current [`Val`]s absolute errors i.e. the deviation from the prime number ratios.
+    /// Returns the current [`Val`]s absolute errors i.e. the deviation from the basis elements.
     ///
     /// # Examples
     ///
@@ -197,11 +282,11 @@ impl Val {
     pub fn errors(&self) -> impl Iterator<Item = Ratio> + '_ {
         self.values
             .iter()
-            .zip(math::U8_PRIMES)
-            .map(move |(&value, &prime)| {
+            .zip(self.basis.elements())
+            .map(move |(&value, element)| {
                 self.step_size
                     .repeated(value)
-                    .deviation_from(Ratio::from_float(prime))
+                    .deviation_from(element.as_ratio())
             })
     }
 
@@ -245,16 +330,18 @@ impl Val {
     /// ```
     pub fn te_simple_badness(&self) -> f64 {
         self.errors_in_steps()
-            .zip(math::U8_PRIMES)
-            .map(|(error_in_steps, &prime)| {
-                let error_in_primes = error_in_steps / Ratio::from_float(prime).as_octaves();
-                error_in_primes * error_in_primes
+            .zip(self.basis.elements())
+            .map(|(error_in_steps, element)| {
+                let error_in_elements = error_in_steps / element.as_ratio().as_octaves();
+                error_in_elements * error_in_elements
             })
             .sum::<f64>()
     }
 
     /// Returns the current [`Val`]s subgroup with the absolute errors below the given `threshold`.
     ///
+    /// Only basis elements that are prime numbers are considered. Use [`Val::subgroup_basis`] to obtain the full [`Subgroup`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -267,20 +354,57 @@ impl Val {
     /// ```
     pub fn subgroup(&self, threshold: Ratio) -> impl IntoIterator<Item = u8> + '_ {
         self.errors()
-            .zip(math::U8_PRIMES)
+            .zip(self.basis.elements())
             .filter(move |&(error, _)| error.as_cents().abs() < threshold.as_cents().abs())
-            .map(|(_, &prime)| prime)
+            .filter_map(|(_, element)| match element.as_fraction()? {
+                (numer, 1) => u8::try_from(numer)
+                    .ok()
+                    .filter(|numer| math::U8_PRIMES.contains(numer)),
+                _ => None,
+            })
+    }
+
+    /// Returns the [`Subgroup`] of basis elements with absolute errors below the given `threshold`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Ratio;
+    /// # use tune::temperament::Subgroup;
+    /// # use tune::temperament::Val;
+    /// let val_of_17_edo = Val::patent(Ratio::octave().divided_into_equal_steps(17), 11);
+    /// let subgroup = val_of_17_edo.subgroup_basis(Ratio::from_cents(25.0));
+    /// assert_eq!(subgroup.to_string(), "2.3.7.11");
+    ///
+    /// let subgroup = "2.3.5/3.7/3".parse::<Subgroup>().unwrap();
+    /// let val_of_17_edo = Val::patent_in_subgroup(Ratio::octave().divided_into_equal_steps(17), subgroup);
+    /// let subgroup = val_of_17_edo.subgroup_basis(Ratio::from_cents(25.0));
+    /// assert_eq!(subgroup.to_string(), "2.3.7/3");
+    ///
+    /// // Non-prime basis elements are skipped by the prime-only accessor
+    /// assert_eq!(Vec::from_iter(val_of_17_edo.subgroup(Ratio::from_cents(25.0))), [2, 3]);
+    /// ```
+    pub fn subgroup_basis(&self, threshold: Ratio) -> Subgroup {
+        Subgroup::new(Vec::from_iter(
+            self.errors()
+                .zip(self.basis.elements())
+                .filter(|&(error, _)| error.as_cents().abs() < threshold.as_cents().abs())
+                .map(|(_, element)| element.clone()),
+        ))
+        .unwrap()
     }
 
     /// Applies the temperament's mapping function to the given [`Comma`].
     ///
-    /// Specifically, it calculates the scalar product of the values of `self` and the values of the `comma` if the prime limit of `self` is at least the prime of `comma`.
+    /// Specifically, it calculates the scalar product of the values of `self` and the [`Subgroup::coordinates`] of the `comma`.
+    /// [`None`] is returned if the `comma` is not a member of the [`Val`]'s [`Subgroup`].
     ///
     /// # Examples
     ///
     /// ```
     /// # use tune::temperament::Comma;
     /// # use tune::pitch::Ratio;
+    /// # use tune::temperament::Subgroup;
     /// # use tune::temperament::Val;
     /// let fifth = Comma::new("fifth", &[-1, 1][..]);
     /// assert_eq!(fifth.as_fraction(), Some((3, 2)));
@@ -297,15 +421,21 @@ impl Val {
     /// let seventh = Comma::new("seventh", &[-2, 0, 0, 1][..]);
     /// assert_eq!(seventh.as_fraction(), Some((7, 4)));
     /// assert_eq!(val_of_12edo.map(&seventh), None);
+    ///
+    /// // 7/4 is a member of the 2.7 subgroup
+    /// let subgroup = "2.7".parse::<Subgroup>().unwrap();
+    /// let val_of_12edo = Val::patent_in_subgroup(Ratio::octave().divided_into_equal_steps(12), subgroup);
+    /// assert_eq!(val_of_12edo.map(&seventh), Some(10));
     /// ```
     pub fn map(&self, comma: &Comma) -> Option<i32> {
-        (self.prime_limit() >= comma.prime_limit()).then(|| {
+        let coordinates = self.basis.coordinates(comma)?;
+        Some(
             self.values
                 .iter()
-                .zip(comma.prime_factors())
-                .map(|(&v, &c)| i32::from(v) * i32::from(c))
-                .sum()
-        })
+                .zip(coordinates)
+                .map(|(&v, c)| i32::from(v) * c)
+                .sum(),
+        )
     }
 
     /// Checks whether the current [`Val`] defines a rank-1 temperament which tempers out the given [`Comma`].
@@ -315,6 +445,7 @@ impl Val {
     /// ```
     /// # use tune::temperament::Comma;
     /// # use tune::pitch::Ratio;
+    /// # use tune::temperament::Subgroup;
     /// # use tune::temperament::Val;
     /// let diesis = Comma::new("diesis", &[7, 0, -3][..]);
     /// assert_eq!(diesis.as_fraction(), Some((128, 125)));
@@ -326,10 +457,269 @@ impl Val {
     /// // 31-edo does not temper out the diesis
     /// let val_of_31edo = Val::patent(Ratio::octave().divided_into_equal_steps(31), 5);
     /// assert!(!val_of_31edo.tempers_out(&diesis));
+    ///
+    /// // 17-edo tempers out the septimal comma in the 2.3.7 subgroup
+    /// let septimal_comma = Comma::new("septimal comma", &[6, -2, 0, -1][..]);
+    /// let subgroup = "2.3.7".parse::<Subgroup>().unwrap();
+    /// let val_of_17edo = Val::patent_in_subgroup(Ratio::octave().divided_into_equal_steps(17), subgroup);
+    /// assert!(val_of_17edo.tempers_out(&septimal_comma));
     /// ```
     pub fn tempers_out(&self, comma: &Comma) -> bool {
         self.map(comma) == Some(0)
     }
+
+    /// Creates a [`Val`] from the given wart notation, e.g. `17c` or `b13`, within the given `prime_limit`.
+    ///
+    /// The number denotes the number of equal divisions of the equave. The equave is the octave unless the number is preceded by a letter, e.g. `b13` for 13-EDT.
+    /// Each wart letter following the number selects the next-best approximation of the corresponding prime number where `a` = 2, `b` = 3, `c` = 5, `d` = 7, etc.
+    /// Repeated letters select even worse approximations. The prime limit is extended if a wart refers to a prime number above `prime_limit`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::temperament::Val;
+    /// let val_of_17_edo = Val::from_wart_notation("17", 11).unwrap();
+    /// assert_eq!(val_of_17_edo.values(), &[17, 27, 39, 48, 59]);
+    ///
+    /// let val_of_17c_edo = Val::from_wart_notation("17c", 11).unwrap();
+    /// assert_eq!(val_of_17c_edo.values(), &[17, 27, 40, 48, 59]);
+    ///
+    /// let val_of_17cc_edo = Val::from_wart_notation("17cc", 11).unwrap();
+    /// assert_eq!(val_of_17cc_edo.values(), &[17, 27, 38, 48, 59]);
+    ///
+    /// let val_of_13_edt = Val::from_wart_notation("b13", 7).unwrap();
+    /// assert_eq!(val_of_13_edt.values(), &[8, 13, 19, 23]);
+    ///
+    /// assert_eq!(
+    ///     Val::from_wart_notation("c", 5).unwrap_err(),
+    ///     "Invalid wart notation 'c': Must be a number of steps, optionally preceded by an equave letter \
+    ///      and followed by wart letters (e.g. 17c or b13)"
+    /// );
+    /// ```
+    pub fn from_wart_notation(wart_notation: &str, prime_limit: u8) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "Invalid wart notation '{wart_notation}': Must be a number of steps, \
+                 optionally preceded by an equave letter and followed by wart letters (e.g. 17c or b13)"
+            )
+        };
+
+        let trimmed = wart_notation.trim();
+        let (equave_index, tail) = match trimmed.chars().next() {
+            Some(letter) if letter.is_ascii_lowercase() => {
+                (wart_index(letter).ok_or_else(invalid)?, &trimmed[1..])
+            }
+            _ => (0, trimmed),
+        };
+        let (digits, warts) = tail.split_at(
+            tail.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(tail.len()),
+        );
+
+        let num_steps = digits
+            .parse::<u16>()
+            .ok()
+            .filter(|&num_steps| num_steps > 0)
+            .ok_or_else(invalid)?;
+        let wart_indexes = warts
+            .chars()
+            .map(wart_index)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+
+        let step_size =
+            Ratio::from_float(math::U8_PRIMES[equave_index]).divided_into_equal_steps(num_steps);
+        let prime_limit = wart_indexes
+            .iter()
+            .chain([&equave_index])
+            .map(|&index| math::U8_PRIMES[index])
+            .max()
+            .unwrap_or_default()
+            .max(prime_limit);
+
+        let mut val = Val::patent(step_size, prime_limit);
+        for &index in &wart_indexes {
+            let num_warts = wart_indexes.iter().filter(|&&other| other == index).count();
+            val.values[index] = nth_best_approximation(
+                Ratio::from_float(math::U8_PRIMES[index]).num_equal_steps_of_size(step_size),
+                num_warts,
+            );
+        }
+
+        Ok(val)
+    }
+
+    /// Returns the wart notation of the current [`Val`].
+    ///
+    /// The equave is the first basis element that is mapped to an exact multiple of the step size.
+    /// The equave and wart letters are prime letters (a = 2, b = 3, c = 5, ...) regardless of the subgroup s.t. the notation can be parsed back using [`Val::from_wart_notation`].
+    /// Basis elements that are not prime numbers do not have a letter and are omitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Ratio;
+    /// # use tune::temperament::Subgroup;
+    /// # use tune::temperament::Val;
+    /// let mut val = Val::patent(Ratio::octave().divided_into_equal_steps(17), 11);
+    /// assert_eq!(val.wart_notation(), "17");
+    ///
+    /// val.pick_alternative(2);
+    /// assert_eq!(val.wart_notation(), "17c");
+    ///
+    /// let val_of_13_edt = Val::patent(Ratio::from_float(3.0).divided_into_equal_steps(13), 7);
+    /// assert_eq!(val_of_13_edt.wart_notation(), "b13");
+    ///
+    /// // In the 2.3.7 subgroup, d still refers to 7
+    /// let subgroup = "2.3.7".parse::<Subgroup>().unwrap();
+    /// let mut val = Val::patent_in_subgroup(Ratio::octave().divided_into_equal_steps(17), subgroup);
+    /// val.pick_alternative(2);
+    /// assert_eq!(val.wart_notation(), "17d");
+    ///
+    /// // The notation round-trips for subgroups without the octave
+    /// let subgroup = "3.5.7".parse::<Subgroup>().unwrap();
+    /// let val_of_13_edt = Val::patent_in_subgroup(Ratio::from_float(3.0).divided_into_equal_steps(13), subgroup);
+    /// assert_eq!(val_of_13_edt.wart_notation(), "b13");
+    /// assert_eq!(Val::from_wart_notation("b13", 7).unwrap().values()[1..], *val_of_13_edt.values());
+    /// ```
+    pub fn wart_notation(&self) -> String {
+        let equave_index = self
+            .errors()
+            .position(|error| error.is_negligible())
+            .unwrap_or_default();
+
+        let mut wart_notation = String::new();
+        if let Some(equave) = self.basis.elements().get(equave_index) {
+            if equave != &JiRatio::octave() {
+                wart_notation.push(prime_letter(equave).unwrap_or('?'));
+            }
+        }
+        wart_notation.push_str(
+            &self
+                .values
+                .get(equave_index)
+                .copied()
+                .unwrap_or_default()
+                .to_string(),
+        );
+
+        for (index, (&value, element)) in self.values.iter().zip(self.basis.elements()).enumerate()
+        {
+            if index == equave_index {
+                continue;
+            }
+            let Some(letter) = prime_letter(element) else {
+                continue;
+            };
+            let num_steps = element.as_ratio().num_equal_steps_of_size(self.step_size);
+            let num_warts = (0..)
+                .take_while(|&rank| {
+                    let approximation = nth_best_approximation(num_steps, rank);
+                    approximation != value
+                        && (f64::from(approximation) - num_steps).abs()
+                            <= (f64::from(value) - num_steps).abs()
+                })
+                .count();
+            wart_notation.extend(iter::repeat(letter).take(num_warts));
+        }
+
+        wart_notation
+    }
+}
+
+/// Parses a [`Val`] from ket notation, e.g. `<17 27 39]`, or wart notation, e.g. `17c`.
+///
+/// The step size of vals in ket notation is derived from the first value which is considered to be the number of steps per octave.
+/// Vals in wart notation are created within the 13-limit (see [`Val::from_wart_notation`]).
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::temperament::Val;
+/// let val = "<17 27 39]".parse::<Val>().unwrap();
+/// assert_eq!(val.values(), &[17, 27, 39]);
+/// assert_approx_eq!(val.step_size().as_cents(), 1200.0 / 17.0);
+///
+/// let val = "<12, 19, 28|".parse::<Val>().unwrap();
+/// assert_eq!(val.values(), &[12, 19, 28]);
+///
+/// let val = "17c".parse::<Val>().unwrap();
+/// assert_eq!(val.values(), &[17, 27, 40, 48, 59, 63]);
+///
+/// assert_eq!(
+///     "<17 x]".parse::<Val>().unwrap_err(),
+///     "Invalid val '<17 x]': Must be a list of non-negative numbers (e.g. <12 19 28])"
+/// );
+/// ```
+impl FromStr for Val {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(ket) = s.trim().strip_prefix('<') else {
+            return Val::from_wart_notation(s, 13);
+        };
+
+        let values = ket
+            .trim_end_matches(['|', ']'])
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|value| !value.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<u16>, _>>()
+            .ok()
+            .filter(|values| values.first().is_some_and(|&first| first > 0))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid val '{s}': Must be a list of non-negative numbers (e.g. <12 19 28])"
+                )
+            })?;
+
+        Val::create(Ratio::octave().divided_into_equal_steps(values[0]), values)
+            .ok_or_else(|| format!("Invalid val '{s}': Too many values"))
+    }
+}
+
+fn wart_index(letter: char) -> Option<usize> {
+    letter
+        .is_ascii_lowercase()
+        .then(|| usize::from(letter as u8 - b'a'))
+}
+
+fn prime_letter(element: &JiRatio) -> Option<char> {
+    let (numer, 1) = element.as_fraction()? else {
+        return None;
+    };
+    wart_letter(
+        math::U8_PRIMES
+            .iter()
+            .position(|&prime| u128::from(prime) == numer)?,
+    )
+}
+
+fn wart_letter(index: usize) -> Option<char> {
+    u8::try_from(index)
+        .ok()
+        .filter(|&index| index < 26)
+        .map(|index| char::from(b'a' + index))
+}
+
+/// The `rank`-th best non-negative integer approximation of `num_steps`, starting at rank 0.
+fn nth_best_approximation(num_steps: f64, rank: usize) -> u16 {
+    let mut lower = num_steps.floor();
+    let mut upper = lower + 1.0;
+    let mut approximation = lower;
+
+    for _ in 0..=rank {
+        if lower >= 0.0 && num_steps - lower <= upper - num_steps {
+            approximation = lower;
+            lower -= 1.0;
+        } else {
+            approximation = upper;
+            upper += 1.0;
+        }
+    }
+
+    approximation as u16
 }
 
 /// A named rational interval in its prime factor representation.
@@ -515,6 +905,56 @@ impl CommaCatalog {
         let &(prime_limit, index) = self.comma_ref_by_name.get(&normalize(name))?;
         self.commas_by_limit.get(&prime_limit)?.get(index)
     }
+
+    /// Adds the given [`Comma`]s to the catalog, e.g. to combine the built-in catalog with a user-defined one.
+    ///
+    /// A [`Comma`] with the same prime factors as an existing [`Comma`] replaces the existing one s.t. its description takes precedence.
+    /// The names of the replaced [`Comma`] remain valid aliases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::temperament;
+    /// # use tune::temperament::Comma;
+    /// # use tune::temperament::CommaCatalog;
+    /// let mut catalog = CommaCatalog::new(temperament::huygens_fokker_intervals());
+    ///
+    /// catalog.merge([
+    ///     Comma::new("Didymus comma", &[-4, 4, -1][..]),
+    ///     Comma::new("our septimal comma", &[-1, 2, 1, -2][..]),
+    /// ]);
+    ///
+    /// assert_eq!(catalog.commas_for_limit(5).len(), 127);
+    /// assert_eq!(catalog.commas_for_limit(7).len(), 116);
+    ///
+    /// let syntonic_comma = catalog.comma_for_name("syntonic comma").unwrap();
+    /// assert_eq!(syntonic_comma.description(), "Didymus comma");
+    ///
+    /// let new_comma = catalog.comma_for_name("our septimal comma").unwrap();
+    /// assert_eq!(new_comma.as_fraction(), Some((45, 98)));
+    /// ```
+    pub fn merge(&mut self, commas: impl IntoIterator<Item = Comma>) {
+        for comma in commas {
+            let prime_limit = comma.prime_limit();
+            let commas_for_limit = self.commas_by_limit.entry(prime_limit).or_default();
+
+            let index = commas_for_limit
+                .iter()
+                .position(|existing| existing.prime_factors() == comma.prime_factors())
+                .unwrap_or(commas_for_limit.len());
+
+            for name in comma.description().split(',') {
+                self.comma_ref_by_name
+                    .insert(normalize(name), (prime_limit, index));
+            }
+
+            if index < commas_for_limit.len() {
+                commas_for_limit[index] = comma;
+            } else {
+                commas_for_limit.push(comma);
+            }
+        }
+    }
 }
 
 fn normalize(name: &str) -> String {
@@ -0,0 +1,894 @@
+use std::iter;
+
+use crate::{math, pitch::Ratio};
+
+use super::{Comma, Val};
+
+/// A regular temperament of arbitrary rank, represented by its mapping matrix in defactored Hermite normal form.
+///
+/// Each row of the mapping matrix is a val describing how the prime numbers [2, 3, 5, 7, ...] are mapped to multiples of a generator.
+/// Two [`RegularTemperament`]s are equal iff they temper out the same commas.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegularTemperament {
+    num_primes: usize,
+    mapping: Vec<Vec<i32>>,
+    comma_basis: Vec<Vec<i32>>,
+}
+
+impl RegularTemperament {
+    /// Creates the [`RegularTemperament`] that is supported by all of the given [`Val`]s.
+    ///
+    /// [`None`] is returned if `vals` is empty, if the [`Val`]s have different prime limits, if any [`Val`] is not based on the full prime basis (see [`Subgroup::is_prime_basis`](super::Subgroup::is_prime_basis)) or if the calculation overflows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Ratio;
+    /// # use tune::temperament::RegularTemperament;
+    /// # use tune::temperament::Val;
+    /// let val_of_12_edo = Val::patent(Ratio::octave().divided_into_equal_steps(12), 5);
+    /// let val_of_19_edo = Val::patent(Ratio::octave().divided_into_equal_steps(19), 5);
+    ///
+    /// let meantone = RegularTemperament::from_vals(&[val_of_12_edo, val_of_19_edo]).unwrap();
+    ///
+    /// assert_eq!(meantone.prime_limit(), 5);
+    /// assert_eq!(meantone.rank(), 2);
+    /// assert_eq!(meantone.mapping(), [[1, 0, -4], [0, 1, 4]]);
+    ///
+    /// // Vals with a common factor are defactored
+    /// let val_of_24_edo = Val::patent(Ratio::octave().divided_into_equal_steps(24), 5);
+    /// let val_of_24_edo = RegularTemperament::from_vals(&[val_of_24_edo]).unwrap();
+    ///
+    /// assert_eq!(val_of_24_edo.mapping(), [[12, 19, 28]]);
+    /// ```
+    pub fn from_vals(vals: &[Val]) -> Option<Self> {
+        let num_primes = vals.first()?.values().len();
+        if vals
+            .iter()
+            .any(|val| val.values().len() != num_primes || !val.basis().is_prime_basis())
+        {
+            return None;
+        }
+
+        let vals = Vec::from_iter(
+            vals.iter()
+                .map(|val| Vec::from_iter(val.values().iter().map(|&value| i64::from(value)))),
+        );
+        let commas = integer_kernel(&vals, num_primes)?;
+
+        Self::from_kernel(&commas, num_primes)
+    }
+
+    /// Creates the [`RegularTemperament`] of the given `prime_limit` which tempers out all of the given [`Comma`]s.
+    ///
+    /// [`None`] is returned if `prime_limit` is not a prime number, if any [`Comma`] is a unison or exceeds the given `prime_limit` or if the calculation overflows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::temperament::Comma;
+    /// # use tune::temperament::RegularTemperament;
+    /// let syntonic_comma = Comma::new("syntonic comma", &[-4, 4, -1][..]);
+    /// let starling_comma = Comma::new("starling comma", &[1, 2, -3, 1][..]);
+    ///
+    /// let meantone = RegularTemperament::from_commas(&[syntonic_comma.clone()], 5).unwrap();
+    ///
+    /// assert_eq!(meantone.mapping(), [[1, 0, -4], [0, 1, 4]]);
+    /// assert_eq!(meantone.comma_basis(), [[-4, 4, -1]]);
+    /// assert!(meantone.tempers_out(&syntonic_comma));
+    ///
+    /// let septimal_meantone =
+    ///     RegularTemperament::from_commas(&[syntonic_comma, starling_comma], 7).unwrap();
+    ///
+    /// assert_eq!(septimal_meantone.mapping(), [[1, 0, -4, -13], [0, 1, 4, 10]]);
+    ///
+    /// // The prime limit must not be lower than the prime limit of the commas
+    /// let seventh = Comma::new("harmonic seventh", &[-2, 0, 0, 1][..]);
+    /// assert!(RegularTemperament::from_commas(&[seventh], 5).is_none());
+    ///
+    /// // A unison does not describe a temperament
+    /// let unison = Comma::new("unison", &[][..]);
+    /// assert!(RegularTemperament::from_commas(&[unison], 2).is_none());
+    /// ```
+    pub fn from_commas(commas: &[Comma], prime_limit: u8) -> Option<Self> {
+        let num_primes = math::U8_PRIMES.iter().position(|&p| p == prime_limit)? + 1;
+        if commas.iter().any(|comma| {
+            comma.prime_factors().iter().all(|&power| power == 0)
+                || comma
+                    .prime_factors()
+                    .iter()
+                    .skip(num_primes)
+                    .any(|&power| power != 0)
+        }) {
+            return None;
+        }
+
+        let commas = Vec::from_iter(commas.iter().map(|comma| {
+            Vec::from_iter((0..num_primes).map(|index| {
+                i64::from(
+                    comma
+                        .prime_factors()
+                        .get(index)
+                        .copied()
+                        .unwrap_or_default(),
+                )
+            }))
+        }));
+
+        Self::from_kernel(&commas, num_primes)
+    }
+
+    fn from_kernel(commas: &[Vec<i64>], num_primes: usize) -> Option<Self> {
+        let mapping = hermite_normal_form(integer_kernel(commas, num_primes)?)?;
+        let comma_basis = reduced_comma_basis(&mapping, num_primes)?;
+
+        Some(Self {
+            num_primes,
+            mapping: to_i32_rows(mapping)?,
+            comma_basis: to_i32_rows(comma_basis)?,
+        })
+    }
+
+    /// Returns the prime limit of the [`RegularTemperament`].
+    pub fn prime_limit(&self) -> u8 {
+        match self.num_primes {
+            0 => 1,
+            num_primes => math::U8_PRIMES[num_primes - 1],
+        }
+    }
+
+    /// Returns the rank of the [`RegularTemperament`], i.e. the number of generators.
+    pub fn rank(&self) -> usize {
+        self.mapping.len()
+    }
+
+    /// Returns the mapping matrix in defactored Hermite normal form.
+    ///
+    /// The *k*-th row contains the number of *k*-th generators needed to reach each of the prime numbers [2, 3, 5, 7, ...].
+    pub fn mapping(&self) -> &[Vec<i32>] {
+        &self.mapping
+    }
+
+    /// Returns a basis of the commas tempered out by the [`RegularTemperament`] in their prime factor representation.
+    ///
+    /// The basis is reduced such that the commas are small in terms of their Tenney height. Each comma is oriented such that it represents an ascending interval.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Ratio;
+    /// # use tune::temperament::RegularTemperament;
+    /// # use tune::temperament::Val;
+    /// let val_of_12_edo = Val::patent(Ratio::octave().divided_into_equal_steps(12), 5);
+    /// let val_of_12_edo = RegularTemperament::from_vals(&[val_of_12_edo]).unwrap();
+    ///
+    /// // 81/80 and 128/125
+    /// assert_eq!(val_of_12_edo.comma_basis(), [[-4, 4, -1], [7, 0, -3]]);
+    /// ```
+    pub fn comma_basis(&self) -> &[Vec<i32>] {
+        &self.comma_basis
+    }
+
+    /// Checks whether the [`RegularTemperament`] tempers out the given [`Comma`].
+    ///
+    /// Commas exceeding the prime limit of the temperament are never tempered out.
+    pub fn tempers_out(&self, comma: &Comma) -> bool {
+        comma.prime_limit() <= self.prime_limit()
+            && self.mapping.iter().all(|row| {
+                row.iter()
+                    .zip(comma.prime_factors())
+                    .map(|(&value, &power)| i64::from(value) * i64::from(power))
+                    .sum::<i64>()
+                    == 0
+            })
+    }
+
+    /// Calculates the wedgie, i.e. the exterior product of the rows of the mapping matrix.
+    ///
+    /// The entries are the minors of the mapping matrix for all combinations of primes in lexicographical order.
+    /// The first nonzero entry is always positive.
+    /// [`None`] is returned if an entry does not fit into an [`i64`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::temperament::Comma;
+    /// # use tune::temperament::RegularTemperament;
+    /// let syntonic_comma = Comma::new("syntonic comma", &[-4, 4, -1][..]);
+    /// let starling_comma = Comma::new("starling comma", &[1, 2, -3, 1][..]);
+    ///
+    /// let septimal_meantone =
+    ///     RegularTemperament::from_commas(&[syntonic_comma, starling_comma], 7).unwrap();
+    ///
+    /// assert_eq!(septimal_meantone.wedgie().unwrap(), [1, 4, 10, 4, 13, 12]);
+    /// ```
+    pub fn wedgie(&self) -> Option<Vec<i64>> {
+        let mut wedgie = combinations(self.num_primes, self.rank())
+            .into_iter()
+            .map(|columns| {
+                i64::try_from(integer_determinant(Vec::from_iter(
+                    self.mapping.iter().map(|row| {
+                        Vec::from_iter(columns.iter().map(|&column| i128::from(row[column])))
+                    }),
+                )))
+                .ok()
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if wedgie.iter().find(|&&entry| entry != 0) < Some(&0) {
+            for entry in &mut wedgie {
+                *entry = entry.checked_neg()?;
+            }
+        }
+
+        Some(wedgie)
+    }
+
+    /// Calculates the Tenney-Euclidean complexity, i.e. the RMS-normalized norm of the Tenney-weighted wedgie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::temperament::Comma;
+    /// # use tune::temperament::RegularTemperament;
+    /// let syntonic_comma = Comma::new("syntonic comma", &[-4, 4, -1][..]);
+    /// let meantone = RegularTemperament::from_commas(&[syntonic_comma], 5).unwrap();
+    ///
+    /// assert_approx_eq!(meantone.te_complexity(), 0.710802);
+    /// ```
+    pub fn te_complexity(&self) -> f64 {
+        let num_primes = self.num_primes as f64;
+        let weighted_mapping = self.weighted_mapping();
+        let gram_matrix = Vec::from_iter(weighted_mapping.iter().map(|row_a| {
+            Vec::from_iter(
+                weighted_mapping
+                    .iter()
+                    .map(|row_b| dot_product(row_a, row_b) / num_primes),
+            )
+        }));
+
+        determinant(gram_matrix).max(0.0).sqrt()
+    }
+
+    /// Calculates the Tenney-Euclidean error, i.e. the RMS of the Tenney-weighted errors of the TE tuning in cents per octave.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::temperament::Comma;
+    /// # use tune::temperament::RegularTemperament;
+    /// let syntonic_comma = Comma::new("syntonic comma", &[-4, 4, -1][..]);
+    /// let meantone = RegularTemperament::from_commas(&[syntonic_comma], 5).unwrap();
+    ///
+    /// assert_approx_eq!(meantone.te_error().unwrap(), 1.582221);
+    /// ```
+    pub fn te_error(&self) -> Option<f64> {
+        let tuning = self.optimal_tuning(TuningOptimization::Te)?;
+        let sum_of_squares: f64 = tuning
+            .weighted_errors()
+            .map(|error| error.as_cents() * error.as_cents())
+            .sum();
+
+        Some((sum_of_squares / self.num_primes as f64).sqrt())
+    }
+
+    /// Calculates the Tenney-Euclidean simple badness, i.e. the product of [`RegularTemperament::te_complexity`] and [`RegularTemperament::te_error`] with the latter measured in octaves per octave.
+    pub fn te_simple_badness(&self) -> Option<f64> {
+        Some(self.te_complexity() * self.te_error()? / 1200.0)
+    }
+
+    /// Optimizes the generator sizes of the [`RegularTemperament`] according to the given criterion.
+    ///
+    /// [`None`] is returned if the optimization problem has no solution, e.g. if the octave is tempered out s.t. CTE and POTE cannot keep it pure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala;
+    /// # use tune::temperament::Comma;
+    /// # use tune::temperament::RegularTemperament;
+    /// # use tune::temperament::TuningOptimization;
+    /// let syntonic_comma = Comma::new("syntonic comma", &[-4, 4, -1][..]);
+    /// let meantone = RegularTemperament::from_commas(&[syntonic_comma.clone()], 5).unwrap();
+    ///
+    /// // The second generator of the normal form is the tempered tritave
+    /// let generators = |optimization| {
+    ///     let tuning = meantone.optimal_tuning(optimization).unwrap();
+    ///     Vec::from_iter(tuning.generators.iter().map(|generator| generator.as_cents()))
+    /// };
+    ///
+    /// let top_generators = generators(TuningOptimization::Top);
+    /// assert_approx_eq!(top_generators[0], 1201.698520);
+    /// assert_approx_eq!(top_generators[1], 1899.262909);
+    ///
+    /// let te_generators = generators(TuningOptimization::Te);
+    /// assert_approx_eq!(te_generators[0], 1201.396851);
+    /// assert_approx_eq!(te_generators[1], 1898.445962);
+    ///
+    /// let cte_generators = generators(TuningOptimization::Cte);
+    /// assert_approx_eq!(cte_generators[0], 1200.0);
+    /// assert_approx_eq!(cte_generators[1], 1897.214316);
+    ///
+    /// let pote_generators = generators(TuningOptimization::Pote);
+    /// assert_approx_eq!(pote_generators[0], 1200.0);
+    /// assert_approx_eq!(pote_generators[1], 1896.238659);
+    ///
+    /// // Build a meantone scale with the POTE fifth
+    /// let tuning = meantone.optimal_tuning(TuningOptimization::Pote).unwrap();
+    /// let period = tuning.generators[0];
+    /// let fifth = tuning.generators[1].deviation_from(period);
+    ///
+    /// let scl = scala::create_rank2_temperament_scale(None, fifth, 5, 1, period).unwrap();
+    /// assert_approx_eq!(scl.relative_pitch_of(4).as_cents(), 696.238659);
+    ///
+    /// // The octave cannot be kept pure if it is tempered out
+    /// let octave = Comma::new("octave", &[1, 0, 0][..]);
+    /// let no_octaves = RegularTemperament::from_commas(&[syntonic_comma, octave], 5).unwrap();
+    ///
+    /// assert!(no_octaves.optimal_tuning(TuningOptimization::Te).is_some());
+    /// assert!(no_octaves.optimal_tuning(TuningOptimization::Cte).is_none());
+    /// assert!(no_octaves.optimal_tuning(TuningOptimization::Pote).is_none());
+    /// ```
+    pub fn optimal_tuning(&self, optimization: TuningOptimization) -> Option<TemperamentTuning> {
+        let weighted_mapping = self.weighted_mapping();
+
+        let generators = match optimization {
+            TuningOptimization::Top => self.top_generators(&weighted_mapping)?,
+            TuningOptimization::Te => self.te_generators(&weighted_mapping)?,
+            TuningOptimization::Cte => self.cte_generators(&weighted_mapping)?,
+            TuningOptimization::Pote => {
+                let generators = self.te_generators(&weighted_mapping)?;
+                let octave = self.tuning_map_in_octaves(&generators)[0];
+                if octave.abs() < EPSILON {
+                    return None;
+                }
+                Vec::from_iter(generators.iter().map(|generator| generator / octave))
+            }
+        };
+
+        Some(TemperamentTuning {
+            tuning_map: Vec::from_iter(
+                self.tuning_map_in_octaves(&generators)
+                    .into_iter()
+                    .map(Ratio::from_octaves),
+            ),
+            generators: Vec::from_iter(generators.into_iter().map(Ratio::from_octaves)),
+        })
+    }
+
+    /// Mapping matrix where each column is divided by the size of its prime number in octaves.
+    fn weighted_mapping(&self) -> Vec<Vec<f64>> {
+        Vec::from_iter(self.mapping.iter().map(|row| {
+            Vec::from_iter(
+                row.iter()
+                    .zip(math::U8_PRIMES)
+                    .map(|(&value, &prime)| f64::from(value) / f64::from(prime).log2()),
+            )
+        }))
+    }
+
+    fn tuning_map_in_octaves(&self, generators: &[f64]) -> Vec<f64> {
+        Vec::from_iter((0..self.num_primes).map(|index| {
+            self.mapping
+                .iter()
+                .zip(generators)
+                .map(|(row, generator)| f64::from(row[index]) * generator)
+                .sum()
+        }))
+    }
+
+    /// Least-squares solution of `generators * weighted_mapping = [1, 1, 1, ...]`.
+    fn te_generators(&self, weighted_mapping: &[Vec<f64>]) -> Option<Vec<f64>> {
+        let gram_matrix = Vec::from_iter(weighted_mapping.iter().map(|row_a| {
+            Vec::from_iter(
+                weighted_mapping
+                    .iter()
+                    .map(|row_b| dot_product(row_a, row_b)),
+            )
+        }));
+        let row_sums = Vec::from_iter(weighted_mapping.iter().map(|row| row.iter().sum()));
+
+        solve(gram_matrix, row_sums)
+    }
+
+    /// Least-squares solution subject to the constraint that the octave is pure.
+    fn cte_generators(&self, weighted_mapping: &[Vec<f64>]) -> Option<Vec<f64>> {
+        let octave_mapping = Vec::from_iter(self.mapping.iter().map(|row| f64::from(row[0])));
+
+        // Lagrange multiplier method: The last row and column represent the constraint
+        let matrix = Vec::from_iter(
+            weighted_mapping
+                .iter()
+                .zip(&octave_mapping)
+                .map(|(row_a, &octave)| {
+                    Vec::from_iter(
+                        weighted_mapping
+                            .iter()
+                            .map(|row_b| dot_product(row_a, row_b))
+                            .chain([octave]),
+                    )
+                })
+                .chain([Vec::from_iter(octave_mapping.iter().copied().chain([0.0]))]),
+        );
+        let rhs = Vec::from_iter(
+            weighted_mapping
+                .iter()
+                .map(|row| row.iter().sum())
+                .chain([1.0]),
+        );
+
+        let mut solution = solve(matrix, rhs)?;
+        solution.truncate(self.rank());
+        Some(solution)
+    }
+
+    /// Minimax solution of `generators * weighted_mapping = [1, 1, 1, ...]`.
+    ///
+    /// The primal problem is: Minimize `t` s.t. `-t <= generators * weighted_mapping[.., i] - 1 <= t` for all primes `i`.
+    /// The simplex method is applied to the dual problem whose optimal basis yields the active constraints of the primal problem.
+    fn top_generators(&self, weighted_mapping: &[Vec<f64>]) -> Option<Vec<f64>> {
+        let rank = self.rank();
+        let num_primes = self.num_primes;
+
+        // Without any commas, the just tuning is the exact solution of the least-squares problem
+        if rank == num_primes {
+            return self.te_generators(weighted_mapping);
+        }
+
+        // Dual problem: Maximize sum(u) - sum(v) s.t. weighted_mapping * (u - v) = 0, sum(u) + sum(v) = 1 and u, v >= 0
+        let constraint = |index: usize, sign: f64| {
+            Vec::from_iter(
+                weighted_mapping
+                    .iter()
+                    .map(|row| sign * row[index])
+                    .chain([1.0]),
+            )
+        };
+        let columns = Vec::from_iter(
+            (0..num_primes)
+                .map(|index| (index, 1.0))
+                .chain((0..num_primes).map(|index| (index, -1.0))),
+        );
+        let basis = simplex(
+            &Vec::from_iter(columns.iter().map(|&(index, sign)| constraint(index, sign))),
+            &Vec::from_iter(columns.iter().map(|&(_, sign)| sign)),
+            &Vec::from_iter(iter::repeat(0.0).take(rank).chain([1.0])),
+        )?;
+
+        // Active constraints: generators * weighted_mapping[.., i] - sign * t = 1
+        let matrix = Vec::from_iter(basis.iter().map(|&column| {
+            let (index, sign) = columns[column];
+            Vec::from_iter(weighted_mapping.iter().map(|row| row[index]).chain([-sign]))
+        }));
+
+        let mut solution = solve(matrix, vec![1.0; rank + 1])?;
+        solution.pop();
+        Some(solution)
+    }
+}
+
+/// Criterion used to optimize the generator sizes of a [`RegularTemperament`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TuningOptimization {
+    /// Tenney OPtimal: Minimizes the maximum Tenney-weighted error.
+    Top,
+    /// Tenney-Euclidean: Minimizes the RMS of the Tenney-weighted errors.
+    Te,
+    /// Constrained Tenney-Euclidean: Minimizes the RMS of the Tenney-weighted errors while keeping the octave pure.
+    Cte,
+    /// Pure-Octave Tenney-Euclidean: The TE tuning stretched such that the octave is pure.
+    Pote,
+}
+
+/// Generator sizes and the resulting tuning of the prime numbers of a [`RegularTemperament`].
+#[derive(Clone, Debug)]
+pub struct TemperamentTuning {
+    /// The sizes of the generators in the order of the rows of [`RegularTemperament::mapping`].
+    pub generators: Vec<Ratio>,
+
+    /// The tempered sizes of the prime numbers [2, 3, 5, 7, ...].
+    pub tuning_map: Vec<Ratio>,
+}
+
+impl TemperamentTuning {
+    /// Returns the deviations of the tempered prime numbers from their just sizes.
+    pub fn errors(&self) -> impl Iterator<Item = Ratio> + '_ {
+        self.tuning_map
+            .iter()
+            .zip(math::U8_PRIMES)
+            .map(|(&tempered, &prime)| tempered.deviation_from(Ratio::from_float(prime)))
+    }
+
+    /// Returns the deviations of the tempered prime numbers divided by the size of the prime numbers in octaves.
+    pub fn weighted_errors(&self) -> impl Iterator<Item = Ratio> + '_ {
+        self.errors().zip(math::U8_PRIMES).map(|(error, &prime)| {
+            Ratio::from_octaves(error.as_octaves() / f64::from(prime).log2())
+        })
+    }
+}
+
+const EPSILON: f64 = 1e-9;
+
+/// Lattice basis of all integer vectors `v` with `matrix * v = 0` in Hermite normal form. Returns `None` on overflow.
+fn integer_kernel(matrix: &[Vec<i64>], num_columns: usize) -> Option<Vec<Vec<i64>>> {
+    let num_rows = matrix.len();
+
+    // Hermite normal form of [matrix^T | identity]
+    let augmented = Vec::from_iter((0..num_columns).map(|column| {
+        Vec::from_iter(
+            matrix
+                .iter()
+                .map(|row| row[column])
+                .chain((0..num_columns).map(|index| i64::from(index == column))),
+        )
+    }));
+
+    Some(Vec::from_iter(
+        hermite_normal_form(augmented)?
+            .into_iter()
+            .filter(|row| row[..num_rows].iter().all(|&value| value == 0))
+            .map(|row| row[num_rows..].to_vec()),
+    ))
+}
+
+/// Comma basis with small Tenney heights where each comma represents an ascending interval.
+fn reduced_comma_basis(mapping: &[Vec<i64>], num_primes: usize) -> Option<Vec<Vec<i64>>> {
+    // Reversing the columns places the pivots at the highest primes which leads to simpler commas
+    let reversed_mapping = Vec::from_iter(
+        mapping
+            .iter()
+            .map(|row| Vec::from_iter(row.iter().rev().copied())),
+    );
+    let mut commas = Vec::from_iter(
+        integer_kernel(&reversed_mapping, num_primes)?
+            .into_iter()
+            .map(|mut comma| {
+                comma.reverse();
+                comma
+            }),
+    );
+
+    reduce_tenney_heights(&mut commas);
+
+    for comma in &mut commas {
+        if tenney_size(comma) < 0.0 {
+            for power in comma.iter_mut() {
+                *power = power.checked_neg()?;
+            }
+        }
+    }
+    commas.sort_by(|a, b| tenney_height(a).total_cmp(&tenney_height(b)));
+
+    Some(commas)
+}
+
+fn to_i32_rows(rows: Vec<Vec<i64>>) -> Option<Vec<Vec<i32>>> {
+    rows.into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|value| i32::try_from(value).ok())
+                .collect()
+        })
+        .collect()
+}
+
+/// Greedily adds or subtracts multiples of other commas as long as the Tenney height decreases.
+fn reduce_tenney_heights(commas: &mut [Vec<i64>]) {
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for target in 0..commas.len() {
+            for source in 0..commas.len() {
+                if target == source {
+                    continue;
+                }
+                for combine in [i64::checked_add, i64::checked_sub] {
+                    let Some(candidate) = commas[target]
+                        .iter()
+                        .zip(&commas[source])
+                        .map(|(&target, &source)| combine(target, source))
+                        .collect::<Option<Vec<_>>>()
+                    else {
+                        continue;
+                    };
+                    if tenney_height(&candidate) < tenney_height(&commas[target]) - EPSILON {
+                        commas[target] = candidate;
+                        improved = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn tenney_height(comma: &[i64]) -> f64 {
+    comma
+        .iter()
+        .zip(math::U8_PRIMES)
+        .map(|(&power, &prime)| power.abs() as f64 * f64::from(prime).log2())
+        .sum()
+}
+
+fn tenney_size(comma: &[i64]) -> f64 {
+    comma
+        .iter()
+        .zip(math::U8_PRIMES)
+        .map(|(&power, &prime)| power as f64 * f64::from(prime).log2())
+        .sum()
+}
+
+/// Row-style Hermite normal form with zero rows removed. Returns `None` on overflow.
+fn hermite_normal_form(mut rows: Vec<Vec<i64>>) -> Option<Vec<Vec<i64>>> {
+    let num_columns = rows.first().map_or(0, Vec::len);
+
+    let mut pivot_row = 0;
+    for column in 0..num_columns {
+        if pivot_row == rows.len() {
+            break;
+        }
+
+        for row in pivot_row + 1..rows.len() {
+            while rows[row][column] != 0 {
+                let quotient = rows[pivot_row][column].checked_div(rows[row][column])?;
+                let (upper, lower) = rows.split_at_mut(row);
+                for (pivot_value, value) in upper[pivot_row].iter_mut().zip(&lower[0]) {
+                    *pivot_value = pivot_value.checked_sub(quotient.checked_mul(*value)?)?;
+                }
+                rows.swap(pivot_row, row);
+            }
+        }
+
+        let pivot = rows[pivot_row][column];
+        if pivot == 0 {
+            continue;
+        }
+        if pivot < 0 {
+            for value in &mut rows[pivot_row] {
+                *value = value.checked_neg()?;
+            }
+        }
+
+        let pivot = rows[pivot_row][column];
+        for row in 0..pivot_row {
+            let quotient = rows[row][column].checked_div_euclid(pivot)?;
+            let (upper, lower) = rows.split_at_mut(pivot_row);
+            for (value, pivot_value) in upper[row].iter_mut().zip(&lower[0]) {
+                *value = value.checked_sub(quotient.checked_mul(*pivot_value)?)?;
+            }
+        }
+
+        pivot_row += 1;
+    }
+
+    rows.truncate(pivot_row);
+    Some(rows)
+}
+
+/// All `k`-element subsets of `0..n` in lexicographical order.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    if k > n {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    for first in 0..n {
+        for tail in combinations(n - first - 1, k - 1) {
+            result.push(
+                [first]
+                    .into_iter()
+                    .chain(tail.into_iter().map(|index| index + first + 1))
+                    .collect(),
+            );
+        }
+    }
+    result
+}
+
+/// Exact determinant using the Bareiss algorithm.
+fn integer_determinant(mut matrix: Vec<Vec<i128>>) -> i128 {
+    let size = matrix.len();
+    let mut sign = 1;
+    let mut previous_pivot = 1;
+
+    for k in 0..size {
+        if matrix[k][k] == 0 {
+            let Some(swap_row) = (k + 1..size).find(|&row| matrix[row][k] != 0) else {
+                return 0;
+            };
+            matrix.swap(k, swap_row);
+            sign = -sign;
+        }
+
+        for i in k + 1..size {
+            for j in k + 1..size {
+                matrix[i][j] =
+                    (matrix[i][j] * matrix[k][k] - matrix[i][k] * matrix[k][j]) / previous_pivot;
+            }
+        }
+        previous_pivot = matrix[k][k];
+    }
+
+    match size {
+        0 => 1,
+        _ => sign * matrix[size - 1][size - 1],
+    }
+}
+
+fn determinant(mut matrix: Vec<Vec<f64>>) -> f64 {
+    let size = matrix.len();
+    let mut result = 1.0;
+
+    for col in 0..size {
+        let Some(pivot) =
+            (col..size).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))
+        else {
+            return 0.0;
+        };
+        if matrix[pivot][col].abs() < EPSILON {
+            return 0.0;
+        }
+        if pivot != col {
+            matrix.swap(col, pivot);
+            result = -result;
+        }
+
+        result *= matrix[col][col];
+        let (upper, lower) = matrix.split_at_mut(col + 1);
+        for row in lower {
+            let factor = row[col] / upper[col][col];
+            for (value, pivot_value) in row[col..].iter_mut().zip(&upper[col][col..]) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+
+    result
+}
+
+/// Maximizes `objective * x` s.t. `columns * x = rhs` and `x >= 0` using the two-phase simplex method with Bland's rule.
+///
+/// `rhs` must be non-negative. Returns the indexes of the columns of the optimal basis or [`None`] if the problem is infeasible or unbounded.
+fn simplex(columns: &[Vec<f64>], objective: &[f64], rhs: &[f64]) -> Option<Vec<usize>> {
+    const MAX_NUM_PIVOTS: usize = 100_000;
+
+    let num_rows = rhs.len();
+    let num_columns = columns.len();
+
+    // Tableau with one artificial variable per row and the right-hand side in the last column
+    let mut tableau = Vec::from_iter((0..num_rows).map(|row| {
+        Vec::from_iter(
+            columns
+                .iter()
+                .map(|column| column[row])
+                .chain((0..num_rows).map(|artificial| f64::from(u8::from(artificial == row))))
+                .chain([rhs[row]]),
+        )
+    }));
+    let mut basis = Vec::from_iter(num_columns..num_columns + num_rows);
+
+    let phase_1_objective = Vec::from_iter(
+        iter::repeat(0.0)
+            .take(num_columns)
+            .chain(iter::repeat(-1.0).take(num_rows)),
+    );
+    let phase_2_objective = Vec::from_iter(
+        objective
+            .iter()
+            .copied()
+            .chain(iter::repeat(0.0).take(num_rows)),
+    );
+
+    for (objective, num_candidates) in [
+        (&phase_1_objective, num_columns + num_rows),
+        (&phase_2_objective, num_columns),
+    ] {
+        let mut num_pivots = 0;
+        loop {
+            let reduced_cost = |column: usize| {
+                objective[column]
+                    - basis
+                        .iter()
+                        .zip(&tableau)
+                        .map(|(&basic, row)| objective[basic] * row[column])
+                        .sum::<f64>()
+            };
+            let Some(entering) = (0..num_candidates).find(|&column| reduced_cost(column) > EPSILON)
+            else {
+                break;
+            };
+
+            let leaving = (0..num_rows)
+                .filter(|&row| tableau[row][entering] > EPSILON)
+                .min_by(|&a, &b| {
+                    let ratio =
+                        |row: usize| tableau[row][num_columns + num_rows] / tableau[row][entering];
+                    ratio(a).total_cmp(&ratio(b)).then(basis[a].cmp(&basis[b]))
+                })?;
+
+            pivot(&mut tableau, leaving, entering);
+            basis[leaving] = entering;
+
+            num_pivots += 1;
+            if num_pivots > MAX_NUM_PIVOTS {
+                return None;
+            }
+        }
+
+        // Drive degenerate artificial variables out of the basis
+        for row in 0..num_rows {
+            if basis[row] >= num_columns {
+                if tableau[row][num_columns + num_rows].abs() > EPSILON {
+                    return None;
+                }
+                let column =
+                    (0..num_columns).find(|&column| tableau[row][column].abs() > EPSILON)?;
+                pivot(&mut tableau, row, column);
+                basis[row] = column;
+            }
+        }
+    }
+
+    Some(basis)
+}
+
+fn pivot(tableau: &mut [Vec<f64>], pivot_row: usize, pivot_column: usize) {
+    let pivot_value = tableau[pivot_row][pivot_column];
+    for value in &mut tableau[pivot_row] {
+        *value /= pivot_value;
+    }
+
+    let pivot_values = tableau[pivot_row].clone();
+    for (row, values) in tableau.iter_mut().enumerate() {
+        let factor = values[pivot_column];
+        if row != pivot_row && factor != 0.0 {
+            for (value, pivot_value) in values.iter_mut().zip(&pivot_values) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+}
+
+/// Solves the linear system `matrix * x = rhs` using Gaussian elimination.
+fn solve(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let size = matrix.len();
+
+    for col in 0..size {
+        let pivot =
+            (col..size).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))?;
+        if matrix[pivot][col].abs() < EPSILON {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+
+        let (upper, lower) = matrix.split_at_mut(col + 1);
+        let (upper_rhs, lower_rhs) = rhs.split_at_mut(col + 1);
+        for (row, row_rhs) in lower.iter_mut().zip(lower_rhs) {
+            let factor = row[col] / upper[col][col];
+            for (value, pivot_value) in row[col..].iter_mut().zip(&upper[col][col..]) {
+                *value -= factor * pivot_value;
+            }
+            *row_rhs -= factor * upper_rhs[col];
+        }
+    }
+
+    let mut solution = vec![0.0; size];
+    for row in (0..size).rev() {
+        let sum: f64 = (row + 1..size)
+            .map(|index| matrix[row][index] * solution[index])
+            .sum();
+        solution[row] = (rhs[row] - sum) / matrix[row][row];
+    }
+    Some(solution)
+}
+
+fn dot_product(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
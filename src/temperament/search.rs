@@ -0,0 +1,147 @@
+use crate::{math, pitch::Ratio};
+
+use super::{Subgroup, Val};
+
+/// Criterion used to rank the results of [`search_vals`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ValRanking {
+    /// Rank by [`ValSearchResult::te_error`].
+    TeError,
+    /// Rank by [`ValSearchResult::te_simple_badness`].
+    TeSimpleBadness,
+    /// Rank by [`ValSearchResult::max_relative_error`].
+    MaxRelativeError,
+}
+
+/// A [`Val`] found by [`search_vals`] together with its error measures within the searched subgroup.
+#[derive(Clone, Debug)]
+pub struct ValSearchResult {
+    /// The [`Val`] mapping the elements of the searched [`Subgroup`].
+    pub val: Val,
+
+    /// The RMS of the Tenney-weighted errors in cents per octave when the step size is optimized for the subgroup.
+    pub te_error: f64,
+
+    /// The TE error (in octaves per octave) multiplied by the TE complexity.
+    pub te_simple_badness: f64,
+
+    /// The largest absolute error relative to the step size of the val, e.g. 0.5 for an error of half a step.
+    pub max_relative_error: f64,
+}
+
+/// Searches the patent vals of all equal divisions of `equave` with up to `max_num_steps` steps and returns the results sorted by `ranking`.
+///
+/// The vals are created within the given `subgroup`, e.g. 2.3.7.11 or 2.5/3.7/3.
+/// If `include_alternatives` is set, each patent val is accompanied by all vals with a single wart, e.g. `17c`.
+/// Contorted vals, i.e. vals whose values share a common factor, are skipped since they are equivalent to a smaller val.
+/// No vals are returned if `equave` is not larger than 1/1.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::pitch::Ratio;
+/// # use tune::temperament;
+/// # use tune::temperament::Subgroup;
+/// # use tune::temperament::ValRanking;
+/// let subgroup = "2.3.5".parse::<Subgroup>().unwrap();
+/// let best_5_limit_edos = Vec::from_iter(
+///     temperament::search_vals(Ratio::octave(), 60, &subgroup, false, ValRanking::TeSimpleBadness)
+///         .iter()
+///         .take(5)
+///         .map(|result| result.val.wart_notation()),
+/// );
+/// assert_eq!(best_5_limit_edos, ["53", "34", "19", "12", "46"]);
+///
+/// let subgroup = "3.5.7".parse::<Subgroup>().unwrap();
+/// let best_3_5_7_edts = Vec::from_iter(
+///     temperament::search_vals(Ratio::from_float(3.0), 30, &subgroup, true, ValRanking::TeError)
+///         .iter()
+///         .take(3)
+///         .map(|result| result.val.values().to_vec()),
+/// );
+/// assert_eq!(best_3_5_7_edts, [[13, 19, 23], [30, 44, 53], [17, 25, 30]]);
+///
+/// // Equaves must be larger than 1/1
+/// for equave in [Ratio::default(), Ratio::from_float(0.5)] {
+///     assert!(temperament::search_vals(equave, 12, &subgroup, true, ValRanking::TeError).is_empty());
+/// }
+/// ```
+pub fn search_vals(
+    equave: Ratio,
+    max_num_steps: u16,
+    subgroup: &Subgroup,
+    include_alternatives: bool,
+    ranking: ValRanking,
+) -> Vec<ValSearchResult> {
+    if subgroup.elements().is_empty() || equave.as_octaves() <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut results = Vec::new();
+    for num_steps in 1..=max_num_steps {
+        let patent_val =
+            Val::patent_in_subgroup(equave.divided_into_equal_steps(num_steps), subgroup.clone());
+
+        let mut vals = vec![patent_val.clone()];
+        if include_alternatives {
+            for index in 0..patent_val.values().len() {
+                let mut alternative_val = patent_val.clone();
+                if alternative_val.pick_alternative(index as u8) {
+                    vals.push(alternative_val);
+                }
+            }
+        }
+
+        results.extend(
+            vals.into_iter()
+                .filter(|val| !is_contorted(val))
+                .map(evaluate),
+        );
+    }
+
+    let key = |result: &ValSearchResult| match ranking {
+        ValRanking::TeError => result.te_error,
+        ValRanking::TeSimpleBadness => result.te_simple_badness,
+        ValRanking::MaxRelativeError => result.max_relative_error,
+    };
+    results.sort_by(|a, b| key(a).total_cmp(&key(b)));
+    results
+}
+
+fn is_contorted(val: &Val) -> bool {
+    val.values()
+        .iter()
+        .fold(0, |gcd, &value| math::gcd_u16(gcd, value))
+        > 1
+}
+
+fn evaluate(val: Val) -> ValSearchResult {
+    let num_elements = val.values().len() as f64;
+
+    let weighted_values = Vec::from_iter(
+        val.values()
+            .iter()
+            .zip(val.basis().elements())
+            .map(|(&value, element)| f64::from(value) / element.as_ratio().as_octaves()),
+    );
+    let sum_of_squares: f64 = weighted_values.iter().map(|value| value * value).sum();
+    let optimal_step_size = weighted_values.iter().sum::<f64>() / sum_of_squares;
+
+    let te_error = (weighted_values
+        .iter()
+        .map(|value| (optimal_step_size * value - 1.0).powi(2))
+        .sum::<f64>()
+        / num_elements)
+        .sqrt()
+        * 1200.0;
+    let te_complexity = (sum_of_squares / num_elements).sqrt();
+
+    let max_relative_error = val.errors_in_steps().map(f64::abs).fold(0.0, f64::max);
+
+    ValSearchResult {
+        val,
+        te_error,
+        te_simple_badness: te_complexity * te_error / 1200.0,
+        max_relative_error,
+    }
+}
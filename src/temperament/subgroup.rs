@@ -0,0 +1,308 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{ji::JiRatio, math};
+
+use super::Comma;
+
+/// A basis of just intervals, e.g. 2.3.7 or 2.5/3.7/3, against which [`Val`](super::Val)s and [`Comma`]s can be expressed.
+///
+/// The elements of a [`Subgroup`] are linearly independent [`JiRatio`]s greater than 1/1. They do not need to be prime numbers.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Subgroup {
+    elements: Vec<JiRatio>,
+}
+
+impl Subgroup {
+    /// Creates a [`Subgroup`] from the given basis `elements`.
+    ///
+    /// [`None`] is returned if an element is not greater than 1/1 or if the elements are linearly dependent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ji::JiRatio;
+    /// # use tune::temperament::Subgroup;
+    /// let [two, three, four, five_thirds] =
+    ///     [(2, 1), (3, 1), (4, 1), (5, 3)].map(|(numer, denom)| JiRatio::from_fraction(numer, denom).unwrap());
+    ///
+    /// let subgroup = Subgroup::new([two.clone(), five_thirds.clone()]).unwrap();
+    /// assert_eq!(subgroup.to_string(), "2.5/3");
+    ///
+    /// // 4 = 2 * 2
+    /// assert!(Subgroup::new([two.clone(), four]).is_none());
+    ///
+    /// // 2/3 < 1/1
+    /// assert!(Subgroup::new([two, three.inv()]).is_none());
+    /// ```
+    pub fn new(elements: impl Into<Vec<JiRatio>>) -> Option<Self> {
+        let elements = elements.into();
+
+        let is_valid = elements
+            .iter()
+            .all(|element| element.as_ratio().as_octaves() > 0.0)
+            && row_echelon(&mut basis_matrix(&elements, &[])) == elements.len();
+
+        is_valid.then_some(Self { elements })
+    }
+
+    /// Creates the [`Subgroup`] consisting of all prime numbers up to `prime_limit`, i.e. [2, 3, 5, 7, ..., `prime_limit`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::temperament::Subgroup;
+    /// assert_eq!(Subgroup::primes_up_to(11).to_string(), "2.3.5.7.11");
+    /// assert_eq!(Subgroup::primes_up_to(12).to_string(), "2.3.5.7.11");
+    /// assert_eq!(Subgroup::primes_up_to(1).to_string(), "");
+    /// ```
+    pub fn primes_up_to(prime_limit: u8) -> Self {
+        Self::from_num_primes(
+            math::U8_PRIMES
+                .iter()
+                .take_while(|&&prime| prime <= prime_limit)
+                .count(),
+        )
+    }
+
+    pub(crate) fn from_num_primes(num_primes: usize) -> Self {
+        Self {
+            elements: Vec::from_iter((0..num_primes).map(|index| {
+                let mut prime_factors = vec![0; index + 1];
+                prime_factors[index] = 1;
+                JiRatio::from_prime_factors(prime_factors).unwrap()
+            })),
+        }
+    }
+
+    /// Returns the basis elements of the [`Subgroup`].
+    pub fn elements(&self) -> &[JiRatio] {
+        &self.elements
+    }
+
+    /// Returns the largest prime number occurring in any of the basis elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::temperament::Subgroup;
+    /// assert_eq!("2.3.7".parse::<Subgroup>().unwrap().prime_limit(), 7);
+    /// assert_eq!("2.7/5".parse::<Subgroup>().unwrap().prime_limit(), 7);
+    /// assert_eq!("".parse::<Subgroup>().unwrap().prime_limit(), 1);
+    /// ```
+    pub fn prime_limit(&self) -> u8 {
+        self.elements
+            .iter()
+            .map(JiRatio::prime_limit)
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Checks whether the [`Subgroup`] consists of the consecutive prime numbers [2, 3, 5, 7, ...].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::temperament::Subgroup;
+    /// assert!("2.3.5.7".parse::<Subgroup>().unwrap().is_prime_basis());
+    /// assert!(!"2.3.7".parse::<Subgroup>().unwrap().is_prime_basis());
+    /// assert!(!"3.2.5".parse::<Subgroup>().unwrap().is_prime_basis());
+    /// ```
+    pub fn is_prime_basis(&self) -> bool {
+        *self == Self::from_num_primes(self.elements.len())
+    }
+
+    /// Expresses the given [`Comma`] in terms of the basis elements.
+    ///
+    /// [`None`] is returned if the [`Comma`] is not a product of integer powers of the basis elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::temperament::Comma;
+    /// # use tune::temperament::Subgroup;
+    /// let septimal_comma = Comma::new("septimal comma", &[6, -2, 0, -1][..]);
+    /// assert_eq!(septimal_comma.as_fraction(), Some((64, 63)));
+    ///
+    /// let subgroup = "2.3.7".parse::<Subgroup>().unwrap();
+    /// assert_eq!(subgroup.coordinates(&septimal_comma), Some(vec![6, -2, -1]));
+    ///
+    /// // 64/63 = 2^2 * (3/2)^-4 * 9/7
+    /// let subgroup = "2.3/2.9/7".parse::<Subgroup>().unwrap();
+    /// assert_eq!(subgroup.coordinates(&septimal_comma), Some(vec![2, -4, 1]));
+    ///
+    /// // 5 is not part of the subgroup
+    /// let syntonic_comma = Comma::new("syntonic comma", &[-4, 4, -1][..]);
+    /// assert_eq!(subgroup.coordinates(&syntonic_comma), None);
+    ///
+    /// // 2 is only reachable in steps of 4
+    /// let octave = Comma::new("octave", &[1][..]);
+    /// let subgroup = "4.3".parse::<Subgroup>().unwrap();
+    /// assert_eq!(subgroup.coordinates(&octave), None);
+    /// ```
+    pub fn coordinates(&self, comma: &Comma) -> Option<Vec<i32>> {
+        let target = Vec::from_iter(comma.prime_factors().iter().map(|&power| i32::from(power)));
+
+        let mut matrix = basis_matrix(&self.elements, &target);
+        let num_elements = self.elements.len();
+        let rank = row_echelon(&mut matrix);
+
+        if matrix[rank..]
+            .iter()
+            .any(|row| row[num_elements].abs() > EPSILON)
+        {
+            return None;
+        }
+
+        let mut solution = vec![0.0; num_elements];
+        for (row_index, row) in matrix[..rank].iter().enumerate().rev() {
+            let known: f64 = (row_index + 1..num_elements)
+                .map(|col| row[col] * solution[col])
+                .sum();
+            solution[row_index] = (row[num_elements] - known) / row[row_index];
+        }
+
+        let coordinates = solution
+            .iter()
+            .all(|&value| (value - value.round()).abs() < EPSILON)
+            .then(|| Vec::from_iter(solution.iter().map(|&value| value.round() as i32)))?;
+
+        let num_primes = matrix.len();
+        let is_exact = (0..num_primes).all(|prime_index| {
+            let mapped: i32 = self
+                .elements
+                .iter()
+                .zip(&coordinates)
+                .map(|(element, &coordinate)| {
+                    element
+                        .prime_factors()
+                        .get(prime_index)
+                        .copied()
+                        .unwrap_or_default()
+                        * coordinate
+                })
+                .sum();
+            mapped == target.get(prime_index).copied().unwrap_or_default()
+        });
+
+        is_exact.then_some(coordinates)
+    }
+}
+
+/// Parses a [`Subgroup`] from a dot-separated list of fractions, e.g. `2.3.7` or `2.5/3.7/3`.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::temperament::Subgroup;
+/// let subgroup = "2.5/3.7/3".parse::<Subgroup>().unwrap();
+/// assert_eq!(subgroup.elements().len(), 3);
+/// assert_eq!(subgroup.elements()[1].as_fraction(), Some((5, 3)));
+///
+/// assert_eq!(
+///     "2.3.9".parse::<Subgroup>().unwrap_err(),
+///     "Invalid subgroup '2.3.9': Elements must be greater than 1 and linearly independent"
+/// );
+/// assert_eq!(
+///     "2.x".parse::<Subgroup>().unwrap_err(),
+///     "Invalid subgroup '2.x': Must be a dot-separated list of fractions (e.g. 2.3.7 or 2.5/3.7/3)"
+/// );
+/// ```
+impl FromStr for Subgroup {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Ok(Self::from_num_primes(0));
+        }
+
+        let elements = trimmed
+            .split('.')
+            .map(|element| {
+                let (numer, denom) = element.split_once('/').unwrap_or((element, "1"));
+                JiRatio::from_fraction(numer.trim().parse().ok()?, denom.trim().parse().ok()?)
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                format!(
+                    "Invalid subgroup '{s}': Must be a dot-separated list of fractions (e.g. 2.3.7 or 2.5/3.7/3)"
+                )
+            })?;
+
+        Self::new(elements).ok_or_else(|| {
+            format!(
+                "Invalid subgroup '{s}': Elements must be greater than 1 and linearly independent"
+            )
+        })
+    }
+}
+
+impl Display for Subgroup {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (index, element) in self.elements.iter().enumerate() {
+            if index > 0 {
+                write!(f, ".")?;
+            }
+            match element.as_fraction() {
+                Some((numer, 1)) => write!(f, "{numer}")?,
+                _ => write!(f, "{element}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+const EPSILON: f64 = 1e-9;
+
+/// Creates a matrix with one row per prime number and one column per element, augmented by the `target` column.
+fn basis_matrix(elements: &[JiRatio], target: &[i32]) -> Vec<Vec<f64>> {
+    let num_primes = elements
+        .iter()
+        .map(|element| element.prime_factors().len())
+        .chain([target.len()])
+        .max()
+        .unwrap_or_default();
+
+    Vec::from_iter((0..num_primes).map(|prime_index| {
+        Vec::from_iter(
+            elements
+                .iter()
+                .map(|element| element.prime_factors())
+                .chain([target])
+                .map(|prime_factors| {
+                    f64::from(prime_factors.get(prime_index).copied().unwrap_or_default())
+                }),
+        )
+    }))
+}
+
+/// Transforms the non-augmented part of `matrix` into row echelon form and returns its rank.
+///
+/// The pivot of the *i*-th row is located in the *i*-th column, i.e. the elimination stops at the first linearly dependent column.
+fn row_echelon(matrix: &mut [Vec<f64>]) -> usize {
+    let num_cols = matrix.first().map_or(0, |row| row.len() - 1);
+
+    for col in 0..num_cols {
+        let Some(pivot) = (col..matrix.len())
+            .max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))
+            .filter(|&pivot| matrix[pivot][col].abs() > EPSILON)
+        else {
+            return col;
+        };
+        matrix.swap(col, pivot);
+
+        let (upper, lower) = matrix.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for row in lower {
+            let factor = row[col] / pivot_row[col];
+            for (value, pivot_value) in row.iter_mut().zip(pivot_row) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+
+    num_cols
+}
@@ -0,0 +1,598 @@
+//! Interop with [AnaMark](https://www.mark-henning.de/am_downloads_tuningfiles.php) tuning files (`.tun`).
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    io::{self, BufRead, BufReader, Read},
+};
+
+use crate::{
+    key::PianoKey,
+    pitch::{Pitch, Ratio},
+    scala::{Kbm, KbmRoot, Scl},
+    tuning::{KeyboardMapping, Tuning},
+};
+
+/// The frequency of MIDI note 0 in 440&nbsp;Hz standard tuning which is the reference of the `[Tuning]` section.
+const DEFAULT_BASE_FREQ_HZ: f64 = 8.175_798_915_643_707;
+
+const NUM_NOTES: usize = 128;
+
+/// Tuning format according to <https://www.mark-henning.de/files/am/Tuning_File_V2_Doc.pdf>.
+///
+/// An AnaMark tuning file assigns an absolute [`Pitch`] to each of the 128 MIDI notes.
+/// Optionally, a `[Functional Tuning]` section describes how the tuning was built.
+/// This crate uses that section to save the periodic [`Scl`] and the anchor key of a linear [`Kbm`] such that the scale can be recovered on import.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::key::PianoKey;
+/// # use tune::note::Note;
+/// # use tune::scala::KbmRoot;
+/// # use tune::scala::Scl;
+/// # use tune::tun::Tun;
+/// use tune::tuning::KeyboardMapping;
+///
+/// let scl = Scl::builder()
+///     .push_fraction(5, 4)
+///     .push_fraction(3, 2)
+///     .push_int(2)
+///     .build_with_description("Major triad")
+///     .unwrap();
+/// let kbm = KbmRoot::from(Note::from_midi_number(60)).to_kbm();
+///
+/// let tun = Tun::from_scl_and_kbm(&scl, &kbm);
+/// let exported = tun.export().to_string();
+///
+/// let imported = Tun::import(exported.as_bytes()).unwrap();
+/// assert_eq!(imported.name(), "Major triad");
+///
+/// let pitch_of = |midi_number| imported.maybe_pitch_of(PianoKey::from_midi_number(midi_number));
+/// assert_approx_eq!(pitch_of(60).unwrap().as_hz(), 261.625565);
+/// assert_approx_eq!(pitch_of(61).unwrap().as_hz(), 327.031957);
+/// assert_approx_eq!(pitch_of(63).unwrap().as_hz(), 523.251131);
+/// assert!(pitch_of(128).is_none());
+///
+/// let (imported_scl, imported_kbm) = imported.scl_and_kbm().unwrap();
+/// assert_eq!(imported_scl.num_items(), 3);
+/// assert_approx_eq!(imported_scl.relative_pitch_of(1).as_float(), 1.25);
+/// assert_eq!(imported_kbm.kbm_root().ref_key, PianoKey::from_midi_number(60));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tun {
+    name: String,
+    pitches: Vec<Option<Pitch>>,
+    scl_and_kbm: Option<(Scl, Kbm)>,
+}
+
+impl Tun {
+    /// Creates a [`Tun`] from the pitches of the MIDI keys 0..128 of the given [`KeyboardMapping`].
+    ///
+    /// The AnaMark format does not support unmapped keys.
+    /// Unmapped keys are omitted on export and fall back to their 440&nbsp;Hz standard tuning pitch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::key::PianoKey;
+    /// # use tune::note::Note;
+    /// # use tune::scala::Kbm;
+    /// # use tune::scala;
+    /// # use tune::tun::Tun;
+    /// # use tune::tuning::KeyboardMapping;
+    /// let scl = scala::create_harmonics_scale(None, scala::SegmentType::Otonal, 8, 8, None).unwrap();
+    /// let kbm = Kbm::builder(Note::from_midi_number(62))
+    ///     .range(PianoKey::from_midi_number(62)..PianoKey::from_midi_number(64))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let tun = Tun::from_keyboard_mapping((&scl, &kbm));
+    ///
+    /// assert!(tun.scl_and_kbm().is_none());
+    /// assert_eq!(
+    ///     tun.defined_keys().collect::<Vec<_>>(),
+    ///     [PianoKey::from_midi_number(62), PianoKey::from_midi_number(63)]
+    /// );
+    /// assert_eq!(
+    ///     tun.export().to_string().lines().skip_while(|&line| line != "[Exact Tuning]").take(4).collect::<Vec<_>>(),
+    ///     ["[Exact Tuning]", "BaseFreq= 8.175798915643707", "note 62= 6200.000000", "note 63= 6403.910002"]
+    /// );
+    /// ```
+    pub fn from_keyboard_mapping(keyboard_mapping: impl KeyboardMapping<PianoKey>) -> Self {
+        Self {
+            name: String::new(),
+            pitches: Vec::from_iter((0..NUM_NOTES).map(|index| {
+                keyboard_mapping.maybe_pitch_of(PianoKey::from_midi_number(index as i32))
+            })),
+            scl_and_kbm: None,
+        }
+    }
+
+    /// Creates a [`Tun`] from the given [`Scl`] and [`Kbm`] using the [`Scl`] description as a name.
+    ///
+    /// If all mapped keys of the [`Kbm`] follow a linear mapping the functional tuning section will be exported as well.
+    pub fn from_scl_and_kbm(scl: &Scl, kbm: &Kbm) -> Self {
+        let mut tun = Self::from_keyboard_mapping((scl, kbm));
+        tun.name = scl.description().to_owned();
+
+        let kbm_root = kbm.kbm_root();
+        let origin = kbm_root.ref_key.plus_steps(kbm_root.root_offset);
+        let is_linear = (0..NUM_NOTES as i32)
+            .map(PianoKey::from_midi_number)
+            .all(|key| match kbm.scale_degree_of(key) {
+                Some(degree) => degree == origin.num_keys_before(key),
+                None => true,
+            });
+
+        if is_linear {
+            tun.scl_and_kbm = functional_anchor(origin, scl.num_items()).map(|anchor| {
+                let kbm_root = KbmRoot {
+                    ref_key: anchor,
+                    ref_pitch: (scl, kbm_root).pitch_of(anchor),
+                    root_offset: 0,
+                };
+                (scl.clone(), kbm_root.to_kbm())
+            });
+        }
+
+        tun
+    }
+
+    /// Imports the given file in AnaMark tuning format.
+    ///
+    /// The `[Exact Tuning]` section takes precedence over the `[Tuning]` section.
+    /// Notes missing in both sections fall back to their 440&nbsp;Hz standard tuning pitch.
+    /// Comments start with `;` unless the `;` is part of a double-quoted value. Quoted values can contain escaped quotes (`\"`) and backslashes (`\\`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::key::PianoKey;
+    /// # use tune::tun::Tun;
+    /// # use tune::tuning::KeyboardMapping;
+    /// let tun_file = [
+    ///     "; A comment",
+    ///     "[Scale Begin]",
+    ///     "[Info]",
+    ///     "Name= \"Example tuning\"",
+    ///     "[Tuning]",
+    ///     "note 69=6900 ; 440 Hz",
+    ///     "Note 70=7050",
+    ///     "[Exact Tuning]",
+    ///     "BaseFreq= 10.0",
+    ///     "note 71= 7100.5",
+    ///     "[Scale End]",
+    /// ];
+    ///
+    /// let tun = Tun::import(tun_file.join("\n").as_bytes()).unwrap();
+    ///
+    /// let pitch_of = |midi_number| tun.maybe_pitch_of(PianoKey::from_midi_number(midi_number)).unwrap();
+    /// assert_eq!(tun.name(), "Example tuning");
+    /// assert_approx_eq!(pitch_of(60).as_hz(), 261.625565);
+    /// assert_approx_eq!(pitch_of(69).as_hz(), 440.0);
+    /// assert_approx_eq!(pitch_of(70).as_hz(), 479.823402);
+    /// assert_approx_eq!(pitch_of(71).as_hz(), 604.254050);
+    /// assert!(tun.scl_and_kbm().is_none());
+    /// ```
+    pub fn import(reader: impl Read) -> Result<Self, TunImportError> {
+        let mut importer = TunImporter::new();
+        for (line_number, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let main_item = strip_comment(&line).trim();
+            if !main_item.is_empty() {
+                importer.consume(line_number + 1, main_item)?;
+            }
+        }
+        importer.finalize()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into()
+    }
+
+    /// Iterates over all keys with an explicitly defined [`Pitch`], i.e. all keys that will be exported.
+    pub fn defined_keys(&self) -> impl Iterator<Item = PianoKey> + '_ {
+        self.mapped_pitches()
+            .map(|(index, _)| PianoKey::from_midi_number(index as i32))
+    }
+
+    /// Returns the [`Scl`] and [`Kbm`] described by the functional tuning section, if present.
+    pub fn scl_and_kbm(&self) -> Option<(&Scl, &Kbm)> {
+        self.scl_and_kbm.as_ref().map(|(scl, kbm)| (scl, kbm))
+    }
+
+    /// Exports the current tuning in AnaMark tuning format (version 2.00).
+    ///
+    /// Quotes and backslashes in the name are escaped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::note::Note;
+    /// # use tune::scala::KbmRoot;
+    /// # use tune::scala::Scl;
+    /// # use tune::tun::Tun;
+    /// let scl = Scl::builder().push_cents(100.0).build().unwrap();
+    /// let kbm = KbmRoot::from(Note::from_midi_number(69)).to_kbm();
+    ///
+    /// let mut tun = Tun::from_scl_and_kbm(&scl, &kbm);
+    /// tun.set_name("The \"best\" tuning; with a \\ backslash");
+    ///
+    /// let exported = tun.export().to_string();
+    /// assert!(exported.contains("Name= \"The \\\"best\\\" tuning; with a \\\\ backslash\""));
+    ///
+    /// let imported = Tun::import(exported.as_bytes()).unwrap();
+    /// assert_eq!(imported.name(), "The \"best\" tuning; with a \\ backslash");
+    /// ```
+    pub fn export(&self) -> TunExport<'_> {
+        TunExport(self)
+    }
+
+    fn mapped_pitches(&self) -> impl Iterator<Item = (usize, Pitch)> + '_ {
+        self.pitches
+            .iter()
+            .enumerate()
+            .filter_map(|(index, pitch)| pitch.map(|pitch| (index, pitch)))
+    }
+}
+
+impl KeyboardMapping<PianoKey> for Tun {
+    fn maybe_pitch_of(&self, key: PianoKey) -> Option<Pitch> {
+        key.checked_midi_number().map(|midi_number| {
+            let index = usize::from(midi_number);
+            self.pitches[index].unwrap_or_else(|| default_pitch(index))
+        })
+    }
+}
+
+fn default_pitch(index: usize) -> Pitch {
+    Pitch::from_hz(DEFAULT_BASE_FREQ_HZ) * Ratio::from_semitones(index as f64)
+}
+
+struct TunImporter {
+    section: Section,
+    name: String,
+    base_freq: Option<Pitch>,
+    cents: Vec<Option<f64>>,
+    exact_cents: Vec<Option<f64>>,
+    functional: Option<BTreeMap<usize, FunctionalEntry>>,
+}
+
+#[derive(Eq, PartialEq)]
+enum Section {
+    Other,
+    Info,
+    Tuning,
+    ExactTuning,
+    FunctionalTuning,
+}
+
+struct FunctionalEntry {
+    num_notes_below: usize,
+    cents: f64,
+    is_loop: bool,
+}
+
+impl TunImporter {
+    fn new() -> Self {
+        Self {
+            section: Section::Other,
+            name: String::new(),
+            base_freq: None,
+            cents: vec![None; NUM_NOTES],
+            exact_cents: vec![None; NUM_NOTES],
+            functional: None,
+        }
+    }
+
+    fn consume(&mut self, line_number: usize, line: &str) -> Result<(), TunImportError> {
+        if let Some(section) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            self.section = match section.trim().to_ascii_lowercase().as_str() {
+                "info" => Section::Info,
+                "tuning" => Section::Tuning,
+                "exact tuning" => Section::ExactTuning,
+                "functional tuning" => {
+                    self.functional.get_or_insert_with(BTreeMap::new);
+                    Section::FunctionalTuning
+                }
+                _ => Section::Other,
+            };
+            return Ok(());
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .map(|(key, value)| (key.trim().to_ascii_lowercase(), value.trim()))
+            .ok_or(TunImportError::ParseError {
+                line_number,
+                kind: TunParseErrorKind::KeyValuePair,
+            })?;
+
+        let parse_error = |kind| TunImportError::ParseError { line_number, kind };
+
+        match self.section {
+            Section::Info if key == "name" => self.name = unquote(value),
+            Section::ExactTuning if key == "basefreq" => {
+                let hz = value
+                    .parse()
+                    .map_err(|_| parse_error(TunParseErrorKind::FrequencyValue))?;
+                self.base_freq = Some(Pitch::from_hz(hz));
+            }
+            Section::Tuning | Section::ExactTuning | Section::FunctionalTuning
+                if key.starts_with("note") =>
+            {
+                let note_number = key["note".len()..]
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&note_number| note_number < NUM_NOTES)
+                    .ok_or_else(|| parse_error(TunParseErrorKind::NoteNumber))?;
+
+                match self.section {
+                    Section::Tuning => {
+                        let cents = value
+                            .parse()
+                            .map_err(|_| parse_error(TunParseErrorKind::CentsValue))?;
+                        self.cents[note_number] = Some(cents);
+                    }
+                    Section::ExactTuning => {
+                        let cents = value
+                            .parse()
+                            .map_err(|_| parse_error(TunParseErrorKind::CentsValue))?;
+                        self.exact_cents[note_number] = Some(cents);
+                    }
+                    _ => {
+                        let entry = parse_functional_entry(value.trim_matches('"'));
+                        match (entry, &mut self.functional) {
+                            (Some(entry), Some(functional)) => {
+                                functional.insert(note_number, entry);
+                            }
+                            // Unsupported instructions: The functional tuning cannot be recovered.
+                            _ => self.functional = None,
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Tun, TunImportError> {
+        if self
+            .cents
+            .iter()
+            .chain(&self.exact_cents)
+            .all(Option::is_none)
+        {
+            return Err(TunImportError::StructuralError(
+                TunStructuralError::ExpectingNotes,
+            ));
+        }
+
+        let base_freq = self
+            .base_freq
+            .unwrap_or(Pitch::from_hz(DEFAULT_BASE_FREQ_HZ));
+
+        let pitches = Vec::from_iter((0..NUM_NOTES).map(|index| {
+            match (self.exact_cents[index], self.cents[index]) {
+                (Some(exact_cents), _) => Some(base_freq * Ratio::from_cents(exact_cents)),
+                (None, Some(cents)) => {
+                    Some(Pitch::from_hz(DEFAULT_BASE_FREQ_HZ) * Ratio::from_cents(cents))
+                }
+                (None, None) => None,
+            }
+        }));
+
+        let scl_and_kbm = self
+            .functional
+            .and_then(|functional| recover_scl_and_kbm(&self.name, &pitches, functional));
+
+        Ok(Tun {
+            name: self.name,
+            pitches,
+            scl_and_kbm,
+        })
+    }
+}
+
+fn parse_functional_entry(instruction: &str) -> Option<FunctionalEntry> {
+    let mut tokens = instruction.split_ascii_whitespace();
+    let num_notes_below = tokens.next()?.strip_prefix("#>-")?.parse().ok()?;
+    if tokens.next()? != "%" {
+        return None;
+    }
+    let cents = tokens.next()?.parse().ok()?;
+    let is_loop = match tokens.next() {
+        None => false,
+        Some(token) => token.starts_with('~'),
+    };
+    Some(FunctionalEntry {
+        num_notes_below,
+        cents,
+        is_loop,
+    })
+}
+
+fn recover_scl_and_kbm(
+    name: &str,
+    pitches: &[Option<Pitch>],
+    functional: BTreeMap<usize, FunctionalEntry>,
+) -> Option<(Scl, Kbm)> {
+    let (&first_note, first_entry) = functional.iter().next()?;
+    let anchor = first_note.checked_sub(first_entry.num_notes_below)?;
+
+    let mut builder = Scl::builder();
+    for (degree, (&note_number, entry)) in (1..).zip(&functional) {
+        let is_last = degree == functional.len();
+        if note_number != anchor + degree
+            || entry.num_notes_below != degree
+            || entry.is_loop != is_last
+        {
+            return None;
+        }
+        builder = builder.push_cents(entry.cents);
+    }
+
+    let scl = builder.build_with_description(name).ok()?;
+    let kbm = KbmRoot {
+        ref_key: PianoKey::from_midi_number(anchor as i32),
+        ref_pitch: pitches[anchor]?,
+        root_offset: 0,
+    }
+    .to_kbm();
+
+    Some((scl, kbm))
+}
+
+/// Error reported when importing a [`Tun`] fails.
+#[derive(Debug)]
+pub enum TunImportError {
+    IoError(io::Error),
+    ParseError {
+        line_number: usize,
+        kind: TunParseErrorKind,
+    },
+    StructuralError(TunStructuralError),
+}
+
+/// Specifies which kind of item is suspected to be malformed.
+#[derive(Clone, Debug)]
+pub enum TunParseErrorKind {
+    /// Line is neither a section header nor a `key = value` pair.
+    KeyValuePair,
+
+    /// Invalid note number or out of range.
+    NoteNumber,
+
+    /// Invalid cents value.
+    CentsValue,
+
+    /// Invalid frequency value.
+    FrequencyValue,
+}
+
+/// Indicates that the structure of the imported [`Tun`] file is incomplete.
+#[derive(Clone, Debug)]
+pub enum TunStructuralError {
+    ExpectingNotes,
+}
+
+impl From<io::Error> for TunImportError {
+    fn from(v: io::Error) -> Self {
+        Self::IoError(v)
+    }
+}
+
+/// Format / [`Display`] wrapper created by [`Tun::export`].
+pub struct TunExport<'a>(&'a Tun);
+
+impl<'a> Display for TunExport<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let base_freq = Pitch::from_hz(DEFAULT_BASE_FREQ_HZ);
+
+        writeln!(f, "[Scale Begin]")?;
+        writeln!(f, "Format= \"AnaMark-TUN\"")?;
+        writeln!(f, "FormatVersion= 200")?;
+        writeln!(
+            f,
+            "FormatSpecs= \"http://www.mark-henning.de/eternity/tuningspecs.html\""
+        )?;
+        writeln!(f)?;
+        writeln!(f, "[Info]")?;
+        writeln!(f, "Name= \"{}\"", escape(&self.0.name))?;
+        writeln!(f)?;
+        writeln!(f, "[Tuning]")?;
+        for (index, pitch) in self.0.mapped_pitches() {
+            let cents = Ratio::between_pitches(base_freq, pitch).as_cents();
+            writeln!(f, "note {index}={}", cents.round())?;
+        }
+        writeln!(f)?;
+        writeln!(f, "[Exact Tuning]")?;
+        writeln!(f, "BaseFreq= {DEFAULT_BASE_FREQ_HZ}")?;
+        for (index, pitch) in self.0.mapped_pitches() {
+            let cents = Ratio::between_pitches(base_freq, pitch).as_cents();
+            writeln!(f, "note {index}= {cents:.6}")?;
+        }
+        if let Some((scl, kbm)) = &self.0.scl_and_kbm {
+            let anchor = kbm.kbm_root().ref_key.midi_number();
+            writeln!(f)?;
+            writeln!(f, "[Functional Tuning]")?;
+            for degree in 1..=usize::from(scl.num_items()) {
+                let cents = scl.relative_pitch_of(degree as i32).as_cents();
+                let loop_marker = if degree == usize::from(scl.num_items()) {
+                    " ~999"
+                } else {
+                    ""
+                };
+                writeln!(
+                    f,
+                    "note {}=\"#>-{degree} % {cents:.6}{loop_marker}\"",
+                    anchor + degree as i32
+                )?;
+            }
+        }
+        writeln!(f)?;
+        writeln!(f, "[Scale End]")
+    }
+}
+
+/// Removes a `;` comment unless the `;` is part of a double-quoted value.
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote(value: &str) -> String {
+    let Some(quoted) = value.strip_prefix('"') else {
+        return value.to_owned();
+    };
+
+    let mut unquoted = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.extend(chars.next()),
+            '"' => break,
+            c => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Finds a key congruent to the scale's origin s.t. a full period of the scale fits into the MIDI range.
+fn functional_anchor(origin: PianoKey, num_items: u16) -> Option<PianoKey> {
+    let num_items = i32::from(num_items);
+    let max_anchor = NUM_NOTES as i32 - 1 - num_items;
+    let mut anchor = origin.midi_number();
+    while anchor > max_anchor {
+        anchor -= num_items;
+    }
+    while anchor < 0 {
+        anchor += num_items;
+    }
+    (anchor <= max_anchor).then(|| PianoKey::from_midi_number(anchor))
+}
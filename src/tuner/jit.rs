@@ -6,7 +6,7 @@ use std::{
 use crate::{
     note::Note,
     pitch::{Pitch, Pitched, Ratio},
-    tuning::Approximation,
+    tuning::{Approximation, KeyboardMapping},
 };
 
 use super::{Group, GroupBy, IsErr, TunableSynth};
@@ -81,6 +81,25 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> JitTuner<K, S> {
         }
     }
 
+    /// Updates the notes of all active keys with the pitches of the given `tuning`.
+    ///
+    /// This is useful for tunings changing over time, e.g. a [`Morph`](crate::tuning::Morph) whose blend factor was modified.
+    /// Keys that are not mapped by `tuning` keep their current pitch.
+    pub fn retune(&mut self, tuning: impl KeyboardMapping<K>) -> S::Result {
+        let active_keys: Vec<_> = self.model.active_keys().collect();
+
+        for key in active_keys {
+            if let Some(pitch) = tuning.maybe_pitch_of(key) {
+                let result = self.note_pitch(key, pitch);
+                if result.is_err() {
+                    return result;
+                }
+            }
+        }
+
+        S::Result::ok()
+    }
+
     /// Sets a polyphonic attribute for the note with the given `key`.
     pub fn note_attr(&mut self, key: K, attr: S::NoteAttr) -> S::Result {
         match self.model.access_key(key) {
@@ -321,8 +340,88 @@ impl<K: Copy + Eq + Hash, C: Copy, N: Copy> JitPool<K, C, N> {
 
 #[cfg(test)]
 mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::{
+        key::PianoKey,
+        scala::{KbmRoot, Scl},
+        tuning::Morph,
+    };
+
     use super::*;
 
+    #[test]
+    fn retune_held_notes() {
+        let kbm = KbmRoot::from(Note::from_midi_number(62)).to_kbm();
+        let edo_12 = (
+            Scl::builder().push_cents(100.0).build().unwrap(),
+            kbm.clone(),
+        );
+        let edo_22 = (
+            Scl::builder()
+                .push_ratio(Ratio::octave().divided_into_equal_steps(22))
+                .build()
+                .unwrap(),
+            kbm,
+        );
+        let mut morph = Morph::per_key(edo_12, edo_22);
+
+        let mut tuner = JitTuner::start(RecordingSynth::default(), PoolingMode::Stop);
+        for midi_number in [62, 64, 66] {
+            let key = PianoKey::from_midi_number(midi_number);
+            tuner.note_on(key, morph.maybe_pitch_of(key).unwrap(), ());
+        }
+        tuner.note_off(PianoKey::from_midi_number(64), ());
+        tuner.synth.detunings.clear();
+
+        morph.set_blend(0.5);
+        tuner.retune(&morph);
+
+        let mut detunings = tuner.synth.detunings.clone();
+        detunings.sort_by_key(|&(_, note, _)| note.midi_number());
+
+        assert_eq!(detunings.len(), 2);
+        assert_eq!(detunings[0].1, Note::from_midi_number(62));
+        assert_approx_eq!(detunings[0].2, 0.0);
+        assert_eq!(detunings[1].1, Note::from_midi_number(66));
+        assert_approx_eq!(detunings[1].2, (400.0 + 4.0 * 1200.0 / 22.0) / 2.0 - 400.0);
+    }
+
+    #[derive(Default)]
+    struct RecordingSynth {
+        detunings: Vec<(usize, Note, f64)>,
+    }
+
+    impl TunableSynth for RecordingSynth {
+        type Result = ();
+        type NoteAttr = ();
+        type GlobalAttr = ();
+
+        fn num_channels(&self) -> usize {
+            4
+        }
+
+        fn group_by(&self) -> GroupBy {
+            GroupBy::Note
+        }
+
+        fn notes_detune(&mut self, channel: usize, detuned_notes: &[(Note, Ratio)]) {
+            self.detunings.extend(
+                detuned_notes
+                    .iter()
+                    .map(|&(note, detuning)| (channel, note, detuning.as_cents())),
+            );
+        }
+
+        fn note_on(&mut self, _channel: usize, _started_note: Note, _attr: ()) {}
+
+        fn note_off(&mut self, _channel: usize, _stopped_note: Note, _attr: ()) {}
+
+        fn note_attr(&mut self, _channel: usize, _affected_note: Note, _attr: ()) {}
+
+        fn global_attr(&mut self, _attr: ()) {}
+    }
+
     #[test]
     fn pooling_mode_block() {
         let mut pool = JitPool::new(PoolingMode::Block, 0..3);
@@ -3,9 +3,9 @@ use std::collections::HashMap;
 use crate::{
     midi::{ChannelMessage, ChannelMessageType},
     mts::{
-        self, ScaleOctaveTuning, ScaleOctaveTuningFormat, ScaleOctaveTuningMessage,
-        ScaleOctaveTuningOptions, SingleNoteTuningChange, SingleNoteTuningChangeMessage,
-        SingleNoteTuningChangeOptions,
+        self, BulkTuningDumpMessage, BulkTuningDumpOptions, ScaleOctaveTuning,
+        ScaleOctaveTuningFormat, ScaleOctaveTuningMessage, ScaleOctaveTuningOptions,
+        SingleNoteTuningChange, SingleNoteTuningChangeMessage, SingleNoteTuningChangeOptions,
     },
     note::Note,
     pitch::{Pitched, Ratio},
@@ -52,6 +52,21 @@ impl<H> TunableMidi<H> {
         }
     }
 
+    pub fn bulk_tuning_dump(
+        midi_target: MidiTarget<H>,
+        device_id: u8,
+        first_tuning_program: u8,
+    ) -> Self {
+        Self {
+            midi_target,
+            midi_tuning_creator: MidiTuningCreator::BulkTuningDump {
+                device_id,
+                first_tuning_program,
+                detunings: HashMap::new(),
+            },
+        }
+    }
+
     pub fn channel_fine_tuning(midi_target: MidiTarget<H>) -> Self {
         Self {
             midi_target,
@@ -164,6 +179,11 @@ enum MidiTuningCreator {
         format: ScaleOctaveTuningFormat,
         octave_tunings: HashMap<usize, ScaleOctaveTuning>,
     },
+    BulkTuningDump {
+        device_id: u8,
+        first_tuning_program: u8,
+        detunings: HashMap<usize, [Ratio; 128]>,
+    },
     ChannelFineTuning,
     PitchBend,
 }
@@ -237,6 +257,52 @@ impl MidiTuningCreator {
                     target.handler.handle(MidiTunerMessage::new(tuning_message));
                 }
             }
+            MidiTuningCreator::BulkTuningDump {
+                device_id,
+                first_tuning_program,
+                detunings,
+            } => {
+                let detunings = detunings
+                    .entry(tuner_channel)
+                    .or_insert([Ratio::default(); 128]);
+
+                for &(note, detuning) in detuned_notes {
+                    if let Some(midi_number) = note.checked_midi_number() {
+                        detunings[usize::from(midi_number)] = detuning;
+                    }
+                }
+
+                let tuning_program = target.tuning_program(tuner_channel, *first_tuning_program);
+
+                let options = BulkTuningDumpOptions {
+                    device_id: *device_id,
+                    tuning_program,
+                    ..Default::default()
+                };
+
+                for channel_message in
+                    mts::tuning_program_change(midi_channel, tuning_program).unwrap()
+                {
+                    target
+                        .handler
+                        .handle(MidiTunerMessage::new(channel_message));
+                }
+
+                if let Ok(tuning_message) = BulkTuningDumpMessage::from_tuning_changes(
+                    &options,
+                    (0..128)
+                        .zip(detunings.iter())
+                        .map(|(midi_number, &detuning)| {
+                            let note = Note::from_midi_number(midi_number);
+                            SingleNoteTuningChange {
+                                key: note.as_piano_key(),
+                                target_pitch: note.pitch() * detuning,
+                            }
+                        }),
+                ) {
+                    target.handler.handle(MidiTunerMessage::new(tuning_message));
+                }
+            }
             MidiTuningCreator::ChannelFineTuning => {
                 for &(_, detuning) in detuned_notes {
                     for channel_message in mts::channel_fine_tuning(midi_channel, detuning).unwrap()
@@ -262,7 +328,8 @@ impl MidiTuningCreator {
 
     fn group_by(&self) -> GroupBy {
         match self {
-            MidiTuningCreator::SingleNoteTuningChange { .. } => GroupBy::Note,
+            MidiTuningCreator::SingleNoteTuningChange { .. }
+            | MidiTuningCreator::BulkTuningDump { .. } => GroupBy::Note,
             MidiTuningCreator::ScaleOctaveTuning { .. } => GroupBy::NoteLetter,
             MidiTuningCreator::ChannelFineTuning | MidiTuningCreator::PitchBend => GroupBy::Channel,
         }
@@ -272,6 +339,7 @@ impl MidiTuningCreator {
         match self {
             MidiTuningCreator::SingleNoteTuningChange { .. }
             | MidiTuningCreator::ScaleOctaveTuning { .. }
+            | MidiTuningCreator::BulkTuningDump { .. }
             | MidiTuningCreator::ChannelFineTuning => true,
             MidiTuningCreator::PitchBend => false,
         }
@@ -297,6 +365,9 @@ impl MidiTunerMessage {
             MidiTunerMessageVariant::ScaleOctaveTuning(tuning_message) => {
                 receiver(tuning_message.sysex_bytes());
             }
+            MidiTunerMessageVariant::BulkTuningDump(tuning_message) => {
+                receiver(tuning_message.sysex_bytes());
+            }
             MidiTunerMessageVariant::SingleNoteTuningChange(tuning_message) => {
                 for sysex_bytes in tuning_message.sysex_bytes() {
                     receiver(sysex_bytes);
@@ -310,6 +381,7 @@ enum MidiTunerMessageVariant {
     Channel(ChannelMessage),
     ScaleOctaveTuning(ScaleOctaveTuningMessage),
     SingleNoteTuningChange(SingleNoteTuningChangeMessage),
+    BulkTuningDump(BulkTuningDumpMessage),
 }
 
 impl From<ChannelMessage> for MidiTunerMessageVariant {
@@ -330,6 +402,12 @@ impl From<SingleNoteTuningChangeMessage> for MidiTunerMessageVariant {
     }
 }
 
+impl From<BulkTuningDumpMessage> for MidiTunerMessageVariant {
+    fn from(v: BulkTuningDumpMessage) -> Self {
+        Self::BulkTuningDump(v)
+    }
+}
+
 pub trait MidiTunerMessageHandler {
     fn handle(&mut self, message: MidiTunerMessage);
 
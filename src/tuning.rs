@@ -2,6 +2,8 @@
 
 #![allow(clippy::wrong_self_convention)] // Would require a breaking change. Fix when appropriate.
 
+use std::{collections::HashMap, hash::Hash};
+
 use crate::{
     note::{Note, NoteLetter},
     pitch::{Pitch, Pitched, Ratio},
@@ -149,6 +151,13 @@ impl<K, T: KeyboardMapping<K> + ?Sized> KeyboardMapping<K> for &T {
     }
 }
 
+/// `impl` forwarding for boxes.
+impl<K, T: KeyboardMapping<K> + ?Sized> KeyboardMapping<K> for Box<T> {
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        T::maybe_pitch_of(self, key)
+    }
+}
+
 /// Type adapter returned by [`Tuning::as_linear_mapping`].
 pub struct LinearMapping<T> {
     inner: T,
@@ -291,3 +300,592 @@ impl Tuning<Note> for () {
         ConcertPitch::default().find_by_pitch(pitch)
     }
 }
+
+/// Type adapter transposing all pitches of the inner [`Tuning`] or [`KeyboardMapping`] by a fixed [`Ratio`].
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::note::NoteLetter;
+/// # use tune::pitch::Pitch;
+/// # use tune::pitch::Ratio;
+/// # use tune::tuning::ConcertPitch;
+/// # use tune::tuning::Transposed;
+/// use tune::tuning::Tuning;
+///
+/// let a4 = NoteLetter::A.in_octave(4);
+///
+/// let transposed = Transposed::new(ConcertPitch::default(), Ratio::from_cents(-20.0));
+/// assert_approx_eq!(transposed.pitch_of(a4).as_hz(), 434.946169);
+///
+/// // Reference note + detune: A4 sounds at 432 Hz plus 5 cents
+/// let reference = Transposed::with_reference(ConcertPitch::default(), a4, Pitch::from_hz(432.0))
+///     .detuned_by(Ratio::from_cents(5.0));
+/// assert_approx_eq!(reference.pitch_of(a4).as_hz(), 433.249468);
+///
+/// let approximation = reference.find_by_pitch(Pitch::from_hz(432.0));
+/// assert_eq!(approximation.approx_value, a4);
+/// assert_approx_eq!(approximation.deviation.as_cents(), -5.0);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Transposed<T> {
+    inner: T,
+    transposition: Ratio,
+}
+
+impl<T> Transposed<T> {
+    /// Transposes all pitches of `inner` by `transposition`.
+    pub fn new(inner: T, transposition: Ratio) -> Self {
+        Self {
+            inner,
+            transposition,
+        }
+    }
+
+    /// Transposes all pitches of `inner` s.t. the given `key` sounds at the given `pitch`.
+    pub fn with_reference<K>(inner: T, key: K, pitch: impl Pitched) -> Self
+    where
+        T: Tuning<K>,
+    {
+        let transposition = Ratio::between_pitches(inner.pitch_of(key), pitch);
+        Self::new(inner, transposition)
+    }
+
+    /// Applies an additional `detuning` on top of the current transposition.
+    pub fn detuned_by(self, detuning: Ratio) -> Self {
+        Self::new(self.inner, self.transposition.stretched_by(detuning))
+    }
+
+    /// Returns the total transposition.
+    pub fn transposition(&self) -> Ratio {
+        self.transposition
+    }
+}
+
+impl<K, T: Tuning<K>> Tuning<K> for Transposed<T> {
+    fn pitch_of(&self, key: K) -> Pitch {
+        self.inner.pitch_of(key) * self.transposition
+    }
+
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<K> {
+        self.inner.find_by_pitch(pitch / self.transposition)
+    }
+}
+
+impl<K, T: KeyboardMapping<K>> KeyboardMapping<K> for Transposed<T> {
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        self.inner
+            .maybe_pitch_of(key)
+            .map(|pitch| pitch * self.transposition)
+    }
+}
+
+/// Type adapter stretching or compressing the inner [`Tuning`] or [`KeyboardMapping`] uniformly around a reference [`Pitch`].
+///
+/// A stretch of 5 cents means that every octave of the inner tuning is widened to 1205 cents, i.e. all intervals are scaled by a factor of 1205/1200.
+/// Negative values compress the tuning.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::note::NoteLetter;
+/// # use tune::pitch::Pitch;
+/// # use tune::pitch::Ratio;
+/// # use tune::tuning::ConcertPitch;
+/// # use tune::tuning::Stretched;
+/// use tune::tuning::Tuning;
+///
+/// let stretched = Stretched::new(ConcertPitch::default(), Pitch::from_hz(440.0), Ratio::from_cents(5.0));
+///
+/// let a2 = NoteLetter::A.in_octave(2);
+/// let a4 = NoteLetter::A.in_octave(4);
+/// let a6 = NoteLetter::A.in_octave(6);
+///
+/// assert_approx_eq!(Ratio::between_pitches(Pitch::from_hz(110.0), stretched.pitch_of(a2)).as_cents(), -10.0);
+/// assert_approx_eq!(stretched.pitch_of(a4).as_hz(), 440.0);
+/// assert_approx_eq!(Ratio::between_pitches(Pitch::from_hz(1760.0), stretched.pitch_of(a6)).as_cents(), 10.0);
+///
+/// let approximation = stretched.find_by_pitch(Pitch::from_hz(1760.0));
+/// assert_eq!(approximation.approx_value, a6);
+/// assert_approx_eq!(approximation.deviation.as_cents(), -10.0);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Stretched<T> {
+    inner: T,
+    reference: Pitch,
+    stretch_factor: f64,
+}
+
+impl<T> Stretched<T> {
+    /// Stretches every octave of `inner` by `stretch_per_octave` while `reference` stays fixed.
+    pub fn new(inner: T, reference: impl Pitched, stretch_per_octave: Ratio) -> Self {
+        Self {
+            inner,
+            reference: reference.pitch(),
+            stretch_factor: Ratio::octave()
+                .stretched_by(stretch_per_octave)
+                .as_octaves(),
+        }
+    }
+
+    fn stretch(&self, pitch: Pitch, stretch_factor: f64) -> Pitch {
+        self.reference * Ratio::between_pitches(self.reference, pitch).repeated(stretch_factor)
+    }
+}
+
+impl<K, T: Tuning<K>> Tuning<K> for Stretched<T> {
+    fn pitch_of(&self, key: K) -> Pitch {
+        self.stretch(self.inner.pitch_of(key), self.stretch_factor)
+    }
+
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<K> {
+        let approximation = self
+            .inner
+            .find_by_pitch(self.stretch(pitch, self.stretch_factor.recip()));
+        Approximation {
+            approx_value: approximation.approx_value,
+            deviation: approximation.deviation.repeated(self.stretch_factor),
+        }
+    }
+}
+
+impl<K, T: KeyboardMapping<K>> KeyboardMapping<K> for Stretched<T> {
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        self.inner
+            .maybe_pitch_of(key)
+            .map(|pitch| self.stretch(pitch, self.stretch_factor))
+    }
+}
+
+/// Type adapter approximating the Railsback curve of acoustic pianos.
+///
+/// The pitches of the inner [`Tuning`] or [`KeyboardMapping`] are detuned by `strength` &times; *d*&sup3; where *d* is the distance from the `reference` pitch in octaves.
+/// This leaves the center of the keyboard almost unaffected while the outer octaves become increasingly stretched.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::note::NoteLetter;
+/// # use tune::pitch::Pitch;
+/// # use tune::pitch::Ratio;
+/// # use tune::tuning::ConcertPitch;
+/// # use tune::tuning::Railsback;
+/// use tune::tuning::Tuning;
+///
+/// let railsback = Railsback::new(ConcertPitch::default(), Pitch::from_hz(440.0), Ratio::from_cents(0.5)).unwrap();
+///
+/// let detuning = |note| Ratio::between_pitches(ConcertPitch::default().pitch_of(note), railsback.pitch_of(note)).as_cents();
+///
+/// assert_approx_eq!(detuning(NoteLetter::A.in_octave(0)), -32.0);
+/// assert_approx_eq!(detuning(NoteLetter::A.in_octave(3)), -0.5);
+/// assert_approx_eq!(detuning(NoteLetter::A.in_octave(4)), 0.0);
+/// assert_approx_eq!(detuning(NoteLetter::A.in_octave(7)), 13.5);
+///
+/// let approximation = railsback.find_by_pitch(Pitch::from_hz(27.5));
+/// assert_eq!(approximation.approx_value, NoteLetter::A.in_octave(0));
+/// assert_approx_eq!(approximation.deviation.as_cents(), 32.0);
+///
+/// // Negative strengths are rejected
+/// assert!(Railsback::new(ConcertPitch::default(), Pitch::from_hz(440.0), Ratio::from_cents(-0.5)).is_none());
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Railsback<T> {
+    inner: T,
+    reference: Pitch,
+    strength: f64,
+}
+
+impl<T> Railsback<T> {
+    /// Applies a Railsback-like curve with the given `strength` to `inner` while `reference` stays fixed.
+    ///
+    /// [`None`] is returned if `strength` is negative or not finite.
+    pub fn new(inner: T, reference: impl Pitched, strength: Ratio) -> Option<Self> {
+        let strength = strength.as_octaves();
+        (strength >= 0.0 && strength.is_finite()).then(|| Self {
+            inner,
+            reference: reference.pitch(),
+            strength,
+        })
+    }
+
+    fn detune(&self, pitch: Pitch) -> Pitch {
+        let distance = Ratio::between_pitches(self.reference, pitch).as_octaves();
+        self.reference * Ratio::from_octaves(distance + self.strength * distance.powi(3))
+    }
+
+    fn undo_detuning(&self, pitch: Pitch) -> Pitch {
+        let target = Ratio::between_pitches(self.reference, pitch).as_octaves();
+
+        // Newton's method: The detuning curve is strictly increasing for non-negative strengths
+        let mut distance = target;
+        for _ in 0..MAX_NEWTON_ITERATIONS {
+            let error = distance + self.strength * distance.powi(3) - target;
+            if error.abs() < NEWTON_TOLERANCE {
+                break;
+            }
+            distance -= error / (1.0 + 3.0 * self.strength * distance.powi(2));
+        }
+
+        self.reference * Ratio::from_octaves(distance)
+    }
+}
+
+const MAX_NEWTON_ITERATIONS: usize = 50;
+const NEWTON_TOLERANCE: f64 = 1e-12;
+
+impl<K, T: Tuning<K>> Tuning<K> for Railsback<T> {
+    fn pitch_of(&self, key: K) -> Pitch {
+        self.detune(self.inner.pitch_of(key))
+    }
+
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<K> {
+        let approximation = self.inner.find_by_pitch(self.undo_detuning(pitch));
+        let approx_pitch = self.undo_detuning(pitch) / approximation.deviation;
+        Approximation {
+            approx_value: approximation.approx_value,
+            deviation: Ratio::between_pitches(self.detune(approx_pitch), pitch),
+        }
+    }
+}
+
+impl<K, T: KeyboardMapping<K>> KeyboardMapping<K> for Railsback<T> {
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        self.inner
+            .maybe_pitch_of(key)
+            .map(|pitch| self.detune(pitch))
+    }
+}
+
+/// Type adapter detuning individual keys of the inner [`Tuning`] or [`KeyboardMapping`] according to an offset table.
+///
+/// Keys not contained in the offset table remain unchanged.
+/// [`Tuning::find_by_pitch`] compares all keys of the offset table with the unchanged keys of the inner tuning that are closest to the given pitch from below and from above.
+/// Ties are resolved in favor of the unchanged keys and, after that, in the order of the offset table.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::key::PianoKey;
+/// # use tune::note::Note;
+/// # use tune::pitch::Pitch;
+/// # use tune::pitch::Ratio;
+/// # use tune::scala::KbmRoot;
+/// # use tune::scala::Scl;
+/// # use tune::tuning::WithOffsets;
+/// use tune::tuning::Tuning;
+///
+/// let scl = Scl::builder().push_cents(100.0).build().unwrap();
+/// let kbm_root = KbmRoot::from(Note::from_midi_number(69));
+///
+/// let with_offsets = WithOffsets::new(
+///     (scl, kbm_root),
+///     [
+///         (PianoKey::from_midi_number(70), Ratio::from_cents(-40.0)),
+///         (PianoKey::from_midi_number(71), Ratio::from_cents(60.0)),
+///     ],
+/// );
+///
+/// let pitch_of = |midi_number| with_offsets.pitch_of(PianoKey::from_midi_number(midi_number));
+/// assert_approx_eq!(pitch_of(69).as_hz(), 440.0);
+/// assert_approx_eq!(pitch_of(70).as_hz(), 455.516566);
+/// assert_approx_eq!(pitch_of(71).as_hz(), 511.300058);
+///
+/// // 240 cents above A4 is closer to the detuned key 71 than to key 70 and key 72
+/// let approximation = with_offsets.find_by_pitch(Pitch::from_hz(440.0) * Ratio::from_cents(240.0));
+/// assert_eq!(approximation.approx_value, PianoKey::from_midi_number(71));
+/// assert_approx_eq!(approximation.deviation.as_cents(), -20.0);
+///
+/// // Key 70 is detuned far away, s.t. key 71 becomes the closest key to 140 cents above A4
+/// let with_offsets = WithOffsets::new(
+///     (Scl::builder().push_cents(100.0).build().unwrap(), kbm_root),
+///     [(PianoKey::from_midi_number(70), Ratio::from_cents(-90.0))],
+/// );
+///
+/// let approximation = with_offsets.find_by_pitch(Pitch::from_hz(440.0) * Ratio::from_cents(140.0));
+/// assert_eq!(approximation.approx_value, PianoKey::from_midi_number(71));
+/// assert_approx_eq!(approximation.deviation.as_cents(), -60.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct WithOffsets<K, T> {
+    inner: T,
+    offsets: HashMap<K, Ratio>,
+    offset_keys: Vec<K>,
+}
+
+impl<K: Copy + Eq + Hash, T> WithOffsets<K, T> {
+    /// Detunes the keys of `inner` by the given `offsets`.
+    ///
+    /// If a key occurs multiple times, the last offset wins.
+    pub fn new(inner: T, offsets: impl IntoIterator<Item = (K, Ratio)>) -> Self {
+        let mut offset_map = HashMap::new();
+        let mut offset_keys = Vec::new();
+        for (key, offset) in offsets {
+            if offset_map.insert(key, offset).is_none() {
+                offset_keys.push(key);
+            }
+        }
+
+        Self {
+            inner,
+            offsets: offset_map,
+            offset_keys,
+        }
+    }
+
+    /// Returns the offset of the given `key`.
+    pub fn offset_of(&self, key: &K) -> Ratio {
+        self.offsets.get(key).copied().unwrap_or_default()
+    }
+}
+
+impl<K: Copy + Eq + Hash, T: Tuning<K>> Tuning<K> for WithOffsets<K, T> {
+    fn pitch_of(&self, key: K) -> Pitch {
+        self.inner.pitch_of(key) * self.offset_of(&key)
+    }
+
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<K> {
+        let closest_inner_key = self.inner.find_by_pitch(pitch).approx_value;
+
+        let closest_unchanged_keys = if self.offsets.contains_key(&closest_inner_key) {
+            [
+                self.next_unchanged_key(closest_inner_key, -1.0),
+                self.next_unchanged_key(closest_inner_key, 1.0),
+            ]
+        } else {
+            [Some(closest_inner_key), None]
+        };
+
+        closest_unchanged_keys
+            .into_iter()
+            .flatten()
+            .chain(self.offset_keys.iter().copied())
+            .map(|key| Approximation {
+                approx_value: key,
+                deviation: Ratio::between_pitches(self.pitch_of(key), pitch),
+            })
+            .min_by(|a, b| {
+                a.deviation
+                    .abs()
+                    .as_octaves()
+                    .total_cmp(&b.deviation.abs().as_octaves())
+            })
+            .unwrap()
+    }
+}
+
+impl<K: Copy + Eq + Hash, T: Tuning<K>> WithOffsets<K, T> {
+    /// Walks along the inner tuning in the given `direction` (-1.0 or 1.0) until a key without offset is found.
+    fn next_unchanged_key(&self, mut key: K, direction: f64) -> Option<K> {
+        for _ in 0..self.offset_keys.len() {
+            key = self.adjacent_inner_key(key, direction)?;
+            if !self.offsets.contains_key(&key) {
+                return Some(key);
+            }
+        }
+        None
+    }
+
+    /// Finds the key of the inner tuning adjacent to `key` by bisecting the pitch boundary between the two keys.
+    fn adjacent_inner_key(&self, key: K, direction: f64) -> Option<K> {
+        const MAX_NUM_DOUBLINGS: u32 = 20;
+        const PRECISION_IN_OCTAVES: f64 = 1e-9;
+
+        let pitch_of_key = self.inner.pitch_of(key);
+        let key_at = |octaves: f64| {
+            self.inner
+                .find_by_pitch(pitch_of_key * Ratio::from_octaves(direction * octaves))
+                .approx_value
+        };
+
+        let mut inside = 0.0;
+        let mut outside = Ratio::from_cents(1.0).as_octaves();
+        for num_doublings in 0.. {
+            if key_at(outside) != key {
+                break;
+            }
+            if num_doublings == MAX_NUM_DOUBLINGS {
+                return None;
+            }
+            inside = outside;
+            outside *= 2.0;
+        }
+
+        while outside - inside > PRECISION_IN_OCTAVES {
+            let middle = (inside + outside) / 2.0;
+            if key_at(middle) == key {
+                inside = middle;
+            } else {
+                outside = middle;
+            }
+        }
+
+        Some(key_at(outside))
+    }
+}
+
+impl<K: Copy + Eq + Hash, T: KeyboardMapping<K>> KeyboardMapping<K> for WithOffsets<K, T> {
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        let offset = self.offset_of(&key);
+        self.inner.maybe_pitch_of(key).map(|pitch| pitch * offset)
+    }
+}
+
+/// Time-varying interpolation between two [`KeyboardMapping`]s.
+///
+/// The pitch of each key is interpolated linearly in cents between the `source` and the `target` mapping according to the current blend factor.
+/// A blend factor of 0.0 yields the `source` pitches and a blend factor of 1.0 yields the `target` pitches.
+///
+/// A key that is only mapped by one of the two mappings has a [`Pitch`] only when the blend factor is at the corresponding end point.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::key::PianoKey;
+/// # use tune::note::Note;
+/// # use tune::pitch::Ratio;
+/// # use tune::scala::KbmRoot;
+/// # use tune::scala::Scl;
+/// # use tune::tuning::Morph;
+/// use tune::tuning::KeyboardMapping;
+///
+/// let kbm_root = KbmRoot::from(Note::from_midi_number(62));
+/// let edo_12 = (Scl::builder().push_cents(100.0).build().unwrap(), kbm_root.to_kbm());
+/// let edo_22 = (Scl::builder().push_ratio(Ratio::octave().divided_into_equal_steps(22)).build().unwrap(), kbm_root.to_kbm());
+///
+/// let mut morph = Morph::per_key(edo_12, edo_22);
+///
+/// let cents_above_d4 = |morph: &Morph<_, _, _>, midi_number| {
+///     let pitch = morph.maybe_pitch_of(PianoKey::from_midi_number(midi_number)).unwrap();
+///     Ratio::between_pitches(kbm_root.ref_pitch, pitch).as_cents()
+/// };
+///
+/// assert_approx_eq!(cents_above_d4(&morph, 66), 400.0);
+///
+/// morph.set_blend(0.5);
+/// assert_approx_eq!(cents_above_d4(&morph, 66), 309.090909);
+///
+/// morph.set_blend(1.0);
+/// assert_approx_eq!(cents_above_d4(&morph, 66), 218.181818);
+///
+/// // Values outside of [0.0, 1.0] are clamped
+/// morph.set_blend(2.0);
+/// assert_eq!(morph.blend(), 1.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Morph<K, A, B> {
+    source: A,
+    target: B,
+    partners: HashMap<K, K>,
+    blend: f64,
+}
+
+impl<K, A, B> Morph<K, A, B> {
+    /// Morphs each key of `source` into the same key of `target`.
+    ///
+    /// This is the natural choice if both mappings have the same number of scale degrees.
+    pub fn per_key(source: A, target: B) -> Self {
+        Self {
+            source,
+            target,
+            partners: HashMap::new(),
+            blend: 0.0,
+        }
+    }
+
+    /// Returns the current blend factor.
+    pub fn blend(&self) -> f64 {
+        self.blend
+    }
+
+    /// Sets the blend factor, clamped to the range [0.0, 1.0].
+    pub fn set_blend(&mut self, blend: f64) {
+        self.blend = blend.clamp(0.0, 1.0);
+    }
+}
+
+impl<K: Copy + Eq + Hash, A: KeyboardMapping<K>, B: KeyboardMapping<K>> Morph<K, A, B> {
+    /// Morphs each key of `source` into the key of `target` with the nearest [`Pitch`].
+    ///
+    /// This is useful when the scale sizes differ s.t. the same key would end up at a different scale degree.
+    /// The partners are searched among the given `keys`.
+    /// Keys that are not included in `keys` are morphed per key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::key::PianoKey;
+    /// # use tune::note::Note;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::KbmRoot;
+    /// # use tune::scala::Scl;
+    /// # use tune::tuning::Morph;
+    /// use tune::tuning::KeyboardMapping;
+    ///
+    /// let kbm_root = KbmRoot::from(Note::from_midi_number(62));
+    /// let edo_12 = (Scl::builder().push_cents(100.0).build().unwrap(), kbm_root.to_kbm());
+    /// let edo_22 = (Scl::builder().push_ratio(Ratio::octave().divided_into_equal_steps(22)).build().unwrap(), kbm_root.to_kbm());
+    ///
+    /// let keys = (0..128).map(PianoKey::from_midi_number);
+    /// let mut morph = Morph::nearest_pitch(edo_12, edo_22, keys);
+    /// morph.set_blend(1.0);
+    ///
+    /// // 400 cents in 12-EDO are closest to 7 steps = 381.818 cents in 22-EDO
+    /// let pitch = morph.maybe_pitch_of(PianoKey::from_midi_number(66)).unwrap();
+    /// assert_approx_eq!(Ratio::between_pitches(kbm_root.ref_pitch, pitch).as_cents(), 381.818182);
+    /// ```
+    pub fn nearest_pitch(source: A, target: B, keys: impl IntoIterator<Item = K>) -> Self {
+        let target_pitches = Vec::from_iter(
+            keys.into_iter()
+                .filter_map(|key| target.maybe_pitch_of(key).map(|pitch| (key, pitch))),
+        );
+
+        let partners = target_pitches
+            .iter()
+            .filter_map(|&(key, _)| {
+                let source_pitch = source.maybe_pitch_of(key)?;
+                let (partner, _) = target_pitches.iter().min_by(|(_, a), (_, b)| {
+                    let deviation = |pitch| {
+                        Ratio::between_pitches(source_pitch, pitch)
+                            .abs()
+                            .as_octaves()
+                    };
+                    deviation(*a).total_cmp(&deviation(*b))
+                })?;
+                Some((key, *partner))
+            })
+            .collect();
+
+        Self {
+            source,
+            target,
+            partners,
+            blend: 0.0,
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash, A: KeyboardMapping<K>, B: KeyboardMapping<K>> KeyboardMapping<K>
+    for Morph<K, A, B>
+{
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        let partner = self.partners.get(&key).copied().unwrap_or(key);
+
+        match (
+            self.source.maybe_pitch_of(key),
+            self.target.maybe_pitch_of(partner),
+        ) {
+            (Some(source), Some(target)) => {
+                Some(source * Ratio::between_pitches(source, target).repeated(self.blend))
+            }
+            (Some(source), None) if self.blend == 0.0 => Some(source),
+            (None, Some(target)) if self.blend == 1.0 => Some(target),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+use tune::scala::{Propriety, Scl, SclAnalysis};
+
+use crate::{
+    error::ResultExt, scala::SclCommand, scale::ApproximationOptions, App, CliError, CliResult,
+};
+
+#[derive(Parser)]
+pub(crate) struct AnalyzeOptions {
+    /// Output format of the analysis report
+    #[arg(long = "format", value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    #[command(flatten)]
+    approximation: ApproximationOptions,
+
+    #[command(subcommand)]
+    scl: SclCommand,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum OutputFormat {
+    #[value(name = "text")]
+    Text,
+    #[value(name = "yaml")]
+    Yaml,
+    #[value(name = "json")]
+    Json,
+}
+
+impl AnalyzeOptions {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        let scl = self.scl.to_scl(None)?;
+        let analysis = scl.analyze(&self.approximation.strategy());
+
+        match self.format {
+            OutputFormat::Text => print_analysis(app, &scl, &analysis),
+            OutputFormat::Yaml => serde_yaml::to_writer(&mut app.output, &to_dto(&scl, &analysis))
+                .handle_error::<CliError>("Could not write analysis"),
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut app.output, &to_dto(&scl, &analysis))
+                    .handle_error::<CliError>("Could not write analysis")?;
+                Ok(app.writeln("")?)
+            }
+        }
+    }
+}
+
+fn print_analysis(app: &mut App, scl: &Scl, analysis: &SclAnalysis) -> CliResult {
+    let num_step_sizes = analysis.step_sizes.len();
+
+    app.writeln(format_args!("Scale: {}", scl.description()))?;
+    app.writeln(format_args!("Number of notes: {}", scl.num_items()))?;
+    app.write("Step sizes:")?;
+    for (step, step_size) in analysis.step_sizes.iter().enumerate() {
+        app.write(format_args!(
+            " {} = {:.3}c",
+            step_name(step, num_step_sizes),
+            step_size.as_cents()
+        ))?;
+    }
+    app.writeln("")?;
+    app.writeln(format_args!(
+        "Step pattern: {}",
+        String::from_iter(
+            analysis
+                .step_pattern
+                .iter()
+                .map(|&step| step_name(step, num_step_sizes))
+        )
+    ))?;
+    match analysis.mos {
+        Some(mos) => app.writeln(format_args!(
+            "MOS: {}L{}s, L/s = {:.2}",
+            mos.num_primary_steps(),
+            mos.num_secondary_steps(),
+            mos.primary_step() / mos.secondary_step()
+        ))?,
+        None => app.writeln("MOS: no")?,
+    }
+    app.writeln(format_args!(
+        "Propriety: {}",
+        propriety_name(analysis.propriety)
+    ))?;
+    app.writeln(format_args!(
+        "Myhill's property: {}",
+        yes_no(analysis.has_myhill_property)
+    ))?;
+    app.writeln(format_args!(
+        "Maximally even: {}",
+        yes_no(analysis.is_maximally_even)
+    ))?;
+
+    app.writeln("")?;
+    app.writeln("Interval matrix (cents):")?;
+    for (degree, intervals) in analysis.interval_matrix.iter().enumerate() {
+        app.write(format_args!("{degree:>4} |"))?;
+        for interval in intervals {
+            app.write(format_args!(" {:>9.3}", interval.as_cents()))?;
+        }
+        app.writeln("")?;
+    }
+
+    app.writeln("")?;
+    app.writeln("JI interpretations:")?;
+    for (degree, ji_interpretation) in analysis.ji_interpretations.iter().enumerate() {
+        let pitch = scl.sorted_relative_pitch_of(degree as i32 + 1);
+        app.write(format_args!(
+            "{:>4} | {:>9.3}c |",
+            degree + 1,
+            pitch.as_cents()
+        ))?;
+        match ji_interpretation {
+            Some(approximation) => app.writeln(format_args!(
+                " {:>9} {:>+9.3}c",
+                approximation.approx_value.to_string(),
+                approximation.deviation.as_cents()
+            ))?,
+            None => app.writeln(" -")?,
+        }
+    }
+
+    Ok(())
+}
+
+fn step_name(step: usize, num_step_sizes: usize) -> String {
+    match (num_step_sizes, step) {
+        (2 | 3, 0) => "L".to_owned(),
+        (3, 1) => "M".to_owned(),
+        (2, 1) | (3, 2) => "s".to_owned(),
+        _ => match u8::try_from(step) {
+            Ok(step @ 0..=25) => char::from(b'A' + step).to_string(),
+            _ => format!("({step})"),
+        },
+    }
+}
+
+fn propriety_name(propriety: Propriety) -> &'static str {
+    match propriety {
+        Propriety::StrictlyProper => "strictly proper",
+        Propriety::Proper => "proper",
+        Propriety::Improper => "improper",
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn to_dto(scl: &Scl, analysis: &SclAnalysis) -> AnalysisDto {
+    AnalysisDto {
+        description: scl.description().to_owned(),
+        num_items: scl.num_items(),
+        interval_matrix_in_cents: analysis
+            .interval_matrix
+            .iter()
+            .map(|intervals| {
+                intervals
+                    .iter()
+                    .map(|interval| interval.as_cents())
+                    .collect()
+            })
+            .collect(),
+        step_sizes_in_cents: analysis
+            .step_sizes
+            .iter()
+            .map(|step_size| step_size.as_cents())
+            .collect(),
+        step_pattern: analysis.step_pattern.clone(),
+        mos: analysis.mos.map(|mos| MosDto {
+            num_large_steps: mos.num_primary_steps(),
+            num_small_steps: mos.num_secondary_steps(),
+            step_ratio: mos.primary_step() / mos.secondary_step(),
+        }),
+        propriety: propriety_name(analysis.propriety),
+        has_myhill_property: analysis.has_myhill_property,
+        is_maximally_even: analysis.is_maximally_even,
+        ji_interpretations: analysis
+            .ji_interpretations
+            .iter()
+            .map(|ji_interpretation| {
+                ji_interpretation
+                    .as_ref()
+                    .map(|approximation| JiInterpretationDto {
+                        ratio: approximation.approx_value.to_string(),
+                        deviation_in_cents: approximation.deviation.as_cents(),
+                    })
+            })
+            .collect(),
+    }
+}
+
+#[derive(Serialize)]
+struct AnalysisDto {
+    description: String,
+    num_items: u16,
+    interval_matrix_in_cents: Vec<Vec<f64>>,
+    step_sizes_in_cents: Vec<f64>,
+    step_pattern: Vec<usize>,
+    mos: Option<MosDto>,
+    propriety: &'static str,
+    has_myhill_property: bool,
+    is_maximally_even: bool,
+    ji_interpretations: Vec<Option<JiInterpretationDto>>,
+}
+
+#[derive(Serialize)]
+struct MosDto {
+    num_large_steps: u16,
+    num_small_steps: u16,
+    step_ratio: f64,
+}
+
+#[derive(Serialize)]
+struct JiInterpretationDto {
+    ratio: String,
+    deviation_in_cents: f64,
+}
@@ -1,22 +1,30 @@
 use std::{
+    ffi::OsStr,
     fmt::{self, Display},
+    fs::File,
     io,
+    path::{Path, PathBuf},
 };
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
 use tune::{
-    layout::IsomorphicLayout,
+    consonance::{self, HarmonicEntropy},
+    ji::{ApproximationStrategy, JiRatio},
+    layout::{Genchain, IsomorphicLayout, NoteNotation, ValExpression},
     math,
     pitch::Ratio,
-    temperament::{self, CommaCatalog, Val},
+    temperament::{self, Comma, CommaCatalog, CommaImportError, Subgroup, Val, ValRanking},
 };
 
-use crate::{App, CliResult};
+use crate::{scale::ConsonanceOptions, App, CliResult};
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub(crate) struct EstOptions {
     /// Size of the interval to analyze
-    step_size: Ratio,
+    #[arg(required = true)]
+    step_size: Option<Ratio>,
 
     /// Odd limit for val output
     #[arg(long = "limit", default_value = "13")]
@@ -25,11 +33,118 @@ pub(crate) struct EstOptions {
     /// Error threshold for subgroup determination
     #[arg(long = "error", default_value = "25c")]
     error_threshold: Ratio,
+
+    /// Notation used for note names.
+    /// [acc] Sharps and flats.
+    /// [ups-downs] Sharps and flats with ups (^) and downs (v) of one EDO step.
+    /// [heji] HEJI-style names of the nearest JI ratio within the given odd limit.
+    #[arg(long = "notation", value_enum, default_value = "acc")]
+    notation: Notation,
+
+    #[command(flatten)]
+    consonance: ConsonanceOptions,
+
+    /// Comma catalog file whose interval names take precedence over the built-in catalog.
+    /// [*.par] Scala's intnam.par format.
+    /// [*.yml, *.yaml] List of entries with a ratio and names, e.g. {ratio: 81/80, names: syntonic comma}.
+    /// [*] CSV file with a ratio and names per line, e.g. 81/80,syntonic comma.
+    /// Can be specified multiple times
+    #[arg(long = "comma-catalog")]
+    comma_catalogs: Vec<PathBuf>,
+
+    /// YAML file with additional genchains to derive note names from, e.g. [{name: Orwell, generator: {coefficients: [0, 1], divisor: 7}, note_names: BDFHACEGJ, genchain_origin: 4, scale_names: [gramitonic, semiquartal]}].
+    /// Can be specified multiple times
+    #[arg(long = "genchains")]
+    genchain_files: Vec<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<EstCommand>,
+}
+
+#[derive(Parser)]
+enum EstCommand {
+    /// Find the best equal-step tunings for a given subgroup
+    #[command(name = "search")]
+    Search(SearchOptions),
+}
+
+#[derive(Parser)]
+struct SearchOptions {
+    /// Interval to divide into equal steps, e.g. 2 for EDOs or 3 for EDTs
+    #[arg(long = "equave", default_value = "2", value_parser = parse_equave)]
+    equave: Ratio,
+
+    /// Maximum number of steps per equave
+    #[arg(long = "max", default_value = "200")]
+    max_num_steps: u16,
+
+    /// Subgroup to optimize for, e.g. 2.3.7.11 or 2.5/3.7/3
+    #[arg(long = "subgroup", default_value = "2.3.5.7.11.13")]
+    subgroup: Subgroup,
+
+    /// Criterion used to rank the vals
+    #[arg(long = "rank-by", value_enum, default_value = "badness")]
+    ranking: Ranking,
+
+    /// Number of vals to print
+    #[arg(long = "num", default_value = "30")]
+    num_results: usize,
+
+    /// Exclude vals with warts, i.e. only consider patent vals
+    #[arg(long = "patent-only")]
+    patent_only: bool,
+}
+
+fn parse_equave(src: &str) -> Result<Ratio, String> {
+    let equave = src.parse::<Ratio>()?;
+    if equave.as_octaves() > 0.0 {
+        Ok(equave)
+    } else {
+        Err("Equave must be larger than 1/1".to_owned())
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum Ranking {
+    #[value(name = "te-error")]
+    TeError,
+    #[value(name = "badness")]
+    TeSimpleBadness,
+    #[value(name = "max-error")]
+    MaxRelativeError,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum Notation {
+    #[value(name = "acc")]
+    Accidentals,
+    #[value(name = "ups-downs")]
+    UpsAndDowns,
+    #[value(name = "heji")]
+    Heji,
 }
 
 impl EstOptions {
     pub fn run(&self, app: &mut App) -> CliResult {
-        let layouts = IsomorphicLayout::find_by_step_size(self.step_size);
+        match (&self.command, self.step_size) {
+            (Some(EstCommand::Search(options)), _) => options.run(app),
+            (None, Some(step_size)) => self.analyze(app, step_size),
+            (None, None) => unreachable!("step size is required"),
+        }
+    }
+
+    fn analyze(&self, app: &mut App, step_size: Ratio) -> CliResult {
+        let mut genchains = Vec::new();
+        for file_name in &self.genchain_files {
+            genchains.extend(import_genchains(file_name)?);
+        }
+
+        let layouts = IsomorphicLayout::find_by_step_size_with(step_size, &genchains);
+
+        let mut catalog = CommaCatalog::new(temperament::huygens_fokker_intervals());
+        for file_name in &self.comma_catalogs {
+            catalog.merge(import_comma_catalog(file_name)?);
+        }
 
         for print_b_val in [false, true] {
             let mut val_printed = false;
@@ -41,8 +156,8 @@ impl EstOptions {
 
                 let mut printer = EstPrinter {
                     app,
-                    val: Val::patent(self.step_size, self.odd_limit),
-                    catalog: CommaCatalog::new(temperament::huygens_fokker_intervals()),
+                    val: Val::patent(step_size, self.odd_limit),
+                    catalog: catalog.clone(),
                 };
 
                 if layout.b_val() {
@@ -55,7 +170,7 @@ impl EstOptions {
                     printer.print_headline(printer.val.values()[0], layout.wart(), stretch)?;
                     printer.print_newline()?;
 
-                    printer.print_basic_information(self.step_size)?;
+                    printer.print_basic_information(step_size)?;
                     printer.print_newline()?;
 
                     printer.print_val(self.odd_limit, self.error_threshold)?;
@@ -64,26 +179,203 @@ impl EstOptions {
                     printer.print_tempered_out_commas()?;
                     printer.print_newline()?;
 
-                    printer.print_interval_location("septimal minor third")?;
-                    printer.print_interval_location("minor third")?;
-                    printer.print_interval_location("major third")?;
-                    printer.print_interval_location("perfect fourth")?;
-                    printer.print_interval_location("perfect fifth")?;
-                    printer.print_interval_location("harmonic seventh")?;
-                    printer.print_interval_location("octave")?;
+                    printer.print_interval_location(7, 6)?;
+                    printer.print_interval_location(6, 5)?;
+                    printer.print_interval_location(5, 4)?;
+                    printer.print_interval_location(4, 3)?;
+                    printer.print_interval_location(3, 2)?;
+                    printer.print_interval_location(7, 4)?;
+                    printer.print_interval_location(2, 1)?;
 
                     printer.print_newline()?;
 
+                    if let Some(harmonic_entropy) = self.consonance.harmonic_entropy()? {
+                        printer.print_consonance(&harmonic_entropy, self.odd_limit)?;
+                        printer.print_newline()?;
+                    }
+
                     val_printed = true;
                 }
 
-                printer.print_generalized_notes(layout)?;
+                printer.print_generalized_notes(layout, self.note_notation())?;
                 printer.print_newline()?;
             }
         }
 
         Ok(())
     }
+
+    fn note_notation(&self) -> NoteNotation {
+        match self.notation {
+            Notation::Accidentals => NoteNotation::Accidentals,
+            Notation::UpsAndDowns => NoteNotation::UpsAndDowns,
+            Notation::Heji => NoteNotation::Heji {
+                odd_limit: self.odd_limit.into(),
+            },
+        }
+    }
+}
+
+impl SearchOptions {
+    fn run(&self, app: &mut App) -> CliResult {
+        let ranking = match self.ranking {
+            Ranking::TeError => ValRanking::TeError,
+            Ranking::TeSimpleBadness => ValRanking::TeSimpleBadness,
+            Ranking::MaxRelativeError => ValRanking::MaxRelativeError,
+        };
+        let results = temperament::search_vals(
+            self.equave,
+            self.max_num_steps,
+            &self.subgroup,
+            !self.patent_only,
+            ranking,
+        );
+
+        app.writeln(format_args!(
+            "==== Best vals for subgroup {} ====",
+            self.subgroup
+        ))?;
+        app.writeln("")?;
+        app.writeln(format_args!(
+            "{:>5} {:<8} {:<32} {:>11} {:>10} {:>9}",
+            "rank", "val", "mapping", "TE error", "badness", "max error"
+        ))?;
+
+        for (rank, result) in results.iter().take(self.num_results).enumerate() {
+            let mapping = format!("<{}]", WithSeparator(" ", || result.val.values()));
+            app.writeln(format_args!(
+                "{:>4}. {:<8} {:<32} {:>6.3}c/oct {:>9.3}‰ {:>8.1}%",
+                rank + 1,
+                result.val.wart_notation(),
+                mapping,
+                result.te_error,
+                result.te_simple_badness * 1000.0,
+                result.max_relative_error * 100.0,
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn import_comma_catalog(file_name: &Path) -> Result<Vec<Comma>, String> {
+    let file = File::open(file_name)
+        .map_err(|err| format!("Could not read comma catalog {file_name:#?}: {err}"))?;
+
+    let commas = match file_name.extension().and_then(OsStr::to_str) {
+        Some("par") => temperament::import_intnam_par(file),
+        Some("yml" | "yaml") => return import_comma_yaml(file_name, file),
+        _ => temperament::import_comma_csv(file),
+    };
+    commas.map_err(|err| match err {
+        CommaImportError::IoError(err) => {
+            format!("Could not read comma catalog {file_name:#?}: {err}")
+        }
+        CommaImportError::ParseError { line_number, kind } => {
+            format!("Could not parse comma catalog {file_name:#?} at line {line_number}: {kind:#?}")
+        }
+    })
+}
+
+fn import_comma_yaml(file_name: &Path, file: File) -> Result<Vec<Comma>, String> {
+    let entries: Vec<CommaEntry> = serde_yaml::from_reader(file)
+        .map_err(|err| format!("Could not parse comma catalog {file_name:#?}: {err}"))?;
+
+    let mut commas = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let comma = temperament::parse_comma_entry(&entry.ratio, &entry.names).map_err(|kind| {
+            format!(
+                "Could not parse comma catalog {file_name:#?} at entry {}: {kind:#?}",
+                index + 1
+            )
+        })?;
+        commas.extend(comma);
+    }
+    Ok(commas)
+}
+
+#[derive(Deserialize)]
+struct CommaEntry {
+    ratio: String,
+    names: String,
+}
+
+#[derive(Deserialize)]
+struct GenchainEntry {
+    name: String,
+    #[serde(default = "octave")]
+    period: ValExpressionEntry,
+    generator: ValExpressionEntry,
+    note_names: String,
+    genchain_origin: u16,
+    #[serde(default = "sharp_flat_signs")]
+    sharp_flat_signs: [char; 2],
+    #[serde(default = "negative_sharp_flat_signs")]
+    negative_sharp_flat_signs: [char; 2],
+    scale_names: [String; 2],
+}
+
+#[derive(Deserialize)]
+struct ValExpressionEntry {
+    coefficients: Vec<i16>,
+    #[serde(default = "one")]
+    divisor: u16,
+}
+
+fn octave() -> ValExpressionEntry {
+    ValExpressionEntry {
+        coefficients: vec![1],
+        divisor: 1,
+    }
+}
+
+fn sharp_flat_signs() -> [char; 2] {
+    ['#', 'b']
+}
+
+fn negative_sharp_flat_signs() -> [char; 2] {
+    ['-', '+']
+}
+
+fn one() -> u16 {
+    1
+}
+
+impl From<ValExpressionEntry> for ValExpression {
+    fn from(entry: ValExpressionEntry) -> Self {
+        ValExpression::new(entry.coefficients, entry.divisor)
+    }
+}
+
+pub fn import_genchains(file_name: &Path) -> Result<Vec<Genchain>, String> {
+    let file = File::open(file_name)
+        .map_err(|err| format!("Could not read genchains {file_name:#?}: {err}"))?;
+
+    let entries: Vec<GenchainEntry> = serde_yaml::from_reader(file)
+        .map_err(|err| format!("Could not parse genchains {file_name:#?}: {err}"))?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            if usize::from(entry.genchain_origin) >= entry.note_names.chars().count() {
+                return Err(format!(
+                    "Invalid genchain {:?} in {file_name:#?}: Genchain origin must be less than the number of note names",
+                    entry.name
+                ));
+            }
+            let [positive_scale_name, negative_scale_name] = entry.scale_names;
+            Ok(Genchain {
+                name: entry.name.into(),
+                period: entry.period.into(),
+                generator: entry.generator.into(),
+                note_names: Vec::from_iter(entry.note_names.chars()).into(),
+                genchain_origin: entry.genchain_origin,
+                sharp_flat_signs: entry.sharp_flat_signs,
+                negative_sharp_flat_signs: entry.negative_sharp_flat_signs,
+                scale_names: [positive_scale_name.into(), negative_scale_name.into()],
+            })
+        })
+        .collect()
 }
 
 struct EstPrinter<'a, 'b> {
@@ -150,7 +442,7 @@ impl<'a, 'b> EstPrinter<'a, 'b> {
         ))?;
         self.app.writeln(format_args!(
             "- subgroup: {}",
-            WithSeparator(".", || self.val.subgroup(threshold))
+            self.val.subgroup_basis(threshold)
         ))?;
 
         Ok(())
@@ -181,10 +473,11 @@ impl<'a, 'b> EstPrinter<'a, 'b> {
         Ok(())
     }
 
-    fn print_interval_location(&mut self, interval_name: &str) -> io::Result<()> {
-        let interval = self.catalog.comma_for_name(interval_name).unwrap();
-        let fraction = interval.as_fraction().unwrap();
-        let tempered_location = self.val.map(interval).unwrap_or_default();
+    fn print_interval_location(&mut self, numer: u128, denom: u128) -> io::Result<()> {
+        let interval = JiRatio::from_fraction(numer, denom)
+            .and_then(|interval| interval.to_comma(format!("{numer}/{denom}")))
+            .unwrap();
+        let tempered_location = self.val.map(&interval).unwrap_or_default();
         let patent_location = interval
             .as_ratio()
             .num_equal_steps_of_size(self.val.step_size())
@@ -192,11 +485,51 @@ impl<'a, 'b> EstPrinter<'a, 'b> {
 
         self.app.writeln(format_args!(
             "- tempered vs. patent location of {}/{}: {} vs. {}",
-            fraction.0, fraction.1, tempered_location, patent_location
+            numer, denom, tempered_location, patent_location
         ))
     }
 
-    fn print_generalized_notes(&mut self, layout: &IsomorphicLayout) -> io::Result<()> {
+    fn print_consonance(
+        &mut self,
+        harmonic_entropy: &HarmonicEntropy,
+        odd_limit: u8,
+    ) -> io::Result<()> {
+        self.app.writeln("---- Consonance ----")?;
+        self.print_newline()?;
+
+        let strategy = ApproximationStrategy::OddLimit {
+            odd_limit: odd_limit.into(),
+        };
+
+        for num_steps in 0..=self.val.values()[0] {
+            let ratio = self.val.step_size().repeated(num_steps);
+            self.app.write(format_args!(
+                "{:>4}. {:>9.3}c | HE {:.3}",
+                num_steps,
+                ratio.as_cents(),
+                harmonic_entropy.of_ratio(ratio)
+            ))?;
+            match strategy.best_approximation(ratio) {
+                Some(approximation) => self.app.writeln(format_args!(
+                    " | {:>7} {:>+5.1}c | TH {:>5.2} | EG {:>3}",
+                    approximation.approx_value.to_string(),
+                    approximation.deviation.as_cents(),
+                    approximation.approx_value.tenney_height(),
+                    consonance::euler_gradus(approximation.approx_value)
+                        .map_or_else(|| "-".to_owned(), |gradus| gradus.to_string()),
+                ))?,
+                None => self.print_newline()?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn print_generalized_notes(
+        &mut self,
+        layout: &IsomorphicLayout,
+        notation: NoteNotation,
+    ) -> io::Result<()> {
         self.app
             .writeln(format_args!("==== {} notation ====", layout.genchain()))?;
         self.print_newline()?;
@@ -227,7 +560,7 @@ impl<'a, 'b> EstPrinter<'a, 'b> {
             self.app.writeln(format_args!(
                 "{:>4}. {}",
                 index,
-                layout.get_note_name(index)
+                layout.get_note_name_with(index, notation)
             ))?;
         }
         self.print_newline()?;
@@ -1,3 +1,4 @@
+mod analysis;
 mod dto;
 mod error;
 mod est;
@@ -8,6 +9,7 @@ mod mts;
 mod portable;
 mod scala;
 mod scale;
+mod temperament;
 
 use std::{
     fmt::{self, Display},
@@ -16,6 +18,7 @@ use std::{
     path::PathBuf,
 };
 
+use analysis::AnalyzeOptions;
 use clap::Parser;
 use error::ResultExt;
 use est::EstOptions;
@@ -25,7 +28,8 @@ use live::LiveOptions;
 use mos::MosCommand;
 use mts::MtsOptions;
 use scala::{KbmCommand, SclOptions};
-use scale::{DiffOptions, DumpOptions, ScaleCommand};
+use scale::{DiffOptions, DumpOptions, ScaleCommand, TunOptions};
+use temperament::TemperamentCommand;
 
 #[doc(hidden)]
 pub mod shared;
@@ -51,10 +55,22 @@ enum MainCommand {
     #[command(subcommand, name = "kbm")]
     Kbm(KbmCommand),
 
+    /// Create a tuning file in AnaMark tun format
+    #[command(name = "tun")]
+    Tun(TunOptions),
+
     /// Analyze equal-step tunings
     #[command(name = "est")]
     Est(EstOptions),
 
+    /// Analyze the structure of a scale, e.g. its step pattern, MOS properties and JI interpretation
+    #[command(name = "analyze")]
+    Analyze(AnalyzeOptions),
+
+    /// Analyze regular temperaments, e.g. their mapping, wedgie, complexity, badness and optimal tunings
+    #[command(subcommand, name = "temperament")]
+    Temperament(TemperamentCommand),
+
     /// Find MOS scales from generators or vice versa
     #[command(subcommand, name = "mos")]
     Mos(MosCommand),
@@ -108,7 +124,10 @@ impl MainCommand {
         match self {
             MainCommand::Scl(options) => options.run(app),
             MainCommand::Kbm(options) => options.run(app),
+            MainCommand::Tun(options) => options.run(app),
             MainCommand::Est(options) => options.run(app),
+            MainCommand::Analyze(options) => options.run(app),
+            MainCommand::Temperament(options) => options.run(app),
             MainCommand::Mos(options) => options.run(app),
             MainCommand::Scale(options) => options.run(app),
             MainCommand::Dump(options) => options.run(app),
@@ -1,13 +1,19 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 use flume::Sender;
 use tune::{
+    key::PianoKey,
     midi::{ChannelMessage, ChannelMessageType},
+    pitch::Pitch,
     tuner::{AotTuner, JitTuner, MidiTarget, MidiTunerMessageHandler, PoolingMode},
+    tuning::{KeyboardMapping, Morph},
 };
 
 use crate::{
     error::ResultExt,
     midi::{self, MidiInArgs, MidiOutArgs, MidiSource, MultiChannelOffset, TuningMethod},
+    scale::Scale,
     App, CliError, CliResult, ScaleCommand,
 };
 
@@ -56,6 +62,21 @@ struct JustInTimeOptions {
     #[arg(long = "clash", default_value = "stop", value_parser = parse_mitigation)]
     clash_mitigation: PoolingMode,
 
+    /// Scale file in YAML format to morph into.
+    /// Held notes follow the morph.
+    /// Not available in `aot` mode since its key-to-channel mapping is fixed ahead of time.
+    #[arg(long = "morph-to")]
+    morph_to: Option<PathBuf>,
+
+    /// MIDI controller number controlling the morph, e.g. 1 for the modulation wheel
+    #[arg(long = "morph-cc", default_value = "1")]
+    morph_cc: u8,
+
+    /// Morph each key into the target key with the nearest pitch instead of into the same key.
+    /// Useful when the scale sizes differ.
+    #[arg(long = "morph-by-pitch")]
+    morph_by_pitch: bool,
+
     /// MIDI-out tuning method
     #[arg(value_enum)]
     method: TuningMethod,
@@ -161,7 +182,19 @@ impl JustInTimeOptions {
         midi_out_args: MidiOutArgs,
         status_send: Sender<String>,
     ) -> CliResult<()> {
-        let tuning = self.scale.to_scale(app)?.tuning;
+        let scale = self.scale.to_scale(app)?;
+        let mut tuning = match &self.morph_to {
+            None => LiveTuning::Fixed(scale.tuning),
+            Some(morph_to) => {
+                let morph_target = Scale::from_scale_file(morph_to)?.tuning;
+                LiveTuning::Morph(if self.morph_by_pitch {
+                    Morph::nearest_pitch(scale.tuning, morph_target, scale.keys)
+                } else {
+                    Morph::per_key(scale.tuning, morph_target)
+                })
+            }
+        };
+        let morph_cc = self.morph_to.is_some().then_some(self.morph_cc);
 
         let synth = midi_out_args.create_synth(target, self.method);
         let mut tuner = JitTuner::start(synth, self.clash_mitigation);
@@ -188,6 +221,14 @@ impl JustInTimeOptions {
                     let piano_key = offset.get_piano_key(key);
                     tuner.note_attr(piano_key, pressure);
                 }
+                ChannelMessageType::ControlChange { controller, value }
+                    if Some(controller) == morph_cc =>
+                {
+                    if let LiveTuning::Morph(morph) = &mut tuning {
+                        morph.set_blend(f64::from(value) / 127.0);
+                    }
+                    tuner.retune(&tuning);
+                }
                 message_type @ (ChannelMessageType::ControlChange { .. }
                 | ChannelMessageType::ProgramChange { .. }
                 | ChannelMessageType::ChannelPressure { .. }
@@ -202,6 +243,22 @@ impl JustInTimeOptions {
     }
 }
 
+type BoxedMapping = Box<dyn KeyboardMapping<PianoKey> + Send>;
+
+enum LiveTuning {
+    Fixed(BoxedMapping),
+    Morph(Morph<PianoKey, BoxedMapping, BoxedMapping>),
+}
+
+impl KeyboardMapping<PianoKey> for LiveTuning {
+    fn maybe_pitch_of(&self, key: PianoKey) -> Option<Pitch> {
+        match self {
+            LiveTuning::Fixed(tuning) => tuning.maybe_pitch_of(key),
+            LiveTuning::Morph(morph) => morph.maybe_pitch_of(key),
+        }
+    }
+}
+
 impl AheadOfTimeOptions {
     fn run(
         &self,
@@ -153,6 +153,9 @@ impl MidiOutArgs {
                 self.device_id.device_id,
                 ScaleOctaveTuningFormat::TwoByte,
             ),
+            TuningMethod::Bulk => {
+                TunableMidi::bulk_tuning_dump(target, self.device_id.device_id, self.tuning_program)
+            }
             TuningMethod::ChannelFineTuning => TunableMidi::channel_fine_tuning(target),
             TuningMethod::PitchBend => TunableMidi::pitch_bend(target),
         }
@@ -214,6 +217,9 @@ pub enum TuningMethod {
     #[value(name = "octave-2-rt")]
     #[serde(rename = "octave-2-rt")]
     Octave2Rt,
+    #[value(name = "bulk")]
+    #[serde(rename = "bulk")]
+    Bulk,
     #[value(name = "fine-tuning")]
     #[serde(rename = "fine-tuning")]
     ChannelFineTuning,
@@ -1,7 +1,15 @@
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
 
 use clap::Parser;
-use tune::{math, pergen::Mos, pitch::Ratio};
+use tune::{
+    math,
+    pergen::{self, Mos},
+    pitch::Ratio,
+};
 
 use crate::{App, CliResult};
 
@@ -14,6 +22,10 @@ pub(crate) enum MosCommand {
     /// Find generators for a given MOS
     #[command(name = "gen")]
     FindGenerators(FindGeneratorsOptions),
+
+    /// List the modes of a given MOS ordered by brightness
+    #[command(name = "modes")]
+    ListModes(ListModesOptions),
 }
 
 impl MosCommand {
@@ -21,6 +33,7 @@ impl MosCommand {
         match self {
             MosCommand::FindMoses(options) => options.run(app),
             MosCommand::FindGenerators(options) => options.run(app),
+            MosCommand::ListModes(options) => options.run(app),
         }
     }
 }
@@ -143,6 +156,136 @@ impl FindGeneratorsOptions {
     }
 }
 
+#[derive(Parser)]
+pub(crate) struct ListModesOptions {
+    /// Period of the MOS
+    #[arg(long = "per", default_value = "2.0")]
+    period: Ratio,
+
+    /// Step ratio L:s, e.g. 2:1 (basic), 3:2 (soft) or 3:1 (hard)
+    #[arg(long = "ratio", default_value = "2:1")]
+    step_ratio: StepRatio,
+
+    /// Number of large steps
+    num_large_steps: u16,
+
+    /// Number of small steps
+    num_small_steps: u16,
+}
+
+impl ListModesOptions {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        if self.num_large_steps == 0 && self.num_small_steps == 0 {
+            return Err("MOS must contain at least one step".to_owned().into());
+        }
+
+        let mos = Mos::<u16>::new_collapsed(self.num_large_steps, self.num_small_steps);
+        let (large_step, small_step) = self
+            .step_ratio
+            .step_sizes(&mos, self.period)
+            .ok_or_else(|| format!("Step ratio {} yields an empty period", self.step_ratio))?;
+
+        app.write(format_args!(
+            "==== Modes of {}L{}s",
+            self.num_large_steps, self.num_small_steps
+        ))?;
+        if let Some(tamnams_name) = mos.tamnams_name() {
+            app.write(format_args!(" ({tamnams_name})"))?;
+        }
+        app.writeln(" ====")?;
+        app.writeln("")?;
+        app.writeln(format_args!(
+            "- step ratio: {} ({})",
+            self.step_ratio,
+            self.step_ratio.tamnams_name()
+        ))?;
+        app.writeln(format_args!(
+            "- L = {:.3}c, s = {:.3}c",
+            large_step.as_cents(),
+            small_step.as_cents()
+        ))?;
+        app.writeln("")?;
+
+        for (index, mode) in mos.modes().iter().enumerate() {
+            app.write(format_args!(
+                "{:>3}. {:<8} {:<12}",
+                index + 1,
+                mode.udp(),
+                mode.pattern
+            ))?;
+            let mut pitch = Ratio::default();
+            for step in mode.pattern.chars() {
+                pitch = pitch.stretched_by(match step {
+                    'L' => large_step,
+                    _ => small_step,
+                });
+                app.write(format_args!(" {:>9.3}", pitch.as_cents()))?;
+            }
+            app.writeln("")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Step ratio L:s of a MOS, e.g. 3:2.
+#[derive(Copy, Clone, Debug)]
+pub struct StepRatio {
+    pub large_step: f64,
+    pub small_step: f64,
+}
+
+impl StepRatio {
+    pub fn tamnams_name(&self) -> &'static str {
+        pergen::tamnams_step_ratio_name(self.large_step, self.small_step)
+    }
+
+    fn step_sizes(&self, mos: &Mos, period: Ratio) -> Option<(Ratio, Ratio)> {
+        let (num_large_steps, num_small_steps) = mos.num_large_and_small_steps();
+        let total_size = f64::from(num_large_steps) * self.large_step
+            + f64::from(num_small_steps) * self.small_step;
+        (total_size > 0.0).then(|| {
+            (
+                period.repeated(self.large_step / total_size),
+                period.repeated(self.small_step / total_size),
+            )
+        })
+    }
+}
+
+impl FromStr for StepRatio {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (large_step, small_step) = s.split_once(':').unwrap_or((s, "1"));
+
+        let parse = |step: &str| {
+            step.trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|step| step.is_finite() && *step >= 0.0)
+        };
+
+        match (parse(large_step), parse(small_step)) {
+            (Some(large_step), Some(small_step)) if large_step >= small_step && large_step > 0.0 => {
+                Ok(Self {
+                    large_step,
+                    small_step,
+                })
+            }
+            _ => Err(format!(
+                "Invalid step ratio '{s}': Must be of the form L:s with L >= s >= 0 (e.g. 2:1 or 3:2)"
+            )),
+        }
+    }
+}
+
+impl Display for StepRatio {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.large_step, self.small_step)
+    }
+}
+
 fn ls_pattern(generator: u16, num_large_steps: u16, num_small_steps: u16) -> String {
     let num_steps = u32::from(num_large_steps) + u32::from(num_small_steps);
     let num_periods = u32::from(math::gcd_u16(num_large_steps, num_small_steps));
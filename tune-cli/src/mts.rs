@@ -1,5 +1,5 @@
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::Write,
     path::PathBuf,
 };
@@ -8,10 +8,14 @@ use clap::Parser;
 use midir::MidiOutputConnection;
 use tune::{
     mts::{
-        ScaleOctaveTuningFormat, ScaleOctaveTuningOptions, SingleNoteTuningChangeMessage,
-        SingleNoteTuningChangeOptions,
+        self, BulkTuningDumpMessage, BulkTuningDumpOptions, Channels, MtsMessage, MtsPayload,
+        ScaleOctaveTuningFormat, ScaleOctaveTuningOptions, SingleNoteTuningChange,
+        SingleNoteTuningChangeMessage, SingleNoteTuningChangeOptions,
     },
+    note::NoteLetter,
+    pitch::Pitched,
     tuner::AotTuningModel,
+    tuning::KeyboardMapping,
 };
 
 use crate::{
@@ -64,6 +68,14 @@ enum MtsCommand {
     #[command(name = "octave-2-rt")]
     Octave2Rt(OctaveOptions),
 
+    /// Retune a MIDI device (Bulk Tuning Dump)
+    #[command(name = "bulk")]
+    Bulk(BulkOptions),
+
+    /// Retune a MIDI device (Key-Based Tuning Dump with bank select)
+    #[command(name = "key-dump")]
+    KeyDump(KeyDumpOptions),
+
     /// Select a tuning program
     #[command(name = "tun-pg")]
     TuningProgram(TuningProgramOptions),
@@ -71,6 +83,10 @@ enum MtsCommand {
     /// Select a tuning bank
     #[command(name = "tun-bk")]
     TuningBank(TuningBankOptions),
+
+    /// Decode MTS SysEx messages and print the resulting tuning table
+    #[command(name = "decode")]
+    Decode(DecodeOptions),
 }
 
 #[derive(Parser)]
@@ -103,6 +119,44 @@ struct OctaveOptions {
     scale: ScaleCommand,
 }
 
+#[derive(Parser)]
+struct BulkOptions {
+    #[command(flatten)]
+    device_id: DeviceIdArg,
+
+    /// Tuning program that should be affected
+    #[arg(long = "tun-pg", default_value = "0")]
+    tuning_program: u8,
+
+    /// Name of the tuning (up to 16 ASCII characters)
+    #[arg(long = "name", default_value = "")]
+    name: String,
+
+    #[command(subcommand)]
+    scale: ScaleCommand,
+}
+
+#[derive(Parser)]
+struct KeyDumpOptions {
+    #[command(flatten)]
+    device_id: DeviceIdArg,
+
+    /// Tuning bank that should be affected
+    #[arg(long = "tun-bk", default_value = "0")]
+    tuning_bank: u8,
+
+    /// Tuning program that should be affected
+    #[arg(long = "tun-pg", default_value = "0")]
+    tuning_program: u8,
+
+    /// Name of the tuning (up to 16 ASCII characters)
+    #[arg(long = "name", default_value = "")]
+    name: String,
+
+    #[command(subcommand)]
+    scale: ScaleCommand,
+}
+
 #[derive(Parser)]
 struct TuningProgramOptions {
     /// MIDI channel to apply the tuning program change to
@@ -123,6 +177,13 @@ struct TuningBankOptions {
     tuning_bank: u8,
 }
 
+#[derive(Parser)]
+struct DecodeOptions {
+    /// Binary SysEx file (e.g. written by `tune mts --bin`) or text file with one hex byte per line (e.g. the output of `tune mts`).
+    /// If omitted, the data is read from stdin.
+    sysex_file: Option<PathBuf>,
+}
+
 impl MtsOptions {
     pub fn run(&self, app: &mut App) -> CliResult {
         let mut outputs = Outputs {
@@ -156,8 +217,11 @@ impl MtsOptions {
             MtsCommand::Octave2Rt(options) => {
                 options.run(app, &mut outputs, true, ScaleOctaveTuningFormat::TwoByte)
             }
+            MtsCommand::Bulk(options) => options.run(app, &mut outputs),
+            MtsCommand::KeyDump(options) => options.run(app, &mut outputs),
             MtsCommand::TuningProgram(options) => options.run(app, &mut outputs),
             MtsCommand::TuningBank(options) => options.run(app, &mut outputs),
+            MtsCommand::Decode(options) => options.run(app),
         }
     }
 }
@@ -241,6 +305,66 @@ impl OctaveOptions {
     }
 }
 
+impl BulkOptions {
+    fn run(&self, app: &mut App, outputs: &mut Outputs) -> CliResult {
+        let options = BulkTuningDumpOptions {
+            device_id: self.device_id.device_id,
+            tuning_program: self.tuning_program,
+            with_bank_select: None,
+            name: self.name.clone(),
+        };
+
+        write_bulk_tuning_dump(app, outputs, &self.scale, &options)
+    }
+}
+
+impl KeyDumpOptions {
+    fn run(&self, app: &mut App, outputs: &mut Outputs) -> CliResult {
+        let options = BulkTuningDumpOptions {
+            device_id: self.device_id.device_id,
+            tuning_program: self.tuning_program,
+            with_bank_select: Some(self.tuning_bank),
+            name: self.name.clone(),
+        };
+
+        write_bulk_tuning_dump(app, outputs, &self.scale, &options)
+    }
+}
+
+fn write_bulk_tuning_dump(
+    app: &mut App,
+    outputs: &mut Outputs,
+    scale: &ScaleCommand,
+    options: &BulkTuningDumpOptions,
+) -> CliResult {
+    let scale = scale.to_scale(app)?;
+
+    let tuning_message = BulkTuningDumpMessage::from_tuning_changes(
+        options,
+        scale.keys.iter().flat_map(|&key| {
+            scale
+                .tuning
+                .maybe_pitch_of(key)
+                .map(|target_pitch| SingleNoteTuningChange { key, target_pitch })
+        }),
+    )
+    .handle_error::<CliError>("Could not create bulk tuning dump")?;
+
+    app.errln(format_args!("== SysEx start =="))?;
+    outputs.write_midi_message(app, tuning_message.sysex_bytes())?;
+    app.errln(format_args!("== SysEx end =="))?;
+    app.errln(format_args!(
+        "Number of retuned notes: {}",
+        scale.keys.len() - tuning_message.out_of_range_notes().len(),
+    ))?;
+    app.errln(format_args!(
+        "Number of out-of-range notes: {}",
+        tuning_message.out_of_range_notes().len()
+    ))?;
+
+    Ok(())
+}
+
 impl TuningProgramOptions {
     fn run(&self, app: &mut App, outputs: &mut Outputs) -> CliResult {
         for (enumeration, message) in
@@ -275,6 +399,170 @@ impl TuningBankOptions {
     }
 }
 
+impl DecodeOptions {
+    fn run(&self, app: &mut App) -> CliResult {
+        let data = match &self.sysex_file {
+            Some(sysex_file) => {
+                fs::read(sysex_file).handle_error::<CliError>("Could not read SysEx file")?
+            }
+            None => {
+                let mut data = Vec::new();
+                app.read().read_to_end(&mut data)?;
+                data
+            }
+        };
+        let sysex_bytes = parse_sysex_data(&data)?;
+
+        for (index, message) in mts::split_sysex_messages(&sysex_bytes).enumerate() {
+            let message = MtsMessage::decode(message)
+                .handle_error::<CliError>(&format!("Could not decode SysEx message {index}"))?;
+            print_mts_message(app, &message)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_sysex_data(data: &[u8]) -> CliResult<Vec<u8>> {
+    if data.first() == Some(&0xf0) {
+        return Ok(data.to_vec());
+    }
+
+    String::from_utf8_lossy(data)
+        .split_whitespace()
+        .map(|token| {
+            let hex = token.strip_prefix("0x").unwrap_or(token);
+            u8::from_str_radix(hex, 16)
+                .map_err(|_| CliError::from(format!("Invalid hex byte '{token}'")))
+        })
+        .collect()
+}
+
+fn print_mts_message(app: &mut App, message: &MtsMessage) -> CliResult {
+    let realtime = if message.realtime {
+        "real-time"
+    } else {
+        "non-real-time"
+    };
+
+    match &message.payload {
+        MtsPayload::SingleNoteTuningChange {
+            tuning_bank,
+            tuning_program,
+            tuning_changes,
+        } => {
+            app.writeln(format_args!("== Single Note Tuning Change ({realtime}) =="))?;
+            app.writeln(format_args!("Device ID: {}", message.device_id))?;
+            if let Some(tuning_bank) = tuning_bank {
+                app.writeln(format_args!("Tuning bank: {tuning_bank}"))?;
+            }
+            app.writeln(format_args!("Tuning program: {tuning_program}"))?;
+            print_tuning_changes(app, tuning_changes)?;
+        }
+        MtsPayload::ScaleOctaveTuning {
+            format,
+            channels,
+            octave_tuning,
+        } => {
+            let format = match format {
+                ScaleOctaveTuningFormat::OneByte => "1-byte format",
+                ScaleOctaveTuningFormat::TwoByte => "2-byte format",
+            };
+            app.writeln(format_args!(
+                "== Scale/Octave Tuning ({format}, {realtime}) =="
+            ))?;
+            app.writeln(format_args!("Device ID: {}", message.device_id))?;
+            match channels {
+                Channels::All => app.writeln("Channels: all")?,
+                Channels::Some(channels) => {
+                    let mut channels = Vec::from_iter(channels);
+                    channels.sort();
+                    app.writeln(format_args!(
+                        "Channels: {}",
+                        channels
+                            .iter()
+                            .map(|channel| channel.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))?
+                }
+            }
+
+            for (letter, detuning) in [
+                (NoteLetter::C, octave_tuning.c),
+                (NoteLetter::Csh, octave_tuning.csh),
+                (NoteLetter::D, octave_tuning.d),
+                (NoteLetter::Dsh, octave_tuning.dsh),
+                (NoteLetter::E, octave_tuning.e),
+                (NoteLetter::F, octave_tuning.f),
+                (NoteLetter::Fsh, octave_tuning.fsh),
+                (NoteLetter::G, octave_tuning.g),
+                (NoteLetter::Gsh, octave_tuning.gsh),
+                (NoteLetter::A, octave_tuning.a),
+                (NoteLetter::Ash, octave_tuning.ash),
+                (NoteLetter::B, octave_tuning.b),
+            ] {
+                app.writeln(format_args!(
+                    "{:>6} | {:>+8.3}¢",
+                    letter,
+                    detuning.as_cents()
+                ))?;
+            }
+        }
+        MtsPayload::BulkDumpRequest {
+            tuning_bank,
+            tuning_program,
+        } => {
+            app.writeln(format_args!("== Bulk Dump Request ({realtime}) =="))?;
+            app.writeln(format_args!("Device ID: {}", message.device_id))?;
+            if let Some(tuning_bank) = tuning_bank {
+                app.writeln(format_args!("Tuning bank: {tuning_bank}"))?;
+            }
+            app.writeln(format_args!("Tuning program: {tuning_program}"))?;
+        }
+        MtsPayload::BulkTuningDump {
+            tuning_bank,
+            tuning_program,
+            name,
+            tuning_changes,
+        } => {
+            let message_type = match tuning_bank {
+                None => "Bulk Tuning Dump",
+                Some(_) => "Key-Based Tuning Dump",
+            };
+            app.writeln(format_args!("== {message_type} ({realtime}) =="))?;
+            app.writeln(format_args!("Device ID: {}", message.device_id))?;
+            if let Some(tuning_bank) = tuning_bank {
+                app.writeln(format_args!("Tuning bank: {tuning_bank}"))?;
+            }
+            app.writeln(format_args!("Tuning program: {tuning_program}"))?;
+            app.writeln(format_args!("Tuning name: {name}"))?;
+            print_tuning_changes(app, tuning_changes)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn print_tuning_changes(app: &mut App, tuning_changes: &[SingleNoteTuningChange]) -> CliResult {
+    app.writeln(format_args!("Number of changes: {}", tuning_changes.len()))?;
+
+    for tuning_change in tuning_changes {
+        let approximation = tuning_change.target_pitch.find_in_tuning(());
+        let (letter, octave) = approximation.approx_value.letter_and_octave();
+        app.writeln(format_args!(
+            "{:>3} | {:>10.3} Hz | {:>6} {:>2} | {:>+8.3}¢",
+            tuning_change.key.midi_number(),
+            tuning_change.target_pitch.as_hz(),
+            letter,
+            octave.octave_number(),
+            approximation.deviation.as_cents(),
+        ))?;
+    }
+
+    Ok(())
+}
+
 struct Outputs {
     open_file: Option<File>,
     midi_out: Option<(String, MidiOutputConnection)>,
@@ -1,16 +1,26 @@
 use std::{
     fs::File,
     path::{Path, PathBuf},
+    sync::OnceLock,
 };
 
 use clap::Parser;
 use tune::{
+    ji::JiRatio,
     key::PianoKey,
+    layout::IsomorphicLayout,
+    math,
+    note::Note,
+    pergen::Mos,
     pitch::{Ratio, RatioExpression, RatioExpressionVariant},
-    scala::{self, Kbm, KbmImportError, KbmRoot, Scl, SclBuildError, SclImportError, SegmentType},
+    scala::{
+        self, AblReferencePitch, Kbm, KbmImportError, KbmRoot, NamedScale, PitchValue,
+        ScaleCatalog, Scl, SclBuildError, SclImportError, SegmentType,
+    },
+    temperament::{self, Comma, CommaCatalog},
 };
 
-use crate::{error::ResultExt, App, CliError, CliResult};
+use crate::{error::ResultExt, mos::StepRatio, App, CliError, CliResult};
 
 #[derive(Parser)]
 pub(crate) struct SclOptions {
@@ -18,18 +28,80 @@ pub(crate) struct SclOptions {
     #[arg(long = "name")]
     name: Option<String>,
 
+    /// Add Ableton metadata (ascl format). Note names are taken from the isomorphic layout of equal-step scales
+    #[arg(long = "ascl")]
+    ascl: bool,
+
+    /// Reference note of the scale's root for the ascl format, e.g. 62, D4 or A4@432Hz
+    #[arg(long = "ascl-ref", requires = "ascl")]
+    ascl_ref: Option<KbmRoot>,
+
+    /// Source information for the ascl format
+    #[arg(long = "ascl-source", requires = "ascl")]
+    ascl_source: Option<String>,
+
     #[command(subcommand)]
     scl: SclCommand,
 }
 
 impl SclOptions {
     pub fn run(self, app: &mut App) -> CliResult {
-        Ok(app.write(format_args!("{}", self.scl.to_scl(self.name)?.export()))?)
+        let mut scl = self.scl.to_scl(self.name)?;
+        if self.ascl {
+            let mut abl_metadata = scl.abl_metadata().clone();
+            if let Some(note_names) = note_names_of_equal_step_scale(&scl) {
+                abl_metadata.note_names = Some(note_names);
+            }
+            if let Some(ascl_ref) = self.ascl_ref {
+                let root_key = ascl_ref.ref_key.plus_steps(ascl_ref.root_offset);
+                abl_metadata.reference_pitch = Some(AblReferencePitch {
+                    octave: root_key.midi_number().div_euclid(12) - 2,
+                    degree: 0,
+                    pitch: ascl_ref.ref_pitch,
+                });
+            }
+            if let Some(ascl_source) = self.ascl_source {
+                abl_metadata.source = Some(ascl_source);
+            }
+            scl.set_abl_metadata(abl_metadata);
+        }
+        Ok(app.write(format_args!("{}", scl.export()))?)
     }
 }
 
+fn note_names_of_equal_step_scale(scl: &Scl) -> Option<Vec<String>> {
+    let step_size = scl.relative_pitch_of(1);
+    let is_equal_step = (0..=i32::from(scl.num_items())).all(|degree| {
+        scl.relative_pitch_of(degree)
+            .deviation_from(step_size.repeated(degree))
+            .is_negligible()
+    });
+    if !is_equal_step {
+        return None;
+    }
+
+    let layout = IsomorphicLayout::find_by_step_size(step_size)
+        .into_iter()
+        .next()?;
+    Some(
+        (0..scl.num_items())
+            .map(|degree| layout.get_note_name(degree))
+            .collect(),
+    )
+}
+
 #[derive(Parser)]
 pub enum SclCommand {
+    #[command(flatten)]
+    Source(SclSourceCommand),
+
+    /// Transform a scale, e.g. rotate it to a mode or invert it
+    #[command(name = "transform")]
+    Transform(TransformOptions),
+}
+
+#[derive(Parser)]
+pub enum SclSourceCommand {
     /// Scale with custom step sizes
     #[command(name = "steps")]
     Steps {
@@ -56,6 +128,28 @@ pub enum SclCommand {
         period: Ratio,
     },
 
+    /// Mode of a MOS scale
+    #[command(name = "mos")]
+    MosMode {
+        /// Number of large steps, e.g. 5
+        num_large_steps: u16,
+
+        /// Number of small steps, e.g. 2
+        num_small_steps: u16,
+
+        /// Mode in order of decreasing brightness as listed by `tune mos modes`, e.g. 2 for the Ionian mode of 5L2s
+        #[arg(long = "mode", default_value = "1")]
+        mode: usize,
+
+        /// Step ratio L:s, e.g. 2:1 (basic), 3:2 (soft) or 3:1 (hard)
+        #[arg(long = "ratio", default_value = "2:1")]
+        step_ratio: StepRatio,
+
+        /// Period of the MOS
+        #[arg(long = "per", default_value = "2")]
+        period: Ratio,
+    },
+
     /// Harmonic series
     #[command(name = "harm")]
     HarmonicSeries {
@@ -74,6 +168,78 @@ pub enum SclCommand {
         neji_divisions: Option<u16>,
     },
 
+    /// Combination product set, e.g. the 1.3.5.7 hexany
+    #[command(name = "cps")]
+    CombinationProductSet {
+        /// Number of factors per product, e.g. 2
+        num_chosen: u16,
+
+        /// Factors to combine, e.g. 1.3.5.7
+        #[arg(value_delimiter = '.', value_parser = parse_ji_ratio, required = true)]
+        factors: Vec<JiRatio>,
+    },
+
+    /// Tonality diamond
+    #[command(name = "diamond")]
+    TonalityDiamond {
+        /// Odd limit of the diamond, e.g. 11
+        #[arg(required_unless_present = "identities")]
+        odd_limit: Option<u16>,
+
+        /// Use the given identities instead of all odd numbers up to the odd limit, e.g. 1.5.7.9
+        #[arg(
+            long = "ids",
+            conflicts_with = "odd_limit",
+            value_delimiter = '.',
+            value_parser = parse_ji_ratio
+        )]
+        identities: Option<Vec<JiRatio>>,
+    },
+
+    /// Euler-Fokker genus
+    #[command(name = "euler-genus")]
+    EulerGenus {
+        /// Factors of the genus, e.g. 3.3.5
+        #[arg(value_delimiter = '.', value_parser = parse_ji_ratio, required = true)]
+        factors: Vec<JiRatio>,
+    },
+
+    /// Cross-set scale
+    #[command(name = "cross")]
+    CrossSet {
+        /// First set, e.g. 1.5/4.3/2
+        #[arg(num_args = 1, required = true, value_delimiter = '.', value_parser = parse_ji_ratio)]
+        first_set: Vec<JiRatio>,
+
+        /// Second set, e.g. 1.6/5.3/2
+        #[arg(num_args = 1, required = true, value_delimiter = '.', value_parser = parse_ji_ratio)]
+        second_set: Vec<JiRatio>,
+    },
+
+    /// Fokker periodicity block
+    #[command(name = "fokker")]
+    FokkerBlock {
+        /// Comma (unison vector) spanning the block, e.g. "syntonic comma", 81/80 or "[-4 4 -1>". Can be repeated.
+        #[arg(long = "comma", required = true, value_parser = parse_comma)]
+        commas: Vec<Comma>,
+
+        /// Prime basis where the first prime is the equivalence interval, e.g. 2.3.5. Defaults to 2 and all other primes of the commas
+        #[arg(long = "basis", value_delimiter = '.')]
+        basis: Option<Vec<u8>>,
+
+        /// Position of the block along each comma, between -1 (exclusive) and 0 (inclusive), e.g. -0.5,-0.5
+        #[arg(long = "shift", value_delimiter = ',', allow_hyphen_values = true)]
+        shift: Vec<f64>,
+    },
+
+    /// Historical temperament or other scale from the built-in catalog
+    #[command(name = "named")]
+    Named {
+        /// Name of the scale, e.g. "Werckmeister III" or "1/4-comma meantone"
+        #[arg(value_parser = parse_named_scale)]
+        scale: NamedScale,
+    },
+
     /// Import scl file
     #[command(name = "scl-file")]
     UseSclFile {
@@ -85,9 +251,160 @@ pub enum SclCommand {
 impl SclCommand {
     pub fn to_scl(&self, description: Option<String>) -> Result<Scl, CliError> {
         match self {
-            SclCommand::Steps { items } => create_custom_scale(description, items)
+            SclCommand::Source(source) => source.to_scl(description),
+            SclCommand::Transform(options) => {
+                let mut scl = options.to_scl()?;
+                if let Some(description) = description {
+                    scl.set_description(description)
+                }
+                Ok(scl)
+            }
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct TransformOptions {
+    /// Transformation to apply. Can be repeated, transformations are applied in the given order.
+    /// Supported: mode:<degree>, invert, stretch:<period>, transpose:<ratio>, subset:<degree>,<degree>,..., merge:<scl file>, sort
+    #[arg(long = "op", required = true, value_parser = parse_transformation)]
+    transformations: Vec<Transformation>,
+
+    #[command(subcommand)]
+    scl: SclSourceCommand,
+}
+
+#[derive(Clone)]
+enum Transformation {
+    Mode(i32),
+    Invert,
+    Stretch(Ratio),
+    Transpose(RatioExpression),
+    Subset(Vec<i32>),
+    Merge(PathBuf),
+    Sort,
+}
+
+fn parse_transformation(s: &str) -> Result<Transformation, String> {
+    let (name, arg) = s.split_once(':').unwrap_or((s, ""));
+    match name {
+        "mode" => arg
+            .parse()
+            .map(Transformation::Mode)
+            .map_err(|_| "Invalid degree, e.g. mode:2".to_owned()),
+        "invert" => Ok(Transformation::Invert),
+        "stretch" => arg
+            .parse()
+            .map(Transformation::Stretch)
+            .map_err(|err| format!("Invalid period, e.g. stretch:1210c: {err}")),
+        "transpose" => arg
+            .parse()
+            .map(Transformation::Transpose)
+            .map_err(|err| format!("Invalid ratio, e.g. transpose:81/80: {err}")),
+        "subset" => arg
+            .split(',')
+            .map(|degree| degree.trim().parse())
+            .collect::<Result<_, _>>()
+            .map(Transformation::Subset)
+            .map_err(|_| "Invalid degrees, e.g. subset:0,2,4".to_owned()),
+        "merge" if !arg.is_empty() => Ok(Transformation::Merge(PathBuf::from(arg))),
+        "sort" => Ok(Transformation::Sort),
+        _ => Err(
+            "Should be mode:<degree>, invert, stretch:<period>, transpose:<ratio>, \
+             subset:<degree>,<degree>,..., merge:<scl file> or sort"
+                .to_owned(),
+        ),
+    }
+}
+
+fn parse_ji_ratio(s: &str) -> Result<JiRatio, String> {
+    let (numer, denom) = s.split_once('/').unwrap_or((s, "1"));
+    numer
+        .trim()
+        .parse()
+        .ok()
+        .zip(denom.trim().parse().ok())
+        .and_then(|(numer, denom)| JiRatio::from_fraction(numer, denom))
+        .ok_or_else(|| "Invalid factor, e.g. 7 or 5/4".to_owned())
+}
+
+fn parse_named_scale(s: &str) -> Result<NamedScale, String> {
+    static CATALOG: OnceLock<ScaleCatalog> = OnceLock::new();
+
+    let catalog = CATALOG.get_or_init(|| ScaleCatalog::new(scala::historical_temperaments()));
+    if let Some(scale) = catalog.scale_for_name(s) {
+        return Ok(scale.clone());
+    }
+
+    let candidates = Vec::from_iter(catalog.search(s).map(NamedScale::name));
+    let candidates = match candidates.is_empty() {
+        true => Vec::from_iter(catalog.scales().iter().map(NamedScale::name)),
+        false => candidates,
+    };
+    Err(format!(
+        "Unknown scale. Candidates: {}",
+        candidates.join(", ")
+    ))
+}
+
+pub(crate) fn parse_comma(s: &str) -> Result<Comma, String> {
+    if let Some(monzo) = s.trim().strip_prefix('[') {
+        return monzo
+            .trim_end_matches('>')
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|prime_factors| Comma::new(s.to_owned(), prime_factors))
+            .map_err(|_| "Invalid monzo, e.g. [-4 4 -1>".to_owned());
+    }
+    if let Some(comma) = parse_ji_ratio(s)
+        .ok()
+        .and_then(|ji_ratio| ji_ratio.to_comma(s))
+    {
+        return Ok(comma);
+    }
+    CommaCatalog::new(temperament::huygens_fokker_intervals())
+        .comma_for_name(s)
+        .cloned()
+        .ok_or_else(|| {
+            "Should be a comma name (e.g. syntonic comma), fraction (e.g. 81/80) or monzo (e.g. [-4 4 -1>)"
+                .to_owned()
+        })
+}
+
+impl TransformOptions {
+    fn to_scl(&self) -> Result<Scl, CliError> {
+        let mut scl = self.scl.to_scl(None)?;
+        for transformation in &self.transformations {
+            scl = match transformation {
+                &Transformation::Mode(degree) => scl.mode(degree),
+                Transformation::Invert => scl.inverted(),
+                &Transformation::Stretch(period) => scl
+                    .stretched(period)
+                    .ok_or_else(|| "Cannot stretch a scale with a period of 1/1".to_owned())?,
+                Transformation::Transpose(interval) => {
+                    scl.transposed(as_pitch_value(interval))
+                        .handle_error::<CliError>("Could not transpose scale")?
+                }
+                Transformation::Subset(degrees) => scl
+                    .subset(degrees)
+                    .handle_error::<CliError>("Could not create subset")?,
+                Transformation::Merge(scl_file_location) => scl
+                    .merged(&import_scl_file(scl_file_location)?)
+                    .handle_error::<CliError>("Could not merge scales")?,
+                Transformation::Sort => scl.sorted(),
+            }
+        }
+        Ok(scl)
+    }
+}
+
+impl SclSourceCommand {
+    pub fn to_scl(&self, description: Option<String>) -> Result<Scl, CliError> {
+        match self {
+            SclSourceCommand::Steps { items } => create_custom_scale(description, items)
                 .handle_error("Could not create steps-based scale"),
-            &SclCommand::Rank2Temperament {
+            &SclSourceCommand::Rank2Temperament {
                 generator,
                 num_pos_generations,
                 num_neg_generations,
@@ -100,7 +417,37 @@ impl SclCommand {
                 period,
             )
             .handle_error("Could not create rank2 scale"),
-            &SclCommand::HarmonicSeries {
+            &SclSourceCommand::MosMode {
+                num_large_steps,
+                num_small_steps,
+                mode,
+                step_ratio,
+                period,
+            } => {
+                if num_large_steps == 0 && num_small_steps == 0 {
+                    return Err("MOS must contain at least one step".to_owned().into());
+                }
+
+                let modes = Mos::<u16>::new_collapsed(num_large_steps, num_small_steps).modes();
+                let mode = mode
+                    .checked_sub(1)
+                    .and_then(|index| modes.get(index))
+                    .ok_or_else(|| {
+                        CliError::CommandError(format!(
+                            "Mode must be between 1 and {}",
+                            modes.len()
+                        ))
+                    })?;
+                scala::create_mos_mode_scale(
+                    description,
+                    mode,
+                    step_ratio.large_step,
+                    step_ratio.small_step,
+                    period,
+                )
+                .handle_error("Could not create MOS scale")
+            }
+            &SclSourceCommand::HarmonicSeries {
                 utonal,
                 segment_start,
                 segment_size,
@@ -120,7 +467,73 @@ impl SclCommand {
                 )
                 .handle_error("Could not create harmonic scale")
             }
-            SclCommand::UseSclFile { scl_file_location } => {
+            &SclSourceCommand::CombinationProductSet {
+                num_chosen,
+                ref factors,
+            } => scala::create_cps_scale(description, factors, num_chosen)
+                .handle_error("Could not create combination product set"),
+            &SclSourceCommand::TonalityDiamond {
+                odd_limit,
+                ref identities,
+            } => {
+                let identities = match identities {
+                    Some(identities) => identities.clone(),
+                    None => (1..=odd_limit.unwrap_or(1))
+                        .step_by(2)
+                        .map(|identity| JiRatio::from_int(identity.into()))
+                        .collect::<Option<_>>()
+                        .ok_or_else(|| {
+                            "Odd limit must not exceed 255 (257 is above the supported prime limit)"
+                                .to_owned()
+                        })?,
+                };
+                scala::create_diamond_scale(description, &identities)
+                    .handle_error("Could not create tonality diamond")
+            }
+            SclSourceCommand::EulerGenus { factors } => {
+                scala::create_euler_genus_scale(description, factors)
+                    .handle_error("Could not create Euler-Fokker genus")
+            }
+            SclSourceCommand::CrossSet {
+                first_set,
+                second_set,
+            } => scala::create_cross_set_scale(description, first_set, second_set)
+                .handle_error("Could not create cross-set scale"),
+            SclSourceCommand::FokkerBlock {
+                commas,
+                basis,
+                shift,
+            } => {
+                let basis = basis.clone().unwrap_or_else(|| {
+                    let is_used = |index| {
+                        commas.iter().any(|comma| {
+                            comma
+                                .prime_factors()
+                                .get(index)
+                                .is_some_and(|&power| power != 0)
+                        })
+                    };
+                    Vec::from_iter(
+                        math::U8_PRIMES
+                            .iter()
+                            .enumerate()
+                            .filter(|&(index, _)| index == 0 || is_used(index))
+                            .map(|(_, &prime)| prime),
+                    )
+                });
+                scala::create_fokker_block_scale(description, commas, &basis, shift)
+                    .handle_error("Could not create Fokker block")
+            }
+            SclSourceCommand::Named { scale } => {
+                let mut scl = scale
+                    .to_scl()
+                    .handle_error::<CliError>("Could not create named scale")?;
+                if let Some(description) = description {
+                    scl.set_description(description)
+                }
+                Ok(scl)
+            }
+            SclSourceCommand::UseSclFile { scl_file_location } => {
                 let mut scale = import_scl_file(scl_file_location)?;
                 if let Some(description) = description {
                     scale.set_description(description)
@@ -137,22 +550,7 @@ fn create_custom_scale(
 ) -> Result<Scl, SclBuildError> {
     let mut builder = Scl::builder();
     for item in items {
-        match item.variant() {
-            RatioExpressionVariant::Float { float_value } => {
-                if let Some(float_value) = as_int(float_value) {
-                    builder = builder.push_int(float_value);
-                    continue;
-                }
-            }
-            RatioExpressionVariant::Fraction { numer, denom } => {
-                if let (Some(numer), Some(denom)) = (as_int(numer), as_int(denom)) {
-                    builder = builder.push_fraction(numer, denom);
-                    continue;
-                }
-            }
-            _ => {}
-        }
-        builder = builder.push_ratio(item.ratio());
+        builder = builder.push_pitch_value(as_pitch_value(item));
     }
 
     match description.into() {
@@ -161,6 +559,23 @@ fn create_custom_scale(
     }
 }
 
+fn as_pitch_value(ratio: &RatioExpression) -> PitchValue {
+    match ratio.variant() {
+        RatioExpressionVariant::Float { float_value } => {
+            if let Some(float_value) = as_int(float_value) {
+                return PitchValue::Fraction(float_value, None);
+            }
+        }
+        RatioExpressionVariant::Fraction { numer, denom } => {
+            if let (Some(numer), Some(denom)) = (as_int(numer), as_int(denom)) {
+                return PitchValue::Fraction(numer, Some(denom));
+            }
+        }
+        _ => {}
+    }
+    PitchValue::Cents(ratio.ratio().as_cents())
+}
+
 fn as_int(float: f64) -> Option<u32> {
     let rounded = float.round();
     if (float - rounded).abs() < 1e-6 {
@@ -254,19 +669,20 @@ impl KbmOptions {
 
 #[derive(Parser)]
 pub struct KbmRootOptions {
-    /// Reference note that should sound at its original or a custom pitch, e.g. 69@440Hz
+    /// Reference note that should sound at its original or a custom pitch, e.g. 69@440Hz, A4@432Hz or Eb3+14.2c
+    #[arg(allow_hyphen_values = true)]
     ref_note: KbmRoot,
 
-    /// root note / "middle note" of the scale if different from reference note
+    /// root note / "middle note" of the scale if different from reference note, e.g. 60 or C4
     #[arg(long = "root")]
-    root_note: Option<i16>,
+    root_note: Option<Note>,
 }
 
 impl KbmRootOptions {
     pub fn to_kbm_root(&self) -> KbmRoot {
         match self.root_note {
             Some(root_note) => KbmRoot {
-                root_offset: i32::from(root_note) - self.ref_note.ref_key.midi_number(),
+                root_offset: root_note.midi_number() - self.ref_note.ref_key.midi_number(),
                 ..self.ref_note
             },
             None => self.ref_note,
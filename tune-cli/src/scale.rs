@@ -4,12 +4,15 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use tune::{
+    consonance::{self, HarmonicEntropy},
+    ji::{ApproximationRanking, ApproximationStrategy, Fraction},
     key::PianoKey,
     pitch::{Pitch, Pitched, Ratio},
     scala::{KbmRoot, Scl},
-    tuning::{KeyboardMapping, Tuning},
+    tun::{Tun, TunImportError},
+    tuning::{Approximation, KeyboardMapping, Railsback, Stretched, Tuning},
 };
 
 use crate::{
@@ -48,15 +51,46 @@ pub(crate) enum ScaleCommand {
         scale_file_location: PathBuf,
     },
 
+    /// Use a tun file (AnaMark tuning format)
+    #[command(name = "tun-file")]
+    UseTunFile {
+        /// The location of the tun file to import
+        tun_file_location: PathBuf,
+    },
+
     /// Read a scale file from stdin in YAML format
     #[command(name = "stdin")]
     ReadStdin,
 }
 
+#[derive(Parser)]
+pub(crate) struct TunOptions {
+    /// Name of the tuning
+    #[arg(long = "name")]
+    name: Option<String>,
+
+    #[command(subcommand)]
+    scale: ScaleCommand,
+}
+
 #[derive(Parser)]
 pub(crate) struct DumpOptions {
     #[command(flatten)]
-    limit: LimitOptions,
+    approximation: ApproximationOptions,
+
+    #[command(flatten)]
+    ranking: RankingOptions,
+
+    #[command(flatten)]
+    consonance: ConsonanceOptions,
+
+    /// Stretch every octave by the given interval (e.g. 5c) keeping the root pitch fixed
+    #[arg(long = "stretch")]
+    stretch: Option<Ratio>,
+
+    /// Apply a piano-like Railsback curve of the given strength (e.g. 0.5c) around the root pitch
+    #[arg(long = "railsback")]
+    railsback: Option<Ratio>,
 
     #[command(subcommand)]
     scale: ScaleCommand,
@@ -65,7 +99,13 @@ pub(crate) struct DumpOptions {
 #[derive(Parser)]
 pub(crate) struct DiffOptions {
     #[command(flatten)]
-    limit: LimitOptions,
+    approximation: ApproximationOptions,
+
+    #[command(flatten)]
+    ranking: RankingOptions,
+
+    #[command(flatten)]
+    consonance: ConsonanceOptions,
 
     #[command(subcommand)]
     source_scale: SourceScaleCommand,
@@ -105,10 +145,108 @@ enum TargetScaleCommand {
 }
 
 #[derive(Parser)]
-struct LimitOptions {
+pub(crate) struct ApproximationOptions {
     /// Largest acceptable numerator or denominator (ignoring powers of two)
     #[arg(long = "lim", default_value = "11")]
     odd_limit: u16,
+
+    /// Only accept prime factors up to the given prime limit, e.g. 7
+    #[arg(long = "prime-lim", conflicts_with = "subgroup")]
+    prime_limit: Option<u8>,
+
+    /// Only accept products of the given subgroup elements, e.g. 2.3.7
+    #[arg(long = "subgroup", value_delimiter = '.')]
+    subgroup: Option<Vec<u16>>,
+
+    /// Use continued fraction convergents with denominators up to the given value instead of an odd limit
+    #[arg(long = "cf", conflicts_with_all = ["prime_limit", "subgroup"])]
+    max_denom: Option<u64>,
+}
+
+impl ApproximationOptions {
+    pub fn strategy(&self) -> ApproximationStrategy {
+        let odd_limit = self.odd_limit;
+        if let Some(max_denom) = self.max_denom {
+            ApproximationStrategy::ContinuedFraction { max_denom }
+        } else if let Some(prime_limit) = self.prime_limit {
+            ApproximationStrategy::PrimeLimit {
+                prime_limit,
+                odd_limit,
+            }
+        } else if let Some(elements) = &self.subgroup {
+            ApproximationStrategy::Subgroup {
+                elements: elements.clone(),
+                odd_limit,
+            }
+        } else {
+            ApproximationStrategy::OddLimit { odd_limit }
+        }
+    }
+}
+
+#[derive(Parser)]
+pub(crate) struct RankingOptions {
+    /// Order in which the approximation candidates are considered
+    #[arg(long = "rank", value_enum, default_value = "deviation")]
+    ranking: RankingArg,
+
+    /// Display the first ranked candidate within the given tolerance (e.g. 5c).
+    /// If no candidate is within the tolerance, the closest candidate is displayed.
+    #[arg(long = "tol", default_value = "10c")]
+    tolerance: Ratio,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum RankingArg {
+    /// Smallest deviation first
+    #[value(name = "deviation")]
+    Deviation,
+    /// Smallest Tenney height first
+    #[value(name = "tenney")]
+    TenneyHeight,
+    /// Smallest Weil height first
+    #[value(name = "weil")]
+    WeilHeight,
+    /// Candidates that are closer than any simpler candidate, simplest first
+    #[value(name = "complexity")]
+    ComplexityVsError,
+}
+
+impl RankingOptions {
+    fn ranking(&self) -> ApproximationRanking {
+        match self.ranking {
+            RankingArg::Deviation => ApproximationRanking::Deviation,
+            RankingArg::TenneyHeight => ApproximationRanking::TenneyHeight,
+            RankingArg::WeilHeight => ApproximationRanking::WeilHeight,
+            RankingArg::ComplexityVsError => ApproximationRanking::ComplexityVsError,
+        }
+    }
+}
+
+#[derive(Parser)]
+pub(crate) struct ConsonanceOptions {
+    /// Report consonance metrics (Tenney height, Euler's gradus suavitatis and harmonic entropy)
+    #[arg(long = "consonance")]
+    enabled: bool,
+
+    /// Spread (standard deviation) of the harmonic entropy model
+    #[arg(long = "he-spread", default_value = "17c")]
+    spread: Ratio,
+
+    /// Largest numerator*denominator product taken into account by the harmonic entropy model
+    #[arg(long = "he-lim", default_value = "10000")]
+    n_limit: u64,
+}
+
+impl ConsonanceOptions {
+    pub fn harmonic_entropy(&self) -> CliResult<Option<HarmonicEntropy>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        HarmonicEntropy::new(self.spread, self.n_limit)
+            .map(Some)
+            .ok_or_else(|| "Harmonic entropy spread must not be zero".to_owned().into())
+    }
 }
 
 pub(crate) struct Scale {
@@ -142,7 +280,7 @@ impl Scale {
         })
     }
 
-    fn from_scale_file(scale_file_location: &Path) -> CliResult<Self> {
+    pub fn from_scale_file(scale_file_location: &Path) -> CliResult<Self> {
         let file = File::open(scale_file_location)
             .handle_error::<CliError>("Could not read scale file")?;
         let scale_dto = ScaleDto::read(file)?;
@@ -153,6 +291,18 @@ impl Scale {
         })
     }
 
+    fn from_tun_file(tun_file_location: &Path) -> CliResult<Self> {
+        let tun = import_tun_file(tun_file_location)?;
+        Ok(Scale {
+            origin: tun
+                .scl_and_kbm()
+                .map(|(_, kbm)| kbm.kbm_root().ref_key)
+                .unwrap_or(PianoKey::from_midi_number(60)),
+            keys: tun.defined_keys().collect(),
+            tuning: Box::new(tun),
+        })
+    }
+
     fn from_stdin(app: &mut App) -> CliResult<Self> {
         let scale_dto = ScaleDto::read(app.read())?;
         Ok(Scale {
@@ -174,10 +324,32 @@ impl ScaleCommand {
             ScaleCommand::UseScaleFile {
                 scale_file_location,
             } => Scale::from_scale_file(scale_file_location),
+            ScaleCommand::UseTunFile { tun_file_location } => {
+                Scale::from_tun_file(tun_file_location)
+            }
             ScaleCommand::ReadStdin => Scale::from_stdin(app),
         }
     }
 
+    fn to_tun(&self, app: &mut App) -> CliResult<Tun> {
+        Ok(match self {
+            ScaleCommand::WithRefNote { kbm, scl } => {
+                Tun::from_scl_and_kbm(&scl.to_scl(None)?, &kbm.to_kbm()?)
+            }
+            ScaleCommand::UseKbmFile {
+                kbm_file_location,
+                scl,
+            } => Tun::from_scl_and_kbm(
+                &scl.to_scl(None)?,
+                &scala::import_kbm_file(kbm_file_location)?,
+            ),
+            ScaleCommand::UseTunFile { tun_file_location } => import_tun_file(tun_file_location)?,
+            ScaleCommand::UseScaleFile { .. } | ScaleCommand::ReadStdin => {
+                Tun::from_keyboard_mapping(&*self.to_scale(app)?.tuning)
+            }
+        })
+    }
+
     pub fn run(&self, app: &mut App) -> CliResult {
         let scale = self.to_scale(app)?;
 
@@ -208,15 +380,40 @@ impl ScaleCommand {
     }
 }
 
+impl TunOptions {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        let mut tun = self.scale.to_tun(app)?;
+        if let Some(name) = &self.name {
+            tun.set_name(name);
+        }
+        Ok(app.write(tun.export())?)
+    }
+}
+
 impl DumpOptions {
     pub fn run(&self, app: &mut App) -> CliResult {
-        let scale = self.scale.to_scale(app)?;
+        let mut scale = self.scale.to_scale(app)?;
+
+        if let Some(root_pitch) = scale.tuning.maybe_pitch_of(scale.origin) {
+            if let Some(stretch) = self.stretch {
+                scale.tuning = Box::new(Stretched::new(scale.tuning, root_pitch, stretch));
+            }
+            if let Some(railsback) = self.railsback {
+                scale.tuning = Box::new(
+                    Railsback::new(scale.tuning, root_pitch, railsback)
+                        .ok_or_else(|| "Railsback strength must not be negative".to_owned())?,
+                );
+            }
+        }
 
         let mut printer = ScaleTablePrinter {
             app,
             root_key: scale.origin,
             root_pitch: scale.tuning.maybe_pitch_of(scale.origin),
-            odd_limit: self.limit.odd_limit,
+            strategy: self.approximation.strategy(),
+            ranking: self.ranking.ranking(),
+            tolerance: self.ranking.tolerance,
+            harmonic_entropy: self.consonance.harmonic_entropy()?,
         };
 
         printer.print_table_header()?;
@@ -249,7 +446,10 @@ impl DiffOptions {
             app,
             root_pitch: source_scale.tuning.maybe_pitch_of(source_scale.origin),
             root_key: source_scale.origin,
-            odd_limit: self.limit.odd_limit,
+            strategy: self.approximation.strategy(),
+            ranking: self.ranking.ranking(),
+            tolerance: self.ranking.tolerance,
+            harmonic_entropy: self.consonance.harmonic_entropy()?,
         };
 
         printer.print_table_header()?;
@@ -302,14 +502,27 @@ struct ScaleTablePrinter<'a, 'b> {
     app: &'a mut App<'b>,
     root_key: PianoKey,
     root_pitch: Option<Pitch>,
-    odd_limit: u16,
+    strategy: ApproximationStrategy,
+    ranking: ApproximationRanking,
+    tolerance: Ratio,
+    harmonic_entropy: Option<HarmonicEntropy>,
 }
 
 impl ScaleTablePrinter<'_, '_> {
     fn print_table_header(&mut self) -> io::Result<()> {
-        self.app.writeln(format_args!(
-            "  {source:-^33} ‖ {pitch:-^14} ‖ {target:-^28}",
+        self.app.write(format_args!(
+            "  {source:-^width$} ‖ ",
             source = "Source Scale",
+            width = 29 + 2 * self.fraction_width(),
+        ))?;
+        if self.harmonic_entropy.is_some() {
+            self.app.write(format_args!(
+                "{consonance:-^28} ‖ ",
+                consonance = "Consonance"
+            ))?;
+        }
+        self.app.writeln(format_args!(
+            "{pitch:-^14} ‖ {target:-^28}",
             pitch = "Pitch",
             target = "Target Scale"
         ))
@@ -330,23 +543,77 @@ impl ScaleTablePrinter<'_, '_> {
             self.app.write(format_args!("  "))?;
         }
 
-        let nearest_fraction = Ratio::between_pitches(self.root_pitch.unwrap_or(pitch), pitch)
-            .nearest_fraction(self.odd_limit);
-
-        self.app.writeln(format_args!(
+        let ratio = Ratio::between_pitches(self.root_pitch.unwrap_or(pitch), pitch);
+        let fract_octaves = ratio.as_octaves().floor() as i32;
+        let reduced_ratio = ratio.deviation_from(Ratio::from_octaves(fract_octaves));
+        let approximation = self
+            .strategy
+            .approximate(reduced_ratio, self.ranking)
+            .into_iter()
+            .find(|candidate| candidate.deviation.abs() <= self.tolerance.abs())
+            .or_else(|| self.strategy.best_approximation(reduced_ratio))
+            .unwrap_or(Approximation {
+                approx_value: Fraction::new(1, 1),
+                deviation: reduced_ratio,
+            });
+
+        self.app.write(format_args!(
             "{source_midi:>3} | IDX {source_index:>4} | \
-             {numer:>2}/{denom:<2} {fract_deviation:>+4.0}¢ {fract_octaves:>+3}o ‖ \
-             {pitch:>11.3} Hz ‖ {target_midi:>4} | {target_index} | {deviation:>+8.3}¢",
+             {numer:>width$}/{denom:<width$} {fract_deviation:>+4.0}¢ {fract_octaves:>+3}o ‖ ",
             source_midi = source_key.midi_number(),
             source_index = source_index,
+            numer = approximation.approx_value.numer,
+            denom = approximation.approx_value.denom,
+            width = self.fraction_width(),
+            fract_deviation = approximation.deviation.as_cents(),
+            fract_octaves = fract_octaves,
+        ))?;
+
+        if let Some(harmonic_entropy) = &self.harmonic_entropy {
+            self.app.write(format_args!(
+                "TH {tenney:>5.2} | EG {gradus:>3} | HE {entropy:.3} ‖ ",
+                tenney = approximation.approx_value.tenney_height(),
+                gradus = consonance::euler_gradus(approximation.approx_value)
+                    .map_or_else(|| "-".to_owned(), |gradus| gradus.to_string()),
+                entropy = harmonic_entropy.of_ratio(reduced_ratio),
+            ))?;
+        }
+
+        self.app.writeln(format_args!(
+            "{pitch:>11.3} Hz ‖ {target_midi:>4} | {target_index} | {deviation:>+8.3}¢",
             pitch = pitch.as_hz(),
-            numer = nearest_fraction.numer,
-            denom = nearest_fraction.denom,
-            fract_deviation = nearest_fraction.deviation.as_cents(),
-            fract_octaves = nearest_fraction.num_octaves,
             target_midi = target_midi,
             target_index = target_index,
             deviation = deviation.as_cents(),
         ))
     }
+
+    /// Width of the numerator and denominator columns, large enough for all octave-reduced candidates of the strategy.
+    fn fraction_width(&self) -> usize {
+        let max_denom = match self.strategy {
+            ApproximationStrategy::OddLimit { odd_limit }
+            | ApproximationStrategy::PrimeLimit { odd_limit, .. }
+            | ApproximationStrategy::Subgroup { odd_limit, .. } => u64::from(odd_limit),
+            ApproximationStrategy::ContinuedFraction { max_denom } => max_denom,
+        };
+        // Octave-reduced numerators are less than twice the denominator
+        (2 * max_denom.max(1) - 1).to_string().len().max(2)
+    }
+}
+
+fn import_tun_file(file_name: &Path) -> Result<Tun, String> {
+    File::open(file_name)
+        .map_err(TunImportError::IoError)
+        .and_then(Tun::import)
+        .map_err(|err| match err {
+            TunImportError::IoError(err) => {
+                format!("Could not read tun file {file_name:#?}: {err}")
+            }
+            TunImportError::ParseError { line_number, kind } => {
+                format!("Could not parse tun file {file_name:#?} at line {line_number}: {kind:#?}")
+            }
+            TunImportError::StructuralError(err) => {
+                format!("Malformed tun file {file_name:#?}: {err:#?}")
+            }
+        })
 }
@@ -3,6 +3,9 @@
 pub mod error {
     pub use crate::error::*;
 }
+pub mod est {
+    pub use crate::est::import_genchains;
+}
 pub mod midi {
     pub use crate::midi::*;
 }
@@ -0,0 +1,179 @@
+use std::fmt::Write;
+
+use clap::Parser;
+use tune::{
+    math,
+    temperament::{Comma, RegularTemperament, TuningOptimization, Val},
+};
+
+use crate::{scala::parse_comma, App, CliError, CliResult};
+
+#[derive(Parser)]
+pub(crate) enum TemperamentCommand {
+    /// Temperament supported by the given vals
+    #[command(name = "vals")]
+    Vals(ValsOptions),
+
+    /// Temperament tempering out the given commas
+    #[command(name = "commas")]
+    Commas(CommasOptions),
+}
+
+#[derive(Parser)]
+pub(crate) struct ValsOptions {
+    /// Prime limit of vals given in wart notation
+    #[arg(long = "lim", default_value = "5")]
+    prime_limit: u8,
+
+    /// Vals in wart notation, e.g. 12 19 17c, or ket notation, e.g. "<12 19 28]"
+    #[arg(required = true)]
+    vals: Vec<String>,
+}
+
+#[derive(Parser)]
+pub(crate) struct CommasOptions {
+    /// Prime limit of the temperament. Defaults to the highest prime of the commas
+    #[arg(long = "lim")]
+    prime_limit: Option<u8>,
+
+    /// Commas to temper out, e.g. "syntonic comma", 81/80 or "[-4 4 -1>"
+    #[arg(required = true, value_parser = parse_comma)]
+    commas: Vec<Comma>,
+}
+
+impl TemperamentCommand {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        let temperament = match self {
+            TemperamentCommand::Vals(options) => {
+                let vals = options
+                    .vals
+                    .iter()
+                    .map(|val| match val.trim_start().starts_with('<') {
+                        true => val.parse(),
+                        false => Val::from_wart_notation(val, options.prime_limit),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                RegularTemperament::from_vals(&vals)
+            }
+            TemperamentCommand::Commas(options) => {
+                let prime_limit = options.prime_limit.unwrap_or_else(|| {
+                    options
+                        .commas
+                        .iter()
+                        .map(Comma::prime_limit)
+                        .max()
+                        .unwrap_or_default()
+                        .max(2)
+                });
+                RegularTemperament::from_commas(&options.commas, prime_limit)
+            }
+        };
+
+        match temperament {
+            Some(temperament) => print_temperament(app, &temperament),
+            None => Err(CliError::CommandError(
+                "Invalid prime limit, vals of different prime limits, unison commas, commas exceeding the prime limit or numeric overflow"
+                    .to_owned(),
+            )),
+        }
+    }
+}
+
+fn print_temperament(app: &mut App, temperament: &RegularTemperament) -> CliResult {
+    let rank = temperament.rank();
+
+    app.writeln(format_args!(
+        "==== Rank-{rank} temperament ({}-limit) ====",
+        temperament.prime_limit()
+    ))?;
+    app.writeln("")?;
+
+    let mut mapping = String::new();
+    for row in temperament.mapping() {
+        write!(mapping, " <{}]", join(row, " ")).unwrap();
+    }
+    app.writeln(format_args!("- mapping:{mapping}"))?;
+    match temperament.wedgie() {
+        Some(wedgie) => app.writeln(format_args!(
+            "- wedgie: {}{}{}",
+            "<".repeat(rank),
+            join(&wedgie, " "),
+            "]".repeat(rank)
+        ))?,
+        None => app.writeln("- wedgie: out of range")?,
+    }
+    for comma in temperament.comma_basis() {
+        let fraction = comma
+            .iter()
+            .map(|&power| i8::try_from(power).ok())
+            .collect::<Option<Vec<_>>>()
+            .and_then(|prime_factors| Comma::new("", prime_factors).as_fraction());
+        app.write(format_args!("- tempers out [{}>", join(comma, " ")))?;
+        match fraction {
+            Some((numer, denom)) => app.writeln(format_args!(" ({numer}/{denom})"))?,
+            None => app.writeln("")?,
+        }
+    }
+    app.writeln(format_args!(
+        "- TE complexity: {:.3}",
+        temperament.te_complexity()
+    ))?;
+    if let Some(te_error) = temperament.te_error() {
+        app.writeln(format_args!("- TE error: {te_error:.3}c/octave"))?;
+    }
+    if let Some(te_simple_badness) = temperament.te_simple_badness() {
+        app.writeln(format_args!(
+            "- TE simple badness: {:.3}‰",
+            te_simple_badness * 1000.0
+        ))?;
+    }
+
+    for (name, optimization) in [
+        ("TOP", TuningOptimization::Top),
+        ("TE", TuningOptimization::Te),
+        ("CTE", TuningOptimization::Cte),
+        ("POTE", TuningOptimization::Pote),
+    ] {
+        app.writeln("")?;
+        app.writeln(format_args!("---- {name} tuning ----"))?;
+        app.writeln("")?;
+
+        let Some(tuning) = temperament.optimal_tuning(optimization) else {
+            app.writeln("- no solution")?;
+            continue;
+        };
+        app.writeln(format_args!(
+            "- generators: [{}]",
+            join_formatted(
+                tuning
+                    .generators
+                    .iter()
+                    .map(|g| format!("{:.3}c", g.as_cents()))
+            )
+        ))?;
+        app.writeln(format_args!(
+            "- tuning map ({}): [{}]",
+            join(&math::U8_PRIMES[..tuning.tuning_map.len()], "."),
+            join_formatted(
+                tuning
+                    .tuning_map
+                    .iter()
+                    .map(|prime| format!("{:.3}c", prime.as_cents()))
+            )
+        ))?;
+        app.writeln(format_args!(
+            "- errors: [{}]",
+            join_formatted(tuning.errors().map(|error| format!("{error:#.3}")))
+        ))?;
+    }
+
+    Ok(())
+}
+
+fn join(values: &[impl ToString], separator: &str) -> String {
+    Vec::from_iter(values.iter().map(ToString::to_string)).join(separator)
+}
+
+fn join_formatted(formatted: impl Iterator<Item = String>) -> String {
+    Vec::from_iter(formatted).join(", ")
+}
@@ -65,6 +65,90 @@ fn dump_7_edo_with_root() {
     check_output!("snapshots/dump_7_edo_with_root.stdout", output.stdout);
 }
 
+#[test]
+fn dump_12_edo_with_stretch_and_railsback() {
+    let output = call_cli(&[
+        "dump",
+        "--stretch",
+        "5c",
+        "--railsback",
+        "0.5c",
+        "ref-note",
+        "69",
+        "--lo-key",
+        "21",
+        "--up-key",
+        "109",
+        "steps",
+        "1:12:2",
+    ]);
+    check_output!(
+        "snapshots/dump_12_edo_with_stretch_and_railsback.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_7_edo_with_subgroup() {
+    let output = call_cli(&[
+        "dump",
+        "--subgroup",
+        "2.3.7",
+        "--lim",
+        "27",
+        "ref-note",
+        "62",
+        "--lo-key",
+        "61",
+        "--up-key",
+        "71",
+        "steps",
+        "1:7:2",
+    ]);
+    check_output!("snapshots/dump_7_edo_with_subgroup.stdout", output.stdout);
+}
+
+#[test]
+fn dump_7_edo_with_convergents() {
+    let output = call_cli(&[
+        "dump", "--cf", "100", "ref-note", "62", "--lo-key", "61", "--up-key", "71", "steps",
+        "1:7:2",
+    ]);
+    check_output!(
+        "snapshots/dump_7_edo_with_convergents.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_19_edo_ranked_by_tenney_height() {
+    let output = call_cli(&[
+        "dump", "--rank", "tenney", "--tol", "15c", "ref-note", "62", "--lo-key", "61", "--up-key",
+        "71", "steps", "1:19:2",
+    ]);
+    check_output!(
+        "snapshots/dump_19_edo_ranked_by_tenney_height.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_7_edo_with_consonance() {
+    let output = call_cli(&[
+        "dump",
+        "--consonance",
+        "ref-note",
+        "62",
+        "--lo-key",
+        "61",
+        "--up-key",
+        "71",
+        "steps",
+        "1:7:2",
+    ]);
+    check_output!("snapshots/dump_7_edo_with_consonance.stdout", output.stdout);
+}
+
 #[test]
 fn diff_quarter_comma_and_31_edo() {
     let output = call_cli_piped(
@@ -99,6 +183,69 @@ fn mts_of_7_edo() {
     check_output!("snapshots/README_mts_of_7_edo.stderr", output.stderr);
 }
 
+#[test]
+fn decode_mts_of_7_edo() {
+    let output = call_cli_piped(
+        &[
+            "mts", "full", "--tun-pg", "5", "ref-note", "62", "--lo-key", "60", "--up-key", "66",
+            "steps", "1:7:2",
+        ],
+        &["mts", "decode"],
+    );
+    check_output!("snapshots/decode_mts_of_7_edo.stdout", output.stdout);
+}
+
+#[test]
+fn decode_octave_tuning_of_19_edo() {
+    let output = call_cli_piped(
+        &[
+            "mts",
+            "octave-2-rt",
+            "--lo-chan",
+            "3",
+            "ref-note",
+            "69",
+            "steps",
+            "1:19:2",
+        ],
+        &["mts", "decode"],
+    );
+    check_output!(
+        "snapshots/decode_octave_tuning_of_19_edo.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn decode_bulk_tuning_dump_of_7_edo() {
+    let output = call_cli_piped(
+        &[
+            "mts", "bulk", "--tun-pg", "5", "--name", "7-EDO", "ref-note", "62", "--lo-key", "60",
+            "--up-key", "66", "steps", "1:7:2",
+        ],
+        &["mts", "decode"],
+    );
+    check_output!(
+        "snapshots/decode_bulk_tuning_dump_of_7_edo.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn decode_key_based_tuning_dump_of_7_edo() {
+    let output = call_cli_piped(
+        &[
+            "mts", "key-dump", "--tun-bk", "3", "--tun-pg", "5", "--name", "7-EDO", "ref-note",
+            "62", "--lo-key", "60", "--up-key", "66", "steps", "1:7:2",
+        ],
+        &["mts", "decode"],
+    );
+    check_output!(
+        "snapshots/decode_key_based_tuning_dump_of_7_edo.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn mts_of_19_edo() {
     let output = call_cli(&["mts", "full-rt", "ref-note", "69", "steps", "1:19:2"]);
@@ -162,6 +309,111 @@ fn analysis_of_7_edo() {
     check_output!("snapshots/analysis_of_7_edo.stdout", output.stdout);
 }
 
+#[test]
+fn analysis_of_7_edo_with_comma_catalog() {
+    let catalog_location = env::temp_dir().join("tune-cli-comma-catalog.csv");
+    fs::write(
+        &catalog_location,
+        "ratio,names\n\
+         # Custom interval names\n\
+         81/80,meantone comma\n\
+         [-11 7>,\"Pythagorean chroma, apotome\"\n\
+         # Names of built-in intervals can be reused\n\
+         [127 1>,perfect fifth\n",
+    )
+    .unwrap();
+
+    let output = call_cli(&[
+        "est",
+        "1:7:2",
+        "--comma-catalog",
+        catalog_location.to_str().unwrap(),
+    ]);
+    check_output!(
+        "snapshots/analysis_of_7_edo_with_comma_catalog.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn analysis_of_7_edo_with_yaml_comma_catalog() {
+    let catalog_location = env::temp_dir().join("tune-cli-comma-catalog.yml");
+    fs::write(
+        &catalog_location,
+        "# Custom interval names\n\
+         - ratio: 81/80 # Syntonic comma\n  \
+           names: meantone comma\n\
+         - {ratio: \"[-11 7>\", names: \"Pythagorean chroma, apotome\"}\n\
+         - ratio: \"[127 1>\"\n  \
+           names: >-\n    \
+             perfect fifth\n",
+    )
+    .unwrap();
+
+    let output = call_cli(&[
+        "est",
+        "1:7:2",
+        "--comma-catalog",
+        catalog_location.to_str().unwrap(),
+    ]);
+    check_output!(
+        "snapshots/analysis_of_7_edo_with_comma_catalog.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn analysis_of_22_edo_with_genchains() {
+    let genchains_location = env::temp_dir().join("tune-cli-genchains.yml");
+    fs::write(
+        &genchains_location,
+        "- name: Orwell\n\
+         \x20 generator: {coefficients: [0, 1], divisor: 7}\n\
+         \x20 note_names: BDFHACEGJ\n\
+         \x20 genchain_origin: 4\n\
+         \x20 scale_names: [gramitonic, semiquartal]\n",
+    )
+    .unwrap();
+
+    let output = call_cli(&[
+        "est",
+        "1:22:2",
+        "--genchains",
+        genchains_location.to_str().unwrap(),
+    ]);
+    check_output!(
+        "snapshots/analysis_of_22_edo_with_genchains.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn analysis_of_7_edo_with_consonance() {
+    let output = call_cli(&["est", "1:7:2", "--consonance"]);
+    check_output!(
+        "snapshots/analysis_of_7_edo_with_consonance.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn analysis_of_24_edo_with_ups_and_downs() {
+    let output = call_cli(&["est", "1:24:2", "--notation", "ups-downs"]);
+    check_output!(
+        "snapshots/analysis_of_24_edo_with_ups_and_downs.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn analysis_of_22_edo_with_heji() {
+    let output = call_cli(&["est", "1:22:2", "--notation", "heji", "--limit", "11"]);
+    check_output!(
+        "snapshots/analysis_of_22_edo_with_heji.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn analysis_of_13_edo() {
     let output = call_cli(&["est", "1:13:2"]);
@@ -189,6 +441,48 @@ fn analysis_of_19_edo() {
     check_output!("snapshots/README_analysis_of_19_edo.stdout", output.stdout);
 }
 
+#[test]
+fn search_vals_for_2_3_7_11_subgroup() {
+    let output = call_cli(&["est", "search", "--subgroup", "2.3.7.11", "--num", "15"]);
+    check_output!(
+        "snapshots/search_vals_for_2_3_7_11_subgroup.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn search_vals_for_fractional_subgroup() {
+    let output = call_cli(&[
+        "est",
+        "search",
+        "--subgroup",
+        "2.5/3.7/3",
+        "--max",
+        "100",
+        "--num",
+        "8",
+    ]);
+    check_output!(
+        "snapshots/search_vals_for_fractional_subgroup.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn search_vals_rejects_non_expanding_equaves() {
+    let output = call_cli(&["est", "search", "--equave", "1", "--max", "12"]);
+    check_output!(
+        "snapshots/search_vals_with_unison_equave.stderr",
+        output.stderr
+    );
+
+    let output = call_cli(&["est", "search", "--equave", "0.5", "--max", "12"]);
+    check_output!(
+        "snapshots/search_vals_with_contracting_equave.stderr",
+        output.stderr
+    );
+}
+
 #[test]
 fn moses_from_700_cents_generator() {
     let output = call_cli(&["mos", "find", "700c"]);
@@ -243,6 +537,30 @@ fn generators_for_6l4s_edt() {
     check_output!("snapshots/generators_for_6l4s.stdout", output.stdout);
 }
 
+#[test]
+fn modes_of_soft_5l2s() {
+    let output = call_cli(&["mos", "modes", "--ratio", "3:2", "5", "2"]);
+    check_output!("snapshots/modes_of_soft_5l2s.stdout", output.stdout);
+}
+
+#[test]
+fn modes_of_4l4s() {
+    let output = call_cli(&["mos", "modes", "4", "4"]);
+    check_output!("snapshots/modes_of_4l4s.stdout", output.stdout);
+}
+
+#[test]
+fn temperament_from_vals() {
+    let output = call_cli(&["temperament", "vals", "--lim", "7", "12", "19"]);
+    check_output!("snapshots/temperament_from_vals.stdout", output.stdout);
+}
+
+#[test]
+fn temperament_from_commas() {
+    let output = call_cli(&["temperament", "commas", "225/224", "385/384"]);
+    check_output!("snapshots/temperament_from_commas.stdout", output.stdout);
+}
+
 #[test]
 fn create_scl() {
     let output = call_cli(&[
@@ -261,6 +579,80 @@ fn create_scl() {
     check_output!("snapshots/README_create_scl.stdout", output.stdout);
 }
 
+#[test]
+fn create_scl_of_mos_mode() {
+    let output = call_cli(&["scl", "mos", "--mode", "2", "--ratio", "3:1", "5", "2"]);
+    check_output!("snapshots/create_scl_of_mos_mode.stdout", output.stdout);
+}
+
+#[test]
+fn create_scl_of_empty_mos() {
+    let output = call_cli(&["scl", "mos", "0", "0"]);
+    check_output!("snapshots/create_scl_of_empty_mos.stderr", output.stderr);
+}
+
+#[test]
+fn create_ascl_of_5_edo() {
+    let output = call_cli(&[
+        "scl",
+        "--ascl",
+        "--ascl-ref",
+        "D4",
+        "--ascl-source",
+        "Equal temperaments",
+        "steps",
+        "240c",
+        "480c",
+        "720c",
+        "960c",
+        "2",
+    ]);
+    check_output!("snapshots/create_ascl_of_5_edo.stdout", output.stdout);
+}
+
+#[test]
+fn transform_major_scale() {
+    let output = call_cli(&[
+        "scl",
+        "transform",
+        "--op",
+        "mode:5",
+        "--op",
+        "invert",
+        "--op",
+        "transpose:81/80",
+        "--op",
+        "subset:0,1,2,4,5,6",
+        "steps",
+        "9/8",
+        "5/4",
+        "4/3",
+        "3/2",
+        "5/3",
+        "15/8",
+        "2",
+    ]);
+    check_output!("snapshots/transform_major_scale.stdout", output.stdout);
+}
+
+#[test]
+fn analyze_pythagorean_diatonic_scale() {
+    let output = call_cli(&["analyze", "--prime-lim", "5", "rank2", "3/2", "5", "1"]);
+    check_output!(
+        "snapshots/analyze_pythagorean_diatonic_scale.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn analyze_harmonic_segment_as_yaml() {
+    let output = call_cli(&["analyze", "--format", "yaml", "harm", "4"]);
+    check_output!(
+        "snapshots/analyze_harmonic_segment_as_yaml.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn create_harmonics_scale() {
     let output = call_cli(&["scl", "harm", "-u", "37", "74", "--neji=13"]);
@@ -270,6 +662,39 @@ fn create_harmonics_scale() {
     );
 }
 
+#[test]
+fn create_eikosany() {
+    let output = call_cli(&["scl", "cps", "3", "1.3.5.7.9.11"]);
+    check_output!("snapshots/create_eikosany.stdout", output.stdout);
+}
+
+#[test]
+fn create_cross_set_scale() {
+    let output = call_cli(&["scl", "cross", "1.5/4.3/2", "1.6/5.3/2"]);
+    check_output!("snapshots/create_cross_set_scale.stdout", output.stdout);
+}
+
+#[test]
+fn create_fokker_block() {
+    let output = call_cli(&[
+        "scl",
+        "fokker",
+        "--comma",
+        "syntonic comma",
+        "--comma",
+        "[-10 -1 5>",
+        "--shift",
+        "-0.5,-0.5",
+    ]);
+    check_output!("snapshots/create_fokker_block.stdout", output.stdout);
+}
+
+#[test]
+fn create_named_scale() {
+    let output = call_cli(&["scl", "named", "quarter-comma meantone"]);
+    check_output!("snapshots/create_named_scale.stdout", output.stdout);
+}
+
 #[test]
 fn create_kbm_root() {
     let output = call_cli(&["kbm", "ref-note", "62"]);
@@ -295,3 +720,54 @@ fn crate_kbm() {
     ]);
     check_output!("snapshots/README_create_kbm.stdout", output.stdout);
 }
+
+#[test]
+fn create_kbm_from_note_names() {
+    let output = call_cli(&[
+        "kbm",
+        "ref-note",
+        "D4",
+        "--root",
+        "C4",
+        "--lo-key",
+        "10",
+        "--up-key",
+        "100",
+        "--key-map",
+        "0,x,1,x,2,3,x,4,x,5,x,6",
+        "--octave",
+        "7",
+    ]);
+    check_output!("snapshots/README_create_kbm.stdout", output.stdout);
+}
+
+#[test]
+fn create_tun_of_7_edo() {
+    let output = call_cli(&[
+        "tun", "ref-note", "62", "--lo-key", "61", "--up-key", "71", "steps", "1:7:2",
+    ]);
+    check_output!("snapshots/create_tun_of_7_edo.stdout", output.stdout);
+}
+
+#[test]
+fn dump_tun_file() {
+    let tun_file_location = env::temp_dir().join("tune-cli-dump-tun-file.tun");
+    let tun_file_location = tun_file_location.to_str().unwrap();
+
+    call_cli(&[
+        "--of",
+        tun_file_location,
+        "tun",
+        "ref-note",
+        "62",
+        "--lo-key",
+        "61",
+        "--up-key",
+        "71",
+        "steps",
+        "1:7:2",
+    ]);
+
+    let output = call_cli(&["dump", "tun-file", tun_file_location]);
+    check_output!("snapshots/dump_tun_file.stdout", output.stdout);
+}